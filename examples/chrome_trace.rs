@@ -0,0 +1,22 @@
+use std::io;
+use std::process::exit;
+
+use legion_prof_viewer::export::chrome_trace::ChromeTraceWriter;
+use legion_prof_viewer::file_data::FileDataSource;
+use legion_prof_viewer::parallel_data::ParallelDeferredDataSource;
+
+// Converts a profile archive into a Chrome trace (JSON) file that can be
+// viewed in Perfetto.
+//
+// Usage: cargo run --release --example chrome_trace -- <archive> <output.json>
+fn main() -> io::Result<()> {
+    let args: Vec<_> = std::env::args_os().skip(1).collect();
+    let [input, output] = &args[..] else {
+        eprintln!("usage: chrome_trace <archive directory> <output.json>");
+        exit(1);
+    };
+
+    let data_source = ParallelDeferredDataSource::new(FileDataSource::new(input));
+    println!("Exporting to {:?}", output);
+    ChromeTraceWriter::new(data_source, output, false).write()
+}
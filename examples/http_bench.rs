@@ -0,0 +1,81 @@
+use std::process::exit;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use legion_prof_viewer::deferred_data::{CountingDeferredDataSource, DeferredDataSource};
+use legion_prof_viewer::http::client::{HTTPClientConfig, HTTPClientDataSource};
+use legion_prof_viewer::http::schema::TileRequest;
+
+// Measures how long it takes to fetch the slot tiles covering an entire
+// profile from a server, first with one request per tile and then batched.
+//
+// Usage: cargo run --release --features client --example http_bench -- <url> [<max idle connections>]
+fn main() {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    let (url, max_idle) = match &args[..] {
+        [url] => (url, None),
+        [url, max_idle] => (url, Some(max_idle)),
+        _ => {
+            eprintln!("usage: http_bench <url> [<max idle connections>]");
+            exit(1);
+        }
+    };
+    let url = Url::parse(url).expect("unable to parse URL");
+    let config = HTTPClientConfig {
+        pool_max_idle_per_host: max_idle.map(|n| n.parse().expect("invalid number")),
+        ..Default::default()
+    };
+
+    let mut data_source =
+        CountingDeferredDataSource::new(HTTPClientDataSource::with_config(url, &config));
+
+    data_source.fetch_info();
+    let info = loop {
+        // We requested this once, so we know we'll get zero or one result
+        if let Some(info) = data_source.get_infos().pop() {
+            break info.expect("unable to fetch profile info");
+        }
+        sleep(Duration::from_millis(1));
+    };
+
+    let tile_ids = info.tile_set.covering_tiles(info.interval);
+    let requests: Vec<_> = info
+        .entry_info
+        .slot_ids()
+        .into_iter()
+        .flat_map(|entry_id| {
+            tile_ids.iter().map(move |tile_id| TileRequest {
+                entry_id: entry_id.clone(),
+                tile_id: *tile_id,
+            })
+        })
+        .collect();
+    println!("Fetching {} tiles", requests.len());
+
+    let start = Instant::now();
+    for req in &requests {
+        data_source.fetch_slot_tile(&req.entry_id, req.tile_id, false);
+    }
+    let errors = wait(&mut data_source);
+    println!("  one at a time: {:?} ({} errors)", start.elapsed(), errors);
+
+    let start = Instant::now();
+    data_source.fetch_slot_tiles(&requests, false);
+    let errors = wait(&mut data_source);
+    println!("  batched:       {:?} ({} errors)", start.elapsed(), errors);
+}
+
+// Waits for all outstanding requests and returns the number that failed
+fn wait(data_source: &mut CountingDeferredDataSource<HTTPClientDataSource>) -> usize {
+    let mut errors = 0;
+    while data_source.outstanding_requests() > 0 {
+        let tiles = data_source.get_slot_tiles();
+        errors += tiles.iter().filter(|(tile, _)| tile.is_err()).count();
+        if tiles.is_empty() {
+            sleep(Duration::from_millis(1));
+        }
+    }
+    errors
+}
@@ -1,11 +1,23 @@
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+#[cfg(not(target_arch = "wasm32"))]
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{btree_map, BTreeMap, BTreeSet, VecDeque};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
 
 use egui::{
-    Align2, Color32, NumExt, Pos2, Rect, RichText, ScrollArea, Slider, Stroke, TextStyle, Vec2,
+    Align2, Color32, NumExt, Pos2, Rect, Rgba, RichText, ScrollArea, Slider, Stroke, TextStyle,
+    Vec2,
 };
 use egui_extras::{Column, TableBuilder};
 #[cfg(not(target_arch = "wasm32"))]
@@ -15,12 +27,21 @@ use regex::{escape, Regex};
 use serde::{Deserialize, Serialize};
 
 use crate::data::{
-    DataSourceInfo, EntryID, EntryIndex, EntryInfo, Field, FieldID, FieldSchema, ItemLink,
-    ItemMeta, ItemUID, SlotMetaTileData, SlotTileData, SummaryTileData, TileID, TileSet, UtilPoint,
+    DataSourceError, DataSourceInfo, EntryID, EntryIndex, EntryInfo, Field, FieldID, FieldSchema,
+    Item, ItemLink, ItemMeta, ItemUID, Result, SlotMetaTileData, SlotTileData, SummaryTileData,
+    TileID, TileSet, UtilPoint,
+};
+use crate::deferred_data::{
+    CountingDeferredDataSource, DeferredDataSource, SharedDeferredDataSource,
 };
-use crate::deferred_data::{CountingDeferredDataSource, DeferredDataSource};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::export::chrome_trace::ChromeTraceWriter;
+use crate::http::schema::TileRequest;
+use crate::key_bindings::{KeyAction, KeyBinding, KeyBindings};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::svg::SvgWriter;
 use crate::timestamp::{
-    Interval, Timestamp, TimestampDisplay, TimestampParseError, TimestampUnits,
+    DisplayUnit, Interval, IntervalDisplay, Timestamp, TimestampDisplay, TimestampParseError,
 };
 
 /// Overview:
@@ -57,8 +78,12 @@ use crate::timestamp::{
 struct Summary {
     entry_id: EntryID,
     color: Color32,
-    tiles: BTreeMap<TileID, Option<SummaryTileData>>,
+    tiles: BTreeMap<TileID, Option<Result<SummaryTileData>>>,
     last_view_interval: Option<Interval>,
+    // Set when the plot was hovered in the last frame
+    hovered: bool,
+    // Utilization of each child (by label), for the hover tooltip
+    breakdown: Vec<(String, Vec<UtilPoint>)>,
 }
 
 #[derive(Debug, Clone)]
@@ -69,8 +94,11 @@ struct Slot {
     expanded: bool,
     max_rows: u64,
     tile_ids: Vec<TileID>,
-    tiles: BTreeMap<TileID, Option<SlotTileData>>,
-    tile_metas: BTreeMap<TileID, Option<SlotMetaTileData>>,
+    tiles: BTreeMap<TileID, Option<Result<SlotTileData>>>,
+    tile_metas: BTreeMap<TileID, Option<Result<SlotMetaTileData>>>,
+    // Where each item in the loaded tiles is, so we don't have to scan them
+    item_index: ItemIndex,
+    meta_index: ItemIndex,
     last_view_interval: Option<Interval>,
 }
 
@@ -92,6 +120,9 @@ struct ItemLocator {
     entry_id: EntryID,
     irow: Option<usize>,
 
+    // Row hint from an ItemLink (not reversed), used when irow is unknown
+    row: Option<u64>,
+
     // If we can't find the item on the initial attempt, we track the ItemUID
     // and attempt to find it once the tile loads
     item_uid: ItemUID,
@@ -119,6 +150,16 @@ struct SearchCacheItem {
     irow: usize,
 }
 
+// Search options remembered across sessions (per profile)
+#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
+struct SearchOptions {
+    search_field: FieldID,
+    whole_word: bool,
+    include_collapsed_entries: bool,
+    #[serde(default)]
+    regex_mode: bool,
+}
+
 #[derive(Debug, Clone)]
 struct SearchState {
     title_field: FieldID,
@@ -130,15 +171,65 @@ struct SearchState {
     last_search_field: FieldID,
     whole_word: bool,
     last_whole_word: bool,
-    last_word_regex: Option<Regex>,
+    // Treat the query as a regular expression (exclusive with whole_word)
+    regex_mode: bool,
+    last_regex_mode: bool,
+    // Compiled query, for regex mode
+    last_regex: Option<Regex>,
+    regex_error: Option<String>,
+    // Otherwise, the words of the query (see parse_search_terms), and in
+    // whole word mode, the same words compiled
+    positive_terms: Vec<String>,
+    negative_terms: Vec<String>,
+    positive_words: Vec<Regex>,
+    negative_words: Vec<Regex>,
+    // Numeric fields are searched by range instead, written min..max in the
+    // query (see parse_search_range)
+    numeric_fields: BTreeSet<FieldID>,
+    range: Option<(Option<i64>, Option<i64>)>,
+    range_min: String,
+    range_max: String,
+    // The query for the other kind of field (text or numeric), put back when
+    // switching to that kind again
+    other_query: String,
+    // Only items whose duration (in ns) is in this half-open range. With no
+    // query, this alone picks the results.
+    duration_range: Option<(i64, i64)>,
+    last_duration_range: Option<(i64, i64)>,
     include_collapsed_entries: bool,
     last_include_collapsed_entries: bool,
+    restrict_to_view: bool,
+    last_restrict_to_view: bool,
+    // Solo the processor of a result when it's clicked
+    focus_result: bool,
     last_view_interval: Option<Interval>,
 
+    // Jump to an item by its UID, and the outcome of the last attempt
+    uid_query: String,
+    uid_status: Option<String>,
+
     // Cache of matching items
     result_set: BTreeSet<ItemUID>,
     result_cache: BTreeMap<EntryID, BTreeMap<TileID, BTreeMap<ItemUID, SearchCacheItem>>>,
     entry_tree: BTreeMap<u64, BTreeMap<u64, BTreeSet<u64>>>,
+
+    // Where to export results (CSV, or JSON if the path ends with .json), and
+    // the outcome of the last export
+    #[cfg(not(target_arch = "wasm32"))]
+    export_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    export_status: Option<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Serialize)]
+struct SearchResultRecord<'a> {
+    entry: &'a str,
+    title: &'a str,
+    start_ns: i64,
+    stop_ns: i64,
+    duration_ns: i64,
+    item_uid: u64,
 }
 
 struct Config {
@@ -152,17 +243,55 @@ struct Config {
     kinds: Vec<String>,
     kind_filter: BTreeSet<String>,
 
+    // Entries the user has soloed (show only these) or muted (hide these)
+    visibility_overrides: BTreeMap<EntryID, EntryVisibility>,
+    saved_layout: Option<SavedLayout>,
+
     // This is just for the local profile
     interval: Interval,
     tile_set: TileSet,
     warning_message: Option<String>,
+    epoch: Option<Timestamp>,
 
-    data_source: CountingDeferredDataSource<Box<dyn DeferredDataSource>>,
+    // Field to color items by (instead of their own colors), and the range of
+    // numeric values seen so far for that field
+    color_by: Option<FieldID>,
+    color_by_range: Option<(f64, f64)>,
+
+    data_source: CountingDeferredDataSource<SharedDeferredDataSource<Box<dyn DeferredDataSource>>>,
 
     search_state: SearchState,
 
+    // View interval and tiles as of the last meta tile eviction check
+    last_evict_interval: Option<Interval>,
+    last_evict_tiles: BTreeSet<TileID>,
+
     // When the user clicks on an item, we put it here
     items_selected: BTreeMap<ItemUID, ItemDetail>,
+    // The most recently selected item, used as the starting point when
+    // stepping between items in a row
+    last_selected: Option<ItemLocator>,
+    // Position in items_selected shown in the detail pane, and the most
+    // recent selection as of the last frame (so the pane can jump to new
+    // selections)
+    detail_index: usize,
+    detail_last_selected: Option<ItemUID>,
+
+    // Tooltip turned into a popup (by holding Alt), and where to show it
+    pinned_tooltip: Option<(ItemDetail, Pos2)>,
+    // Show the selected items side by side
+    compare_items: bool,
+    // Limit the duration histogram to the processor of the selected item
+    histogram_selected_slot: bool,
+    // Group the statistics by processor (instead of kind), and sort them by
+    // a column (descending if true)
+    stats_by_processor: bool,
+    stats_sort: (StatsColumn, bool),
+    // The statistics, and what they were computed from
+    stats_cache: Option<(StatsKey, Vec<(String, ItemStats)>)>,
+    // Item right-clicked in the timeline (with its interval), and where to
+    // show the menu
+    item_menu: Option<(ItemLocator, Interval, Pos2)>,
 
     // When the user clicks "Zoom to Item" or a search result, we put it here
     scroll_to_item: Option<ItemLocator>,
@@ -170,14 +299,57 @@ struct Config {
     // populate the following field to track the re-scroll when the item is found
     scroll_to_item_retry: Option<ItemLocator>,
 
+    // Vertical scroll position of the window, and a position to restore
+    // (from a previous session) on the next frame
+    scroll_offset: f32,
+    restore_scroll_offset: Option<f32>,
+
+    // Level of tile_set to fetch tiles from, or None to pick one based on
+    // the zoom
+    tile_level: Option<usize>,
+
     last_request_interval: Option<Interval>,
     request_tile_cache: Vec<TileID>,
+
+    // Width of the timeline as last drawn, and where to export it as an SVG
+    // (with the outcome of the last export)
+    #[cfg(not(target_arch = "wasm32"))]
+    view_width: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    svg_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    svg_status: Option<String>,
+
+    // Where to export the entire profile as a Chrome trace, the export in
+    // progress (advanced once per frame), and its progress or outcome
+    #[cfg(not(target_arch = "wasm32"))]
+    trace_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    trace_export: Option<ChromeTraceWriter<SharedDeferredDataSource<Box<dyn DeferredDataSource>>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    trace_status: Option<String>,
 }
 
 struct Window {
     panel: Panel<Panel<Panel<Slot>>>, // nodes -> kind -> proc/chan/mem
     index: u64,
     config: Config,
+    // Kept so the window can be duplicated without fetching it again
+    info: DataSourceInfo,
+    // Which copy of the profile this is, 0 for the original
+    copy: u64,
+    // Duplicates pan and zoom on their own. Other windows follow the view
+    // in the context
+    view: Option<WindowView>,
+}
+
+// The parts of the context that describe what is in view, for windows that
+// have their own
+#[derive(Debug, Clone)]
+struct WindowView {
+    interval: Interval,
+    history: IntervalState,
+    select_state: IntervalSelectState,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
@@ -193,6 +365,13 @@ struct IntervalState {
     index: usize,
 }
 
+#[derive(Debug, Clone, Default)]
+struct MarkerState {
+    time_buffer: String,
+    label_buffer: String,
+    error: Option<TimestampParseError>,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
 enum IntervalSelectError {
     InvalidValue,
@@ -216,9 +395,9 @@ impl From<TimestampParseError> for IntervalSelectError {
 impl fmt::Display for IntervalSelectError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            IntervalSelectError::InvalidValue => write!(f, "invalid value"),
-            IntervalSelectError::NoUnit => write!(f, "no unit"),
-            IntervalSelectError::InvalidUnit => write!(f, "invalid unit"),
+            IntervalSelectError::InvalidValue => TimestampParseError::InvalidValue.fmt(f),
+            IntervalSelectError::NoUnit => TimestampParseError::NoUnit.fmt(f),
+            IntervalSelectError::InvalidUnit => TimestampParseError::InvalidUnit.fmt(f),
             IntervalSelectError::StartAfterStop => write!(f, "start after stop"),
             IntervalSelectError::StartAfterEnd => write!(f, "start after end"),
             IntervalSelectError::StopBeforeStart => write!(f, "stop before start"),
@@ -244,6 +423,316 @@ enum ItemLinkNavigationMode {
     Pan,
 }
 
+// What to show when profiles are first opened. An explicit interval passed in
+// StartOptions takes precedence over either.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+enum StartupZoom {
+    Fit,
+    #[default]
+    Remembered,
+}
+
+// How to separate adjacent items, so that items of the same color don't
+// merge into one
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+enum ItemBorders {
+    #[default]
+    None,
+    Outline,
+    Gap,
+}
+
+// Where to show the details of selected items
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+enum DetailLayout {
+    // One floating window per item
+    #[default]
+    Windows,
+    // One item at a time in a panel on the right
+    Pane,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+enum RowGuides {
+    #[default]
+    None,
+    Separators,
+    Alternating,
+}
+
+// How much space the utilization plots at the top of each panel take
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+enum SummaryRows {
+    #[default]
+    Full,
+    Strip,
+    Hidden,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum EntryVisibility {
+    Solo,
+    Mute,
+}
+
+// Time spent in the expensive phases of the frame being drawn, and of the
+// last complete frame (which is what gets displayed in debug mode)
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+struct FrameTimings {
+    current: BTreeMap<&'static str, Duration>,
+    last: BTreeMap<&'static str, Duration>,
+}
+
+// Maps times in the view to fractions of the screen width. With a log origin,
+// positions are logarithmic in the time since the origin (plus 1 ns, so the
+// origin itself is finite). Times before the origin all map to the same
+// position as the origin.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct TimeAxis {
+    view: Interval,
+    log_origin: Option<Timestamp>,
+}
+
+impl From<Interval> for TimeAxis {
+    fn from(view: Interval) -> Self {
+        Self {
+            view,
+            log_origin: None,
+        }
+    }
+}
+
+impl TimeAxis {
+    fn log_offset(origin: Timestamp, time: Timestamp) -> f64 {
+        ((time.0 - origin.0).max(0) as f64 + 1.0).ln()
+    }
+
+    // Like Interval::unlerp
+    fn unlerp(self, time: Timestamp) -> f32 {
+        let Some(origin) = self.log_origin else {
+            return self.view.unlerp(time);
+        };
+        let start = Self::log_offset(origin, self.view.start);
+        let stop = Self::log_offset(origin, self.view.stop);
+        if stop <= start {
+            return self.view.unlerp(time);
+        }
+        ((Self::log_offset(origin, time) - start) / (stop - start)) as f32
+    }
+
+    // Like Interval::lerp
+    fn lerp(self, value: f32) -> Timestamp {
+        let Some(origin) = self.log_origin else {
+            return self.view.lerp(value);
+        };
+        let start = Self::log_offset(origin, self.view.start);
+        let stop = Self::log_offset(origin, self.view.stop);
+        if stop <= start {
+            return self.view.lerp(value);
+        }
+        let offset = (start + value as f64 * (stop - start)).exp() - 1.0;
+        Timestamp(origin.0 + offset.round() as i64)
+    }
+}
+
+// Spreads the drawing of a large view over several frames, so that the UI
+// stays responsive. Entries are counted in drawing order. Those drawn in
+// full on the previous frame (since the view last changed or was scrolled)
+// are always drawn, and then more are drawn until the budget runs out. The
+// rest get a placeholder until a later frame.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+struct RenderBudget {
+    // The shared view, followed by those of windows with their own
+    views: Vec<Interval>,
+    // Vertical scroll offset of each window, by index
+    scroll: BTreeMap<u64, f32>,
+    settled: usize,
+    drawn: usize,
+    deadline: Option<Instant>,
+    skipped: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RenderBudget {
+    const DURATION: Duration = Duration::from_millis(30);
+
+    fn start_frame(&mut self, views: Vec<Interval>) {
+        if self.views != views {
+            self.views = views;
+            self.settled = 0;
+        }
+        self.drawn = 0;
+        self.deadline = None;
+        self.skipped = false;
+    }
+
+    // Called before drawing each window. Scrolling brings new entries into
+    // view, so only those already drawn this frame are settled.
+    fn scroll_to(&mut self, window: u64, offset: f32) {
+        if self.scroll.insert(window, offset) != Some(offset) {
+            self.settled = self.settled.min(self.drawn);
+        }
+    }
+
+    // Whether to draw the next entry
+    fn draw_next(&mut self) -> bool {
+        if self.skipped {
+            return false;
+        }
+        self.drawn += 1;
+        if self.drawn <= self.settled {
+            return true;
+        }
+        // The first new entry is always drawn, so every frame makes progress
+        let Some(deadline) = self.deadline else {
+            self.deadline = Some(Instant::now() + Self::DURATION);
+            return true;
+        };
+        if Instant::now() < deadline {
+            return true;
+        }
+        self.drawn -= 1;
+        self.skipped = true;
+        false
+    }
+
+    // Returns true if another frame is needed to finish drawing
+    fn finish_frame(&mut self) -> bool {
+        if self.skipped {
+            self.settled = self.drawn;
+        } else {
+            self.settled = usize::MAX;
+        }
+        self.skipped
+    }
+}
+
+// Layout to go back to after focusing on a search result
+#[derive(Debug, Clone)]
+struct SavedLayout {
+    visibility_overrides: BTreeMap<EntryID, EntryVisibility>,
+    // Entries that were expanded to show the result
+    expanded: Vec<EntryID>,
+}
+
+// Colors used to make selected items and search matches stand out
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+struct HighlightColors {
+    selected: Color32,
+    search_match: Color32,
+    // Items that don't match a search are dimmed by this factor
+    dim_factor: f32,
+}
+
+impl Default for HighlightColors {
+    fn default() -> Self {
+        Self {
+            selected: Color32::RED,
+            search_match: Color32::from_rgb(255, 140, 0),
+            dim_factor: 0.2,
+        }
+    }
+}
+
+// Utilization plots are resampled to a density tied to their width on
+// screen, so that they stay sharp when zoomed in and don't overdraw when
+// zoomed out
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+struct SummarySampling {
+    enabled: bool,
+    samples_per_pixel: f32,
+}
+
+impl Default for SummarySampling {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            samples_per_pixel: 1.0,
+        }
+    }
+}
+
+// Rows of a slot tile with more than this many items per pixel are drawn as a
+// heatmap rather than item by item, since most items would be sub-pixel
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+struct DenseRows {
+    enabled: bool,
+    items_per_pixel: f32,
+}
+
+impl Default for DenseRows {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            items_per_pixel: 4.0,
+        }
+    }
+}
+
+// Limits how often the app repaints itself while loading or animating (e.g.,
+// to save power on battery). Input always repaints right away, and once
+// everything is loaded the app doesn't repaint at all.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+struct RepaintCap {
+    enabled: bool,
+    max_fps: f32,
+}
+
+impl Default for RepaintCap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_fps: 30.0,
+        }
+    }
+}
+
+impl RepaintCap {
+    fn min_interval(&self) -> Duration {
+        if self.enabled {
+            Duration::from_secs_f64(1.0 / f64::from(self.max_fps.max(1.0)))
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+// Utilization below which the summary plots are shaded, to find stalls
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+struct UtilThreshold {
+    enabled: bool,
+    value: f32,
+}
+
+impl Default for UtilThreshold {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            value: 0.5,
+        }
+    }
+}
+
+// How far (in points) and how long (in seconds) the pointer may move and be
+// held while still counting as a click on an item. Touchpads often move a
+// little during a click, so these are more forgiving than they might be.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+struct ClickThresholds {
+    max_distance: f32,
+    max_duration: f32,
+}
+
+impl Default for ClickThresholds {
+    fn default() -> Self {
+        Self {
+            max_distance: 6.0,
+            max_duration: 0.6,
+        }
+    }
+}
+
 impl ItemLinkNavigationMode {
     fn label_text(&self) -> &'static str {
         match *self {
@@ -253,6 +742,37 @@ impl ItemLinkNavigationMode {
     }
 }
 
+// Columns of the statistics table, in order
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+enum StatsColumn {
+    #[default]
+    Name,
+    Busy,
+    Count,
+    Mean,
+    Median,
+}
+
+impl StatsColumn {
+    const ALL: [StatsColumn; 5] = [
+        StatsColumn::Name,
+        StatsColumn::Busy,
+        StatsColumn::Count,
+        StatsColumn::Mean,
+        StatsColumn::Median,
+    ];
+
+    fn label_text(&self) -> &'static str {
+        match *self {
+            StatsColumn::Name => "Name",
+            StatsColumn::Busy => "Busy",
+            StatsColumn::Count => "Items",
+            StatsColumn::Mean => "Mean",
+            StatsColumn::Median => "Median",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 struct Context {
     #[serde(skip)]
@@ -262,6 +782,17 @@ struct Context {
 
     #[serde(skip)]
     row_scroll_delta: i32,
+    // Wheel movement not yet large enough to scroll a whole row
+    #[serde(skip)]
+    wheel_remainder: f32,
+    // Distance (in points) left to scroll when scrolling smoothly
+    #[serde(skip)]
+    scroll_pending: f32,
+    // Number of rows visible in each profile, for scrolling by page
+    #[serde(skip)]
+    page_rows: i32,
+
+    smooth_scroll: bool,
 
     #[serde(skip)]
     subheading_size: f32,
@@ -270,72 +801,387 @@ struct Context {
     #[serde(skip)]
     total_interval: Interval,
 
+    // Last-used search options, keyed by profile source locator
+    #[serde(default)]
+    search_options: BTreeMap<String, SearchOptions>,
+
+    // Last vertical scroll position, keyed by profile source locator
+    #[serde(default)]
+    scroll_offsets: BTreeMap<String, f32>,
+
+    // Last view and its history, keyed by the source locators of all open
+    // profiles (see ProfApp::view_key)
+    #[serde(default)]
+    saved_views: BTreeMap<String, (Interval, IntervalState)>,
+
+    // Keys of saved_views from least to most recently saved, so the oldest
+    // can be dropped (see remember_view)
+    #[serde(default)]
+    saved_view_order: VecDeque<String>,
+
     // Visible time range
     #[serde(skip)]
     view_interval: Interval,
 
+    // Wall-clock time at which the profiles start (from the first profile
+    // that provides one), used to display absolute times
+    #[serde(skip)]
+    epoch: Option<Timestamp>,
+    absolute_time: bool,
+
+    // Unit to display times in, or Auto to pick one based on the zoom level
+    display_unit: DisplayUnit,
+
     #[serde(skip)]
     drag_origin: Option<Pos2>,
+    // Interval measured with Shift + drag, shown until the next click
+    #[serde(skip)]
+    measurement: Option<Interval>,
+
+    #[serde(default)]
+    click_thresholds: ClickThresholds,
+    // Where and when the primary button was last pressed
+    #[serde(skip)]
+    press_origin: Option<(Pos2, f64)>,
 
     // Hack: We need to track the screenspace rect where slot/summary
     // data gets drawn. This gets used rendering the cursor, but we
     // only know it when we render slots. So stash it here.
     #[serde(skip)]
     slot_rect: Option<Rect>,
+    // Time under the mouse in the timeline, if any (as of the last frame)
+    #[serde(skip)]
+    hover_time: Option<Timestamp>,
+
+    // Vertical lines at fixed times, with their labels
+    #[serde(default)]
+    markers: Vec<(Timestamp, String)>,
+    #[serde(skip)]
+    marker_state: MarkerState,
 
     item_link_mode: ItemLinkNavigationMode,
 
+    // Items narrower than this (in points) are widened so that they remain
+    // visible when zoomed out, even if that means overlapping neighbors
+    min_item_width: f32,
+
+    // Visual aids for following a row across an expanded slot
+    row_guides: RowGuides,
+
+    // Time ruler above the profiles, with gridlines through the slots
+    #[serde(default)]
+    show_ruler: bool,
+
+    #[serde(default)]
+    summary_rows: SummaryRows,
+
+    #[serde(default)]
+    item_borders: ItemBorders,
+
+    #[serde(default)]
+    detail_layout: DetailLayout,
+
+    // Experimental: place times on a log scale (see TimeAxis)
+    #[serde(default)]
+    log_time: bool,
+
+    #[serde(default)]
+    highlight_colors: HighlightColors,
+
+    #[serde(default)]
+    summary_sampling: SummarySampling,
+
+    #[serde(default)]
+    dense_rows: DenseRows,
+
+    #[serde(default)]
+    util_threshold: UtilThreshold,
+
+    #[serde(default)]
+    repaint_cap: RepaintCap,
+
+    // Whether panels of each kind (by lowercase name) start out expanded
+    // when a profile is opened. Kinds not listed start collapsed.
+    #[serde(default)]
+    kind_expanded: BTreeMap<String, bool>,
+
+    // Draw titles on items wide enough to hold some text
+    #[serde(default)]
+    show_item_titles: bool,
+
+    // Shade the time in each slot where no item is running
+    #[serde(default)]
+    shade_idle: bool,
+
+    // Highlight items whose titles match an item selected in another profile
+    #[serde(default)]
+    link_selection: bool,
+
+    // Custom widgets for fields in item details, from StartOptions
+    #[serde(skip)]
+    field_renderers: FieldRenderers,
+
+    // Names of fields not shown in item details and tooltips (in any profile)
+    #[serde(default)]
+    hidden_fields: BTreeSet<String>,
+    // Names of fields to show first in item details and tooltips, in order.
+    // Other fields follow in the order the data source gives them.
+    #[serde(default)]
+    field_order: Vec<String>,
+    // Titles of selected items, and the profiles (by index) selecting them
+    #[serde(skip)]
+    selected_titles: BTreeMap<String, BTreeSet<u64>>,
+    // Hack: titles selected in profiles other than the one being rendered.
+    // Set by each window before it renders its slots.
+    #[serde(skip)]
+    linked_titles: BTreeSet<String>,
+
     toggle_dark_mode: bool,
 
+    // Font files to fall back on for characters missing from the built-in
+    // fonts (e.g., CJK titles), in order of preference
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(default)]
+    font_paths: Vec<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    font_path_buffer: String,
+    // Fonts (by name or path) that could not be installed, with the reason
+    #[serde(skip)]
+    font_errors: BTreeMap<String, String>,
+
     debug: bool,
+    // Debugging aid for data source authors: don't reuse meta tiles across
+    // view changes, so that every view change re-fetches from the source
+    #[serde(skip)]
+    bypass_tile_cache: bool,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    frame_timings: FrameTimings,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    render_budget: RenderBudget,
 
     #[serde(skip)]
     show_controls: bool,
 
+    #[serde(default)]
+    key_bindings: KeyBindings,
+    // Action whose key binding is being changed in the controls window
     #[serde(skip)]
-    view_interval_history: IntervalState,
+    capturing_key: Option<KeyAction>,
+    // Only show controls matching this text
     #[serde(skip)]
-    interval_select_state: IntervalSelectState,
-}
+    controls_filter: String,
 
-#[derive(Default, Deserialize, Serialize)]
-#[serde(default)] // deserialize missing fields as default value
-struct ProfApp {
-    // Data sources waiting to be turned into windows.
+    // Profiles (by index) whose warnings have been dismissed from the banner
     #[serde(skip)]
-    pending_data_sources: VecDeque<Box<dyn DeferredDataSource>>,
-
+    dismissed_warnings: BTreeSet<u64>,
+    // Profiles that failed to load, also shown in the banner
     #[serde(skip)]
-    windows: Vec<Window>,
+    load_errors: Vec<String>,
+    // Shown in the banner if settings were lost loading the saved state
+    #[serde(skip)]
+    state_notice: Option<String>,
 
-    cx: Context,
+    // Index of the window the user asked to duplicate this frame
+    #[serde(skip)]
+    duplicate_window: Option<u64>,
+    // Index of the window under the pointer, if it has its own view. Keys
+    // that pan and zoom apply to it instead of the shared view.
+    #[serde(skip)]
+    active_window: Option<u64>,
 
-    #[cfg(not(target_arch = "wasm32"))]
     #[serde(skip)]
-    last_update: Option<Instant>,
+    view_interval_history: IntervalState,
+    // Set once the startup view has been applied
+    #[serde(skip)]
+    startup_view_applied: bool,
+    #[serde(default)]
+    startup_zoom: StartupZoom,
+    // Requested at startup, applied as profiles load
+    #[serde(skip)]
+    start_options: StartOptions,
+    // Query parameters last written to the page URL, and ones waiting for the
+    // view to settle (with the time they were first seen)
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    written_link: Vec<(&'static str, String)>,
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    pending_link: Option<(Vec<(&'static str, String)>, f64)>,
+    #[serde(skip)]
+    interval_select_state: IntervalSelectState,
 }
 
-trait Entry {
-    fn new(info: &EntryInfo, entry_id: EntryID) -> Self;
+impl Context {
+    // Requests another frame for work in progress (loading, animations),
+    // subject to the repaint cap
+    fn request_repaint(&self, ctx: &egui::Context) {
+        ctx.request_repaint_after(self.repaint_cap.min_interval());
+    }
 
-    fn entry_id(&self) -> &EntryID;
-    fn label_text(&self) -> &str;
-    fn hover_text(&self) -> &str;
+    // Phases are only timed in debug mode, to keep overhead down
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_timer(&self) -> Option<Instant> {
+        self.debug.then(Instant::now)
+    }
 
-    fn find_slot(&self, entry_id: &EntryID, level: u64) -> Option<&Slot>;
-    fn find_slot_mut(&mut self, entry_id: &EntryID, level: u64) -> Option<&mut Slot>;
-    fn find_summary_mut(&mut self, entry_id: &EntryID, level: u64) -> Option<&mut Summary>;
+    #[cfg(not(target_arch = "wasm32"))]
+    fn stop_timer(&mut self, phase: &'static str, start: Option<Instant>) {
+        if let Some(start) = start {
+            let timings = &mut self.frame_timings.current;
+            *timings.entry(phase).or_default() += start.elapsed();
+        }
+    }
+
+    // Whether the primary button was just released at the end of a click on
+    // an item. We test this ourselves rather than using egui's click
+    // detection so that the thresholds can be configured. Note that the same
+    // press also drives drag-to-zoom (see ProfApp::drag_zoom), which ignores
+    // drags short enough to be clicks, so that a click never also zooms.
+    fn is_item_click(&self, i: &egui::InputState) -> bool {
+        if !i.pointer.primary_released() {
+            return false;
+        }
+        let (Some((origin, time)), Some(pos)) = (self.press_origin, i.pointer.interact_pos())
+        else {
+            return false;
+        };
+        let thresholds = &self.click_thresholds;
+        origin.distance(pos) <= thresholds.max_distance
+            && i.time - time <= thresholds.max_duration as f64
+    }
+
+    // Mapping from time to screen position for the current view. The log
+    // scale counts from the start of the profiles.
+    fn time_axis(&self) -> TimeAxis {
+        TimeAxis {
+            view: self.view_interval,
+            log_origin: self.log_time.then_some(self.total_interval.start),
+        }
+    }
+
+    // Epoch to display times relative to, if showing absolute times
+    fn display_epoch(&self) -> Option<Timestamp> {
+        self.epoch.filter(|_| self.absolute_time)
+    }
+
+    fn format_timestamp(&self, timestamp: Timestamp) -> String {
+        TimestampDisplay {
+            timestamp,
+            units: self.display_unit.timestamp_units(timestamp),
+            include_units: true,
+            epoch: self.display_epoch(),
+        }
+        .to_string()
+    }
+
+    // Durations are never shown as wall-clock times
+    fn format_duration(&self, duration_ns: i64) -> String {
+        let duration = Timestamp(duration_ns);
+        TimestampDisplay {
+            timestamp: duration,
+            units: self.display_unit.timestamp_units(duration),
+            include_units: true,
+            epoch: None,
+        }
+        .to_string()
+    }
+
+    fn format_interval(&self, interval: Interval) -> String {
+        IntervalDisplay {
+            interval,
+            epoch: self.display_epoch(),
+            unit: self.display_unit,
+        }
+        .to_string()
+    }
+
+    // Accepts relative times in either mode, so that e.g. "0 s" still works
+    fn parse_timestamp(&self, s: &str) -> std::result::Result<Timestamp, TimestampParseError> {
+        match self.display_epoch() {
+            Some(epoch) => {
+                Timestamp::parse_absolute(s, epoch).or_else(|e| Timestamp::parse(s).map_err(|_| e))
+            }
+            None => Timestamp::parse(s),
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)] // deserialize missing fields as default value
+struct ProfApp {
+    // Data sources waiting to be turned into windows.
+    #[serde(skip)]
+    pending_data_sources: VecDeque<Box<dyn DeferredDataSource>>,
+    // When given too many data sources to open at once, the user picks which
+    // ones to open (checked or not) before they are fetched
+    #[serde(skip)]
+    picker_data_sources: Vec<(Box<dyn DeferredDataSource>, bool)>,
+
+    #[serde(skip)]
+    windows: Vec<Window>,
+
+    cx: Context,
+
+    // Format of the saved state (see STATE_VERSION). Missing from states
+    // saved before it was introduced, which read as 0.
+    version: u32,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    last_update: Option<Instant>,
+}
+
+// Bump this when a change to the saved state needs a migration
+const STATE_VERSION: u32 = 1;
+
+trait Entry {
+    fn new(info: &EntryInfo, entry_id: EntryID) -> Self;
+
+    fn entry_id(&self) -> &EntryID;
+    fn label_text(&self) -> &str;
+    fn hover_text(&self) -> &str;
+
+    fn find_slot(&self, entry_id: &EntryID, level: u64) -> Option<&Slot>;
+    fn find_slot_mut(&mut self, entry_id: &EntryID, level: u64) -> Option<&mut Slot>;
+    fn find_summary_mut(&mut self, entry_id: &EntryID, level: u64) -> Option<&mut Summary>;
+    // The entry's own summary, if any
+    fn summary_mut(&mut self) -> Option<&mut Summary>;
 
     fn expand_slot(&mut self, entry_id: &EntryID, level: u64);
 
     fn inflate_meta(&mut self, config: &mut Config, cx: &mut Context);
 
+    // Drops meta tiles other than the ones given, to bound memory use. With
+    // loaded_too, drops loaded tiles even if given (keeping in-flight ones).
+    fn evict_meta_tiles(&mut self, keep: &BTreeSet<TileID>, loaded_too: bool);
+
+    // Drops all tiles, so that they are requested again
+    fn clear_tiles(&mut self);
+
+    // Rough estimate of the memory held by meta tiles (in bytes)
+    fn meta_tiles_size(&self) -> usize;
+
     fn search(&mut self, config: &mut Config);
 
-    fn label(&mut self, ui: &mut egui::Ui, rect: Rect, cx: &Context) {
+    // Durations of the items in loaded meta tiles that overlap the view, for
+    // the same entries that search looks at
+    fn item_durations(&self, config: &Config, view: Interval, durations: &mut Vec<i64>);
+
+    // Extra lines for the label's tooltip
+    fn hover_details(&self, _cx: &Context) -> Option<String> {
+        None
+    }
+
+    fn label(&mut self, ui: &mut egui::Ui, rect: Rect, config: &mut Config, cx: &Context) {
+        // Summaries can't be soloed or muted on their own
+        let has_menu = matches!(self.entry_id().last_index(), Some(EntryIndex::Slot(_)));
         let response = ui.allocate_rect(
             rect,
-            if self.is_expandable() {
+            if self.is_expandable() || has_menu {
                 egui::Sense::click()
             } else {
                 egui::Sense::hover()
@@ -352,19 +1198,65 @@ trait Entry {
 
         ui.painter()
             .rect(rect, 0.0, visuals.bg_fill, visuals.bg_stroke);
-        ui.painter().text(
-            rect.min + style.spacing.item_spacing * Vec2::new(1.0, cx.scale_factor),
-            Align2::LEFT_TOP,
-            self.label_text(),
-            font_id,
-            visuals.text_color(),
-        );
+        let text_pos = rect.min + style.spacing.item_spacing * Vec2::new(1.0, cx.scale_factor);
+        // Keep long labels from spilling over into the content
+        let text_rect = ui
+            .painter()
+            .with_clip_rect(rect.intersect(ui.clip_rect()))
+            .text(
+                text_pos,
+                Align2::LEFT_TOP,
+                self.label_text(),
+                font_id,
+                visuals.text_color(),
+            );
+        let truncated = text_rect.max.x > rect.max.x;
+
+        if has_menu {
+            response.clone().context_menu(|ui| {
+                let entry_id = self.entry_id();
+                let current = config.visibility_overrides.get(entry_id).copied();
+                for (visibility, text) in [
+                    (EntryVisibility::Solo, "Solo"),
+                    (EntryVisibility::Mute, "Mute"),
+                ] {
+                    let mut checked = current == Some(visibility);
+                    if ui.checkbox(&mut checked, text).clicked() {
+                        if checked {
+                            config
+                                .visibility_overrides
+                                .insert(entry_id.clone(), visibility);
+                        } else {
+                            config.visibility_overrides.remove(entry_id);
+                        }
+                        ui.close_menu();
+                    }
+                }
+                let any = !config.visibility_overrides.is_empty();
+                if ui
+                    .add_enabled(any, egui::Button::new("Clear Solo/Mute"))
+                    .clicked()
+                {
+                    config.visibility_overrides.clear();
+                    ui.close_menu();
+                }
+            });
+        }
 
-        if response.clicked() {
+        if response.clicked() && self.is_expandable() {
             // This will take effect next frame because we can't redraw this widget now
             self.toggle_expanded();
         } else if response.hovered() {
-            response.on_hover_text(self.hover_text());
+            let mut text = if truncated {
+                format!("{}\n{}", self.label_text(), self.hover_text())
+            } else {
+                self.hover_text().to_owned()
+            };
+            if let Some(details) = self.hover_details(cx) {
+                text.push('\n');
+                text.push_str(&details);
+            }
+            response.on_hover_text(text);
         }
     }
 
@@ -379,6 +1271,17 @@ trait Entry {
 
     fn height(&self, prefix: Option<&EntryID>, config: &Config, cx: &Context) -> f32;
 
+    // Like content, but writes the loaded data to an SVG instead of the screen
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(
+        &self,
+        svg: &mut SvgWriter,
+        rect: Rect,
+        ui: &egui::Ui,
+        config: &mut Config,
+        cx: &Context,
+    );
+
     fn is_expandable(&self) -> bool;
 
     fn toggle_expanded(&mut self);
@@ -389,14 +1292,46 @@ impl Summary {
         self.tiles.clear();
     }
 
+    // Requests the tiles for the current view, if not already requested
+    fn update_tiles(&mut self, config: &mut Config, cx: &mut Context) {
+        if self.last_view_interval != Some(cx.view_interval) {
+            self.clear();
+        }
+        self.last_view_interval = Some(cx.view_interval);
+        if self.tiles.is_empty() {
+            #[cfg(not(target_arch = "wasm32"))]
+            let timer = cx.start_timer();
+            self.inflate(config, cx);
+            #[cfg(not(target_arch = "wasm32"))]
+            cx.stop_timer("Inflate", timer);
+        }
+    }
+
+    // All loaded utilization points, in tile order
+    fn utilization(&self) -> Vec<UtilPoint> {
+        self.tiles
+            .values()
+            .flatten()
+            .flatten()
+            .flat_map(|tile| tile.utilization.iter().copied())
+            .collect()
+    }
+
     fn inflate(&mut self, config: &mut Config, cx: &mut Context) {
-        for tile_id in config.request_tiles(cx.view_interval) {
-            config
-                .data_source
-                .fetch_summary_tile(&self.entry_id, tile_id, false);
+        let tile_ids = config.request_tiles(cx.view_interval);
+        let requests = tile_requests(&self.entry_id, &tile_ids);
+        config.data_source.fetch_summary_tiles(&requests, false);
+        for tile_id in tile_ids {
             self.tiles.insert(tile_id, None);
         }
     }
+
+    fn retry_tile(&mut self, tile_id: TileID, config: &mut Config) {
+        config
+            .data_source
+            .fetch_summary_tile(&self.entry_id, tile_id, false);
+        self.tiles.insert(tile_id, None);
+    }
 }
 
 impl Entry for Summary {
@@ -407,6 +1342,8 @@ impl Entry for Summary {
                 color: *color,
                 tiles: BTreeMap::new(),
                 last_view_interval: None,
+                hovered: false,
+                breakdown: Vec::new(),
             }
         } else {
             unreachable!()
@@ -437,6 +1374,10 @@ impl Entry for Summary {
         Some(self)
     }
 
+    fn summary_mut(&mut self) -> Option<&mut Summary> {
+        unreachable!()
+    }
+
     fn expand_slot(&mut self, _entry_id: &EntryID, _level: u64) {
         unreachable!()
     }
@@ -445,10 +1386,26 @@ impl Entry for Summary {
         unreachable!()
     }
 
+    fn evict_meta_tiles(&mut self, _keep: &BTreeSet<TileID>, _loaded_too: bool) {
+        unreachable!()
+    }
+
+    fn clear_tiles(&mut self) {
+        self.clear();
+    }
+
+    fn meta_tiles_size(&self) -> usize {
+        unreachable!()
+    }
+
     fn search(&mut self, _config: &mut Config) {
         unreachable!()
     }
 
+    fn item_durations(&self, _config: &Config, _view: Interval, _durations: &mut Vec<i64>) {
+        unreachable!()
+    }
+
     fn content(
         &mut self,
         ui: &mut egui::Ui,
@@ -460,20 +1417,17 @@ impl Entry for Summary {
         cx.slot_rect = Some(rect); // Save slot rect for use later
 
         const TOOLTIP_RADIUS: f32 = 4.0;
-        let response = ui.allocate_rect(rect, egui::Sense::hover());
-        let hover_pos = response.hover_pos(); // where is the mouse hovering?
+        let response = ui.allocate_rect(rect, egui::Sense::drag());
+        let mut hover_pos = response.hover_pos(); // where is the mouse hovering?
+        self.hovered = hover_pos.is_some();
 
-        if self
-            .last_view_interval
-            .map_or(true, |i| i != cx.view_interval)
-        {
-            self.clear();
-        }
-        self.last_view_interval = Some(cx.view_interval);
-        if self.tiles.is_empty() {
-            self.inflate(config, cx);
+        // Drag to zoom to a region of the plot (or pan)
+        if !ProfApp::drag_pan(ui, rect, &response, cx) {
+            ProfApp::drag_zoom(ui, rect, &response, cx);
         }
 
+        self.update_tiles(config, cx);
+
         let style = ui.style();
         let visuals = style.interact_selectable(&response, false);
         ui.painter()
@@ -481,15 +1435,30 @@ impl Entry for Summary {
 
         let stroke = Stroke::new(visuals.bg_stroke.width, self.color);
 
+        // Draw any tiles that failed to load, and retry them on click
+        let mut retry_tile = None;
+        for (tile_id, tile) in &self.tiles {
+            match tile {
+                Some(Err(error)) => {
+                    if show_tile_error(ui, rect, tile_id.0, error, &mut hover_pos, cx) {
+                        retry_tile = Some(*tile_id);
+                    }
+                }
+                None => show_tile_loading(ui, rect, tile_id.0, cx),
+                Some(Ok(_)) => {}
+            }
+        }
+        if let Some(tile_id) = retry_tile {
+            self.retry_tile(tile_id, config);
+        }
+
         // Conversions to and from screen space coordinates
         let util_to_screen = |util: &UtilPoint| {
-            let time = cx.view_interval.unlerp(util.time);
+            let time = cx.time_axis().unlerp(util.time);
             rect.lerp_inside(Vec2::new(time, 1.0 - util.util))
         };
         let screen_to_util = |screen: Pos2| UtilPoint {
-            time: cx
-                .view_interval
-                .lerp((screen.x - rect.left()) / rect.width()),
+            time: cx.time_axis().lerp((screen.x - rect.left()) / rect.width()),
             util: 1.0 - (screen.y - rect.top()) / rect.height(),
         };
 
@@ -499,65 +1468,171 @@ impl Entry for Summary {
             Rect::from_min_max(p1, p2).lerp_inside(Vec2::new(ratio, ratio))
         };
 
+        // Shade the spread between the least and most utilized units behind
+        // the average, if the data source provides it
+        let band_painter = ui.painter().with_clip_rect(rect);
+        let band_color = self.color.linear_multiply(0.25);
+        for tile in self.tiles.values().flatten().flatten() {
+            for pair in tile.utilization_range.windows(2) {
+                let (r1, r2) = (&pair[0], &pair[1]);
+                let segment = Interval::new(r1.time, r2.time);
+                if segment.duration_ns() <= 0 || !cx.view_interval.overlaps(segment) {
+                    continue;
+                }
+                let corner = |time, util| util_to_screen(&UtilPoint { time, util });
+                let points = vec![
+                    corner(r1.time, r1.min),
+                    corner(r2.time, r2.min),
+                    corner(r2.time, r2.max),
+                    corner(r1.time, r1.max),
+                ];
+                band_painter.add(egui::Shape::convex_polygon(
+                    points,
+                    band_color,
+                    Stroke::NONE,
+                ));
+            }
+        }
+
+        let mut utilization = self.utilization();
+        let sampling = cx.summary_sampling;
+        if sampling.enabled {
+            let samples = (rect.width() * sampling.samples_per_pixel).round() as usize;
+            utilization = resample_utilization(&utilization, cx.time_axis(), samples);
+        }
+
+        let threshold = cx.util_threshold;
+        let threshold_y = util_to_screen(&UtilPoint {
+            time: cx.view_interval.start,
+            util: threshold.value,
+        })
+        .y;
+        let threshold_color = ui.visuals().warn_fg_color;
+        let shade_color = threshold_color.linear_multiply(0.3);
+        if threshold.enabled {
+            ui.painter().extend(egui::Shape::dashed_line(
+                &[
+                    Pos2::new(rect.min.x, threshold_y),
+                    Pos2::new(rect.max.x, threshold_y),
+                ],
+                Stroke::new(1.0, threshold_color),
+                6.0,
+                3.0,
+            ));
+        }
+
         let mut last_util: Option<&UtilPoint> = None;
         let mut last_point: Option<Pos2> = None;
         let mut hover_util = None;
-        for tile in self.tiles.values().flatten() {
-            for util in &tile.utilization {
-                let mut point = util_to_screen(util);
-                if let Some(mut last) = last_point {
-                    let last_util = last_util.unwrap();
-                    if cx
-                        .view_interval
-                        .overlaps(Interval::new(last_util.time, util.time))
-                    {
-                        // Interpolate when out of view
-                        if last.x < rect.min.x {
-                            last = interpolate(last, point, rect.min.x);
-                        }
-                        if point.x > rect.max.x {
-                            point = interpolate(last, point, rect.max.x);
+        for util in &utilization {
+            let mut point = util_to_screen(util);
+            if let Some(mut last) = last_point {
+                let last_util = last_util.unwrap();
+                // Skip segments that go back in time (the data is out of
+                // order). Vertical (zero-length) segments are fine.
+                let segment = Interval::new(last_util.time, util.time);
+                if segment.duration_ns() >= 0 && cx.view_interval.overlaps(segment) {
+                    // Interpolate when out of view
+                    if last.x < rect.min.x {
+                        last = interpolate(last, point, rect.min.x);
+                    }
+                    if point.x > rect.max.x {
+                        point = interpolate(last, point, rect.max.x);
+                    }
+
+                    if threshold.enabled {
+                        if let Some(shading) = shade_below_threshold(last, point, threshold_y) {
+                            ui.painter().add(egui::Shape::convex_polygon(
+                                shading,
+                                shade_color,
+                                Stroke::NONE,
+                            ));
                         }
+                    }
 
-                        ui.painter().line_segment([last, point], stroke);
+                    ui.painter().line_segment([last, point], stroke);
 
-                        if let Some(hover) = hover_pos {
-                            if last.x <= hover.x && hover.x < point.x {
-                                let interp = interpolate(last, point, hover.x);
-                                ui.painter().circle_stroke(
-                                    interp,
-                                    TOOLTIP_RADIUS,
-                                    visuals.fg_stroke,
-                                );
-                                hover_util = Some(screen_to_util(interp));
-                            }
+                    if let Some(hover) = hover_pos {
+                        if last.x <= hover.x && hover.x < point.x {
+                            let interp = interpolate(last, point, hover.x);
+                            ui.painter()
+                                .circle_stroke(interp, TOOLTIP_RADIUS, visuals.fg_stroke);
+                            hover_util = Some(screen_to_util(interp));
                         }
                     }
                 }
-
-                last_point = Some(point);
-                last_util = Some(util);
             }
+
+            last_point = Some(point);
+            last_util = Some(util);
         }
 
         if let Some(util) = hover_util {
-            let time = cx.view_interval.unlerp(util.time);
+            let time = cx.time_axis().unlerp(util.time);
             let util_rect = Rect::from_min_max(
                 rect.lerp_inside(Vec2::new(time - 0.05, 0.0)),
                 rect.lerp_inside(Vec2::new(time + 0.05, 1.0)),
             );
-            ui.show_tooltip(
-                "utilization_tooltip",
-                &util_rect,
-                format!("{:.0}% Utilization", util.util * 100.0),
-            );
+            let mut text = format!("{:.0}% Utilization", util.util * 100.0);
+            for (label, points) in &self.breakdown {
+                if let Some(child_util) = UtilPoint::interpolate(points, util.time) {
+                    text += &format!("\n{}: {:.0}%", label, child_util * 100.0);
+                }
+            }
+            ui.show_tooltip("utilization_tooltip", &util_rect, text);
         }
     }
 
     fn height(&self, prefix: Option<&EntryID>, _config: &Config, cx: &Context) -> f32 {
         assert!(prefix.is_none());
-        const ROWS: u64 = 4;
-        ROWS as f32 * cx.row_height
+        let rows = match cx.summary_rows {
+            SummaryRows::Full => 4,
+            SummaryRows::Strip => 1,
+            SummaryRows::Hidden => 0,
+        };
+        rows as f32 * cx.row_height
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(
+        &self,
+        svg: &mut SvgWriter,
+        rect: Rect,
+        ui: &egui::Ui,
+        _config: &mut Config,
+        cx: &Context,
+    ) {
+        svg.rect(rect, ui.visuals().widgets.inactive.bg_fill);
+
+        let util_to_screen = |util: &UtilPoint| {
+            let time = cx.time_axis().unlerp(util.time);
+            rect.lerp_inside(Vec2::new(time, 1.0 - util.util))
+        };
+
+        let mut utilization = self.utilization();
+        let sampling = cx.summary_sampling;
+        if sampling.enabled {
+            let samples = (rect.width() * sampling.samples_per_pixel).round() as usize;
+            utilization = resample_utilization(&utilization, cx.time_axis(), samples);
+        }
+
+        // Same segments as on screen, with the clip path trimming the ends
+        svg.begin_clip(rect);
+        let mut line = Vec::new();
+        for pair in utilization.windows(2) {
+            let segment = Interval::new(pair[0].time, pair[1].time);
+            if segment.duration_ns() >= 0 && cx.view_interval.overlaps(segment) {
+                if line.is_empty() {
+                    line.push(util_to_screen(&pair[0]));
+                }
+                line.push(util_to_screen(&pair[1]));
+            } else {
+                svg.polyline(&line, self.color, 1.0);
+                line.clear();
+            }
+        }
+        svg.polyline(&line, self.color, 1.0);
+        svg.end_clip();
     }
 
     fn is_expandable(&self) -> bool {
@@ -591,92 +1666,1131 @@ impl fmt::Display for Field {
     }
 }
 
-struct FieldWithName<'a>(&'a str, &'a Field);
+// Intervals are displayed according to the time display settings in the
+// context
+struct FieldWithName<'a>(&'a str, &'a Field, &'a Context);
 
 impl<'a> fmt::Display for FieldWithName<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let FieldWithName(name, value) = self;
+        let FieldWithName(name, value, cx) = *self;
         match value {
             Field::Empty => write!(f, "{name}"),
+            Field::Interval(interval) => {
+                write!(f, "{name}: {}", cx.format_interval(*interval))
+            }
             _ => write!(f, "{name}: {value}"),
         }
     }
 }
 
-impl Slot {
-    fn rows(&self) -> u64 {
-        const UNEXPANDED_ROWS: u64 = 2;
-        if self.expanded {
-            self.max_rows.at_least(UNEXPANDED_ROWS)
-        } else {
-            UNEXPANDED_ROWS
+// Maps each item to the tile, row and index where it was first found
+type ItemIndex = BTreeMap<ItemUID, (TileID, usize, usize)>;
+
+fn index_items<T>(
+    index: &mut ItemIndex,
+    tile_id: TileID,
+    rows: &[Vec<T>],
+    item_uid: impl Fn(&T) -> ItemUID,
+) {
+    for (row, items) in rows.iter().enumerate() {
+        for (i, item) in items.iter().enumerate() {
+            index.entry(item_uid(item)).or_insert((tile_id, row, i));
         }
     }
+}
 
-    fn clear(&mut self) {
-        self.tile_ids.clear();
-        self.tiles.clear();
-        self.tile_metas.clear();
+fn estimate_field_size(field: &Field) -> usize {
+    match field {
+        Field::String(value) => value.capacity(),
+        Field::ItemLink(ItemLink { title, .. }) => title.capacity(),
+        Field::Vec(fields) => {
+            fields.capacity() * std::mem::size_of::<Field>()
+                + fields.iter().map(estimate_field_size).sum::<usize>()
+        }
+        _ => 0,
     }
+}
 
-    fn inflate(&mut self, config: &mut Config, cx: &mut Context) {
-        for tile_id in config.request_tiles(cx.view_interval) {
-            config
-                .data_source
-                .fetch_slot_tile(&self.entry_id, tile_id, false);
-            self.tile_ids.push(tile_id);
-            self.tiles.insert(tile_id, None);
-        }
+fn estimate_meta_tile_size(tile: &SlotMetaTileData) -> usize {
+    let items = tile.items.iter().flatten().map(|item| {
+        std::mem::size_of::<ItemMeta>()
+            + item.title.capacity()
+            + item.fields.capacity() * std::mem::size_of::<(FieldID, Field, Option<Color32>)>()
+            + item
+                .fields
+                .iter()
+                .map(|(_, field, _)| estimate_field_size(field))
+                .sum::<usize>()
+    });
+    tile.items.len() * std::mem::size_of::<Vec<ItemMeta>>() + items.sum::<usize>()
+}
+
+// The area between a segment of the utilization curve (in screen space) and
+// the threshold line where the curve is below the threshold, as a convex
+// polygon. Screen y grows downward, so "below" means a larger y.
+fn shade_below_threshold(p1: Pos2, p2: Pos2, threshold_y: f32) -> Option<Vec<Pos2>> {
+    if p2.x <= p1.x {
+        return None;
+    }
+    let on_line = |x| Pos2::new(x, threshold_y);
+    let crossing = || {
+        let ratio = (threshold_y - p1.y) / (p2.y - p1.y);
+        on_line(p1.x + ratio * (p2.x - p1.x))
+    };
+    match (p1.y > threshold_y, p2.y > threshold_y) {
+        (true, true) => Some(vec![p1, p2, on_line(p2.x), on_line(p1.x)]),
+        (true, false) => Some(vec![p1, crossing(), on_line(p1.x)]),
+        (false, true) => Some(vec![crossing(), p2, on_line(p2.x)]),
+        (false, false) => None,
     }
+}
 
-    fn fetch_meta_tile(
-        &mut self,
-        tile_id: TileID,
-        config: &mut Config,
-    ) -> Option<&SlotMetaTileData> {
-        self.tile_metas
-            .entry(tile_id)
-            .or_insert_with(|| {
-                config
-                    .data_source
-                    .fetch_slot_meta_tile(&self.entry_id, tile_id, false);
-                None
-            })
-            .as_ref()
+// Position of a field in the user's preferred order, with unlisted fields
+// after all listed ones
+fn field_rank(order: &[String], name: &str) -> usize {
+    order.iter().position(|x| x == name).unwrap_or(order.len())
+}
+
+// Sorts an item's fields into the user's preferred order (see
+// Context::field_order), keeping the source order otherwise
+fn ordered_fields<'a>(
+    fields: &'a [(FieldID, Field, Option<Color32>)],
+    schema: &FieldSchema,
+    order: &[String],
+) -> Vec<&'a (FieldID, Field, Option<Color32>)> {
+    let mut result: Vec<_> = fields.iter().collect();
+    if !order.is_empty() {
+        // Stable, so unlisted fields keep their relative order
+        result.sort_by_key(|(field_id, _, _)| {
+            schema
+                .get_name(*field_id)
+                .map_or(order.len(), |name| field_rank(order, name))
+        });
     }
+    result
+}
 
-    #[allow(clippy::too_many_arguments)]
-    fn render_tile(
-        &mut self,
-        tile_index: usize,
-        rows: u64,
-        mut hover_pos: Option<Pos2>,
-        ui: &mut egui::Ui,
-        rect: Rect,
-        viewport: Rect,
-        config: &mut Config,
-        cx: &mut Context,
-    ) -> Option<Pos2> {
-        // Hack: can't pass this as an argument because it aliases self.
-        let tile_id = self.tile_ids[tile_index];
-        let tile = self.tiles.get(&tile_id).unwrap();
+// Item links only show their title on screen, so spell out where they point
+// for text that's read somewhere else
+fn with_link_entries(field: &Field) -> Field {
+    match field {
+        Field::ItemLink(link) => Field::String(format!(
+            "{} (entry {})",
+            link.title,
+            crate::data::EntryIDSlug(&link.entry_id)
+        )),
+        Field::Vec(fields) => Field::Vec(fields.iter().map(with_link_entries).collect()),
+        _ => field.clone(),
+    }
+}
 
-        if !tile.is_some() {
-            // Tile hasn't finished loading.
-            return hover_pos;
+// The fields shown in the details window, one per line
+fn item_details_text(item_meta: &ItemMeta, field_schema: &FieldSchema, cx: &Context) -> String {
+    let mut lines = vec![format!("Title: {}", item_meta.title)];
+    if cx.debug {
+        lines.push(format!("Item UID: {}", item_meta.item_uid.0));
+    }
+    for (field_id, field, _) in ordered_fields(&item_meta.fields, field_schema, &cx.field_order) {
+        let name = field_schema.get_name(*field_id).unwrap();
+        if !cx.hidden_fields.contains(name) {
+            let field = with_link_entries(field);
+            lines.push(FieldWithName(name, &field, cx).to_string());
         }
-        let tile = tile.as_ref().unwrap();
+    }
+    lines.join("\n")
+}
 
-        if !cx.view_interval.overlaps(tile_id.0) {
-            return hover_pos;
+// Splits a search query into words that must appear and words that must not
+// (those prefixed with -). A leading dash can be kept as part of a word by
+// escaping it, as in \-foo.
+fn parse_search_terms(query: &str) -> (Vec<String>, Vec<String>) {
+    let mut positive = Vec::new();
+    let mut negative = Vec::new();
+    for word in query.split_whitespace() {
+        if let Some(rest) = word.strip_prefix("\\-") {
+            positive.push(format!("-{rest}"));
+        } else if let Some(rest) = word.strip_prefix('-').filter(|rest| !rest.is_empty()) {
+            negative.push(rest.to_owned());
+        } else {
+            positive.push(word.to_owned());
         }
+    }
+    (positive, negative)
+}
 
-        // Track which item, if any, we're interacting with
-        let mut interact_item = None;
-
-        for (row, row_items) in tile.items.iter().enumerate() {
-            // Need to reverse the rows because we're working in screen space
-            let irow = rows - (row as u64) - 1;
+// The next step in the sequence 1, 2, 5, 10, 20, 50, ... (saturating)
+fn next_duration_step(step: i64) -> i64 {
+    let mut decade: i64 = 1;
+    while let Some(next) = decade.checked_mul(10).filter(|next| *next <= step) {
+        decade = next;
+    }
+    let factor = match step / decade {
+        1 => 2,
+        2 => 5,
+        _ => 10,
+    };
+    decade.saturating_mul(factor)
+}
+
+// The bucket of a duration (in ns) on a log scale, as a half-open range
+// between consecutive steps of next_duration_step. Zero gets its own bucket.
+fn duration_bucket(duration_ns: i64) -> (i64, i64) {
+    if duration_ns < 1 {
+        return (0, 1);
+    }
+    let mut lo = 1;
+    loop {
+        let hi = next_duration_step(lo);
+        if duration_ns < hi || hi == i64::MAX {
+            return (lo, hi);
+        }
+        lo = hi;
+    }
+}
+
+// Counts the durations in each bucket, from the shortest to the longest (with
+// empty buckets in between, so the chart's scale is even)
+fn duration_histogram(durations: &[i64]) -> Vec<((i64, i64), usize)> {
+    let mut counts = BTreeMap::new();
+    for duration in durations {
+        *counts.entry(duration_bucket(*duration)).or_insert(0) += 1;
+    }
+    let (Some(first), Some(last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Vec::new();
+    };
+    let mut result = Vec::new();
+    let mut bucket = *first;
+    loop {
+        result.push((bucket, counts.get(&bucket).copied().unwrap_or(0)));
+        if bucket == *last {
+            return result;
+        }
+        bucket = (bucket.1, next_duration_step(bucket.1));
+    }
+}
+
+// Times to mark on the ruler: round numbers, roughly one per 100 points (but
+// at most 10 in total). On a log axis, powers of ten after the origin.
+fn ruler_ticks(axis: TimeAxis, width: f32) -> Vec<Timestamp> {
+    let view = axis.view;
+    if let Some(origin) = axis.log_origin {
+        let mut ticks = Vec::new();
+        let mut offset = 1i64;
+        while let Some(time) = origin.0.checked_add(offset) {
+            if time > view.stop.0 {
+                break;
+            }
+            if time >= view.start.0 {
+                ticks.push(Timestamp(time));
+            }
+            let Some(next) = offset.checked_mul(10) else {
+                break;
+            };
+            offset = next;
+        }
+        return ticks;
+    }
+
+    let duration = view.duration_ns();
+    if duration <= 0 {
+        return Vec::new();
+    }
+    let count = (width / 100.0).clamp(2.0, 10.0) as i64;
+    let min_step = (duration / count).max(1);
+    let mut magnitude = 1i64;
+    while magnitude <= min_step / 10 {
+        magnitude *= 10;
+    }
+    let step = [1, 2, 5, 10]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= min_step)
+        .unwrap();
+    let first = view.start.0.div_euclid(step) * step;
+    (0..)
+        .map(|i| first + i * step)
+        .skip_while(|time| *time < view.start.0)
+        .take_while(|time| *time <= view.stop.0)
+        .map(Timestamp)
+        .collect()
+}
+
+// Parses a range of integers, min..max (inclusive), where either end may be
+// left out. Each end may have a size unit, as in 1MB..1GiB.
+fn parse_search_range(query: &str) -> Option<(Option<i64>, Option<i64>)> {
+    let (min, max) = query.split_once("..")?;
+    let parse = |s: &str| {
+        let s = s.trim();
+        if s.is_empty() {
+            Some(None)
+        } else {
+            parse_size(s).map(Some)
+        }
+    };
+    Some((parse(min)?, parse(max)?))
+}
+
+// Parses a whole number with an optional (case-insensitive) size unit: B, KB,
+// MB, GB, TB in powers of 1000, or KiB, MiB, GiB, TiB in powers of 1024
+fn parse_size(s: &str) -> Option<i64> {
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: i64 = number.trim_end().parse().ok()?;
+    let scale: i64 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        _ => return None,
+    };
+    number.checked_mul(scale)
+}
+
+// The query for a range search, or nothing if both ends are left out
+fn format_search_range(min: &str, max: &str) -> String {
+    let (min, max) = (min.trim(), max.trim());
+    if min.is_empty() && max.is_empty() {
+        String::new()
+    } else {
+        format!("{min}..{max}")
+    }
+}
+
+// Most sets of open profiles whose views are remembered
+const MAX_SAVED_VIEWS: usize = 50;
+
+// Saves the view for a set of open profiles, dropping the least recently
+// saved views beyond MAX_SAVED_VIEWS. Views saved before the order was kept
+// are dropped first.
+fn remember_view(
+    views: &mut BTreeMap<String, (Interval, IntervalState)>,
+    order: &mut VecDeque<String>,
+    key: String,
+    view: (Interval, IntervalState),
+) {
+    order.retain(|k| *k != key);
+    order.push_back(key.clone());
+    views.insert(key, view);
+    while views.len() > MAX_SAVED_VIEWS {
+        let unordered = views.keys().find(|k| !order.contains(k)).cloned();
+        let Some(oldest) = unordered.or_else(|| order.pop_front()) else {
+            break;
+        };
+        views.remove(&oldest);
+    }
+    order.retain(|k| views.contains_key(k));
+}
+
+// Fits a view saved in an earlier session into the current profiles, which
+// may have changed since. Returns the view, and its history if that could be
+// kept too, or None if the view misses the profiles entirely.
+fn clamp_saved_view(
+    (interval, history): &(Interval, IntervalState),
+    total: Interval,
+) -> Option<(Interval, Option<IntervalState>)> {
+    let clamp = |level: Interval| {
+        let level = level.intersection(total);
+        (level.duration_ns() > 0).then_some(level)
+    };
+    let interval = clamp(*interval)?;
+    let levels: Option<Vec<_>> = history.levels.iter().map(|level| clamp(*level)).collect();
+    let history = levels
+        .filter(|levels| {
+            history.index < levels.len()
+                && levels.len() == history.origins.len()
+                && levels[history.index] == interval
+        })
+        .map(|levels| IntervalState {
+            levels,
+            origins: history.origins.clone(),
+            index: history.index,
+        });
+    Some((interval, history))
+}
+
+// Lines up the fields of several items (from the same profile) for
+// comparison, one entry per field in any of them. Each entry has the field of
+// every item, in order, or None where an item lacks it.
+fn aligned_fields<'a>(
+    items: &[&'a [(FieldID, Field, Option<Color32>)]],
+    schema: &FieldSchema,
+    order: &[String],
+) -> Vec<(FieldID, Vec<Option<&'a Field>>)> {
+    let find = |fields: &'a [(FieldID, Field, Option<Color32>)], id: FieldID| {
+        fields.iter().find(|(x, _, _)| *x == id).map(|(_, f, _)| f)
+    };
+    let mut result: Vec<(FieldID, Vec<_>)> = Vec::new();
+    for fields in items {
+        for (id, _, _) in ordered_fields(fields, schema, order) {
+            if !result.iter().any(|(x, _)| x == id) {
+                let row = items.iter().map(|fields| find(fields, *id)).collect();
+                result.push((*id, row));
+            }
+        }
+    }
+    if !order.is_empty() {
+        result.sort_by_key(|(id, _)| {
+            schema
+                .get_name(*id)
+                .map_or(order.len(), |name| field_rank(order, name))
+        });
+    }
+    result
+}
+
+// Resamples a utilization curve (linear between points) into the given number
+// of samples evenly spaced across the screen. Each sample is the average of
+// the curve over its bucket, so that short spikes aren't lost when zoomed out.
+// Segments that go back in time are ignored.
+fn resample_utilization(points: &[UtilPoint], axis: TimeAxis, samples: usize) -> Vec<UtilPoint> {
+    if samples == 0 || points.len() < 2 || axis.view.duration_ns() <= 0 {
+        return points.to_vec();
+    }
+
+    let util_at = |p: &UtilPoint, q: &UtilPoint, time: f64| {
+        let ratio = (time - p.time.0 as f64) / (q.time.0 - p.time.0) as f64;
+        p.util as f64 + (q.util - p.util) as f64 * ratio
+    };
+
+    let mut result = Vec::with_capacity(samples);
+    let mut first = 0;
+    for i in 0..samples {
+        let start = axis.lerp(i as f32 / samples as f32).0 as f64;
+        let stop = axis.lerp((i + 1) as f32 / samples as f32).0 as f64;
+
+        // Skip segments that end before this bucket
+        while first + 2 < points.len() && (points[first + 1].time.0 as f64) <= start {
+            first += 1;
+        }
+
+        let mut area = 0.0;
+        let mut covered = 0.0;
+        for segment in points[first..].windows(2) {
+            let (p, q) = (&segment[0], &segment[1]);
+            if p.time.0 as f64 >= stop {
+                break;
+            }
+            if q.time <= p.time {
+                continue;
+            }
+            let a = start.max(p.time.0 as f64);
+            let b = stop.min(q.time.0 as f64);
+            if b <= a {
+                continue;
+            }
+            area += (b - a) * (util_at(p, q, a) + util_at(p, q, b)) / 2.0;
+            covered += b - a;
+        }
+        if covered > 0.0 {
+            result.push(UtilPoint {
+                time: Timestamp(((start + stop) / 2.0) as i64),
+                util: (area / covered) as f32,
+            });
+        }
+    }
+    result
+}
+
+// Horizontal extent of an item within the view, as fractions of the view's
+// width, or None if the item isn't visible. Some versions of Legion generate
+// negative intervals: those items are skipped. Zero-length items are kept, so
+// that min_item_width can make them visible.
+fn item_view_span(axis: TimeAxis, item: Interval) -> Option<(f32, f32)> {
+    let visible = match item.duration_ns() {
+        ..=-1 => false,
+        0 => axis.view.contains(item.start),
+        _ => axis.view.overlaps(item),
+    };
+    if !visible {
+        return None;
+    }
+
+    // Note: the interval is EXCLUSIVE. This turns out to be what
+    // we want here, because in screen coordinates interval.stop
+    // is the BEGINNING of the interval.stop nanosecond.
+    let start = axis.unlerp(item.start).at_least(0.0);
+    let stop = axis.unlerp(item.stop).at_most(1.0);
+    Some((start, stop))
+}
+
+// Color to draw an item in, taking into account coloring by a field, the
+// selection and search results
+fn item_color(
+    ui: &egui::Ui,
+    item: &Item,
+    item_meta: Option<&ItemMeta>,
+    config: &mut Config,
+    cx: &Context,
+) -> Color32 {
+    let highlight = config.items_selected.contains_key(&item.item_uid)
+        || item_meta.is_some_and(|meta| cx.linked_titles.contains(&meta.title));
+
+    let mut color = item.color;
+    if let Some(field_id) = config.color_by {
+        // Until the metadata loads, use the item's own color
+        let title_id = config.search_state.title_field;
+        let key = item_meta.and_then(|meta| ColorKey::new(meta, field_id, title_id));
+        if let Some(key) = key {
+            if let ColorKey::Number(value) = key {
+                let range = config.color_by_range.get_or_insert((value, value));
+                if value < range.0 || value > range.1 {
+                    *range = (range.0.min(value), range.1.max(value));
+                    // Items already drawn used the old range
+                    cx.request_repaint(ui.ctx());
+                }
+            }
+            color = key.color(config.color_by_range);
+        }
+    }
+    let colors = &cx.highlight_colors;
+    if highlight {
+        color = colors.selected;
+    } else if config.search_state.is_active() {
+        if config.search_state.result_set.contains(&item.item_uid) {
+            color = colors.search_match;
+        } else {
+            color = color.gamma_multiply(colors.dim_factor);
+        }
+    }
+    color
+}
+
+// Draws a row with many more items than pixels by blending the colors of the
+// items in each pixel column, weighted by how much of the column they cover.
+// Columns that are only partly covered are drawn partly transparent. Returns
+// the item under hover_pos (the last one drawn there), with its column.
+fn render_dense_row(
+    ui: &mut egui::Ui,
+    row_rect: Rect,
+    row_items: &[Item],
+    row_metas: Option<&Vec<ItemMeta>>,
+    hover_pos: Option<Pos2>,
+    config: &mut Config,
+    cx: &Context,
+) -> Option<(usize, Rect)> {
+    let width = row_rect.width();
+    let mut columns = vec![(Rgba::TRANSPARENT, 0.0_f32); width.ceil().max(0.0) as usize];
+    let hover_column = hover_pos.map(|h| (h.x - row_rect.left()).floor().max(0.0) as usize);
+    let mut hovered = None;
+    for (item_idx, item) in row_items.iter().enumerate() {
+        let Some((start, stop)) = item_view_span(cx.time_axis(), item.interval) else {
+            continue;
+        };
+        let x0 = start * width;
+        let x1 = (stop * width).max(x0 + cx.min_item_width.at_most(1.0));
+        let first = x0.floor() as usize;
+        let last = (x1.ceil() as usize).clamp(first + 1, columns.len().max(first + 1));
+        if hover_column.is_some_and(|c| c >= first && c < last) {
+            hovered = Some(item_idx);
+        }
+
+        let item_meta = row_metas.and_then(|metas| metas.get(item_idx));
+        let color = Rgba::from(item_color(ui, item, item_meta, config, cx));
+        for (i, column) in columns.iter_mut().enumerate().take(last).skip(first) {
+            let overlap = x1.min(i as f32 + 1.0) - x0.max(i as f32);
+            if overlap > 0.0 {
+                column.0 = column.0 + color * overlap;
+                column.1 += overlap;
+            }
+        }
+    }
+
+    let column_rect = |i: usize| {
+        let left = row_rect.left() + i as f32;
+        Rect::from_x_y_ranges(
+            left..=(left + 1.0).at_most(row_rect.right()),
+            row_rect.y_range(),
+        )
+    };
+    for (i, (sum, coverage)) in columns.iter().enumerate() {
+        if *coverage <= 0.0 {
+            continue;
+        }
+        let color = Color32::from(*sum * (1.0 / coverage)).gamma_multiply(coverage.at_most(1.0));
+        ui.painter().rect_filled(column_rect(i), 0.0, color);
+    }
+
+    let column = hover_column?.min(columns.len().saturating_sub(1));
+    hovered.map(|item_idx| (item_idx, column_rect(column)))
+}
+
+// Time within the view covered by at least one of the intervals, as sorted,
+// disjoint intervals
+fn busy_intervals(view: Interval, intervals: impl Iterator<Item = Interval>) -> Vec<Interval> {
+    let mut intervals: Vec<_> = intervals
+        .filter(|i| i.duration_ns() > 0 && view.overlaps(*i))
+        .map(|i| i.intersection(view))
+        .collect();
+    intervals.sort();
+    let mut result: Vec<Interval> = Vec::new();
+    for interval in intervals {
+        match result.last_mut() {
+            Some(last) if interval.start <= last.stop => {
+                last.stop = last.stop.max(interval.stop);
+            }
+            _ => result.push(interval),
+        }
+    }
+    result
+}
+
+// The gaps between busy intervals (as returned by busy_intervals)
+fn idle_intervals(view: Interval, busy: &[Interval]) -> Vec<Interval> {
+    let mut result = Vec::new();
+    let mut start = view.start;
+    for interval in busy {
+        if interval.start > start {
+            result.push(Interval::new(start, interval.start));
+        }
+        start = interval.stop;
+    }
+    if start < view.stop {
+        result.push(Interval::new(start, view.stop));
+    }
+    result
+}
+
+// Totals for the items in view of one or more slots
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ItemStats {
+    // Time in view covered by at least one item, summed over slots
+    busy_ns: i64,
+    // Of each item, sorted once all are in (see sort)
+    durations: Vec<i64>,
+}
+
+impl ItemStats {
+    fn new(view: Interval, intervals: Vec<Interval>) -> Self {
+        let busy_ns = busy_intervals(view, intervals.iter().copied())
+            .iter()
+            .map(|i| i.duration_ns())
+            .sum();
+        let durations = intervals.iter().map(|i| i.duration_ns()).collect();
+        Self { busy_ns, durations }
+    }
+
+    fn merge(&mut self, other: ItemStats) {
+        self.busy_ns += other.busy_ns;
+        self.durations.extend(other.durations);
+    }
+
+    // Needed for the median, after the last merge
+    fn sort(&mut self) {
+        self.durations.sort_unstable();
+    }
+
+    fn mean_ns(&self) -> Option<i64> {
+        let count = self.durations.len() as i64;
+        (count > 0).then(|| self.durations.iter().sum::<i64>() / count)
+    }
+
+    fn median_ns(&self) -> Option<i64> {
+        let count = self.durations.len();
+        match count {
+            0 => None,
+            _ if count % 2 == 1 => Some(self.durations[count / 2]),
+            _ => Some((self.durations[count / 2 - 1] + self.durations[count / 2]) / 2),
+        }
+    }
+}
+
+// The view, the grouping (by processor if true), and a hash of the slots
+// shown (after filters, solo/mute and expansion) with the number of meta tiles
+// loaded for each, that the statistics were computed from
+type StatsKey = (Interval, bool, u64);
+
+fn sort_stats(rows: &mut [(String, ItemStats)], column: StatsColumn, descending: bool) {
+    match column {
+        StatsColumn::Name => rows.sort_by(|a, b| a.0.cmp(&b.0)),
+        StatsColumn::Busy => rows.sort_by_key(|(_, s)| s.busy_ns),
+        StatsColumn::Count => rows.sort_by_key(|(_, s)| s.durations.len()),
+        StatsColumn::Mean => rows.sort_by_key(|(_, s)| s.mean_ns()),
+        StatsColumn::Median => rows.sort_by_key(|(_, s)| s.median_ns()),
+    }
+    if descending {
+        rows.reverse();
+    }
+}
+
+// Items narrower than this (in points) are too small to hold a title
+const ITEM_TITLE_MIN_WIDTH: f32 = 40.0;
+
+// Items narrower than this (in points) get no borders, since when zoomed out
+// the borders would cover the items themselves
+const ITEM_BORDER_MIN_WIDTH: f32 = 4.0;
+
+// Black or white, whichever stands out against the color
+fn contrasting_color(color: Color32) -> Color32 {
+    if Rgba::from(color).intensity() > 0.25 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+// Fields with this name hold source locations ("file:line"), which can be
+// opened in an editor (or copied, on the web)
+const PROVENANCE_FIELD: &str = "Provenance";
+
+#[cfg(not(target_arch = "wasm32"))]
+const SOURCE_LOCATION_BUTTON: &str = "Open";
+#[cfg(target_arch = "wasm32")]
+const SOURCE_LOCATION_BUTTON: &str = "Copy";
+
+fn parse_source_location(s: &str) -> Option<(&str, u32)> {
+    let (file, line) = s.trim().rsplit_once(':')?;
+    if file.is_empty() {
+        return None;
+    }
+    Some((file, line.parse().ok()?))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open_source_location(_ui: &mut egui::Ui, file: &str, line: u32) {
+    let Ok(editor) = std::env::var("EDITOR") else {
+        log::error!("unable to open {file}:{line}: EDITOR is not set");
+        return;
+    };
+    // Most editors (vi, emacs, nano, ...) accept +LINE to jump to a line
+    let result = std::process::Command::new(&editor)
+        .arg(format!("+{line}"))
+        .arg(file)
+        .spawn();
+    if let Err(e) = result {
+        log::error!("unable to open {file}:{line} with {editor:?}: {e}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_source_location(ui: &mut egui::Ui, file: &str, line: u32) {
+    ui.output_mut(|o| o.copied_text = format!("{file}:{line}"));
+}
+
+// The value of an item's color-by field, as far as picking a color goes
+enum ColorKey<'a> {
+    Category(&'a str),
+    Number(f64),
+}
+
+impl<'a> ColorKey<'a> {
+    fn new(item: &'a ItemMeta, field_id: FieldID, title_id: FieldID) -> Option<Self> {
+        if field_id == title_id {
+            return Some(ColorKey::Category(&item.title));
+        }
+        let (_, field, _) = item.fields.iter().find(|(id, _, _)| *id == field_id)?;
+        match field {
+            Field::I64(value) => Some(ColorKey::Number(*value as f64)),
+            Field::U64(value) => Some(ColorKey::Number(*value as f64)),
+            Field::String(value) => Some(ColorKey::Category(value)),
+            Field::Interval(value) => Some(ColorKey::Number(value.duration_ns() as f64)),
+            Field::ItemLink(ItemLink { title, .. }) => Some(ColorKey::Category(title)),
+            Field::Vec(..) | Field::Empty => None,
+        }
+    }
+
+    fn color(&self, range: Option<(f64, f64)>) -> Color32 {
+        match *self {
+            ColorKey::Category(name) => {
+                const PALETTE: [Color32; 10] = [
+                    Color32::from_rgb(0x4e, 0x79, 0xa7),
+                    Color32::from_rgb(0xf2, 0x8e, 0x2b),
+                    Color32::from_rgb(0xe1, 0x57, 0x59),
+                    Color32::from_rgb(0x76, 0xb7, 0xb2),
+                    Color32::from_rgb(0x59, 0xa1, 0x4f),
+                    Color32::from_rgb(0xed, 0xc9, 0x48),
+                    Color32::from_rgb(0xb0, 0x7a, 0xa1),
+                    Color32::from_rgb(0xff, 0x9d, 0xa7),
+                    Color32::from_rgb(0x9c, 0x75, 0x5f),
+                    Color32::from_rgb(0xba, 0xb0, 0xac),
+                ];
+                let mut hasher = DefaultHasher::new();
+                name.hash(&mut hasher);
+                PALETTE[(hasher.finish() % PALETTE.len() as u64) as usize]
+            }
+            ColorKey::Number(value) => {
+                // Gradient from dark blue (smallest) to yellow (largest)
+                let (min, max) = range.unwrap_or((value, value));
+                let t = if max > min {
+                    ((value - min) / (max - min)) as f32
+                } else {
+                    0.5
+                };
+                let low = Rgba::from(Color32::from_rgb(0x44, 0x01, 0x54));
+                let high = Rgba::from(Color32::from_rgb(0xfd, 0xe7, 0x25));
+                Color32::from(low * (1.0 - t) + high * t)
+            }
+        }
+    }
+}
+
+fn tile_requests(entry_id: &EntryID, tile_ids: &[TileID]) -> Vec<TileRequest> {
+    tile_ids
+        .iter()
+        .map(|tile_id| TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id: *tile_id,
+        })
+        .collect()
+}
+
+// Paints the portion of `rect` covered by a tile that failed to load and
+// shows the error when hovered. Returns true if the user clicked to retry.
+fn show_tile_error(
+    ui: &mut egui::Ui,
+    rect: Rect,
+    tile_interval: Interval,
+    error: &DataSourceError,
+    hover_pos: &mut Option<Pos2>,
+    cx: &Context,
+) -> bool {
+    if !cx.view_interval.overlaps(tile_interval) {
+        return false;
+    }
+
+    let start = cx.time_axis().unlerp(tile_interval.start).at_least(0.0);
+    let stop = cx.time_axis().unlerp(tile_interval.stop).at_most(1.0);
+    let error_rect = Rect::from_min_max(
+        rect.lerp_inside(Vec2::new(start, 0.0)),
+        rect.lerp_inside(Vec2::new(stop, 1.0)),
+    );
+    ui.painter()
+        .rect(error_rect, 0.0, Color32::RED, Stroke::NONE);
+
+    if !hover_pos.is_some_and(|h| error_rect.contains(h)) {
+        return false;
+    }
+    *hover_pos = None;
+
+    ui.show_tooltip_ui("tile_error_tooltip", &error_rect, |ui| {
+        ui.label(RichText::new("Failed to load data").strong());
+        ui.label(error.to_string());
+        ui.label("(Click to retry.)");
+    });
+    ui.input(|i| i.pointer.any_click() && i.pointer.primary_released())
+}
+
+// Shades the part of rect covered by a tile that hasn't arrived yet, so it
+// can be told apart from a region with no data.
+fn show_tile_loading(ui: &mut egui::Ui, rect: Rect, tile_interval: Interval, cx: &Context) {
+    if !cx.view_interval.overlaps(tile_interval) {
+        return;
+    }
+
+    let start = cx.time_axis().unlerp(tile_interval.start).at_least(0.0);
+    let stop = cx.time_axis().unlerp(tile_interval.stop).at_most(1.0);
+    let loading_rect = Rect::from_min_max(
+        rect.lerp_inside(Vec2::new(start, 0.0)),
+        rect.lerp_inside(Vec2::new(stop, 1.0)),
+    );
+    let visuals = &ui.style().visuals;
+    let color = visuals.weak_text_color();
+    ui.painter()
+        .rect(loading_rect, 0.0, color.linear_multiply(0.1), Stroke::NONE);
+
+    // Skip the label if it wouldn't fit
+    const LOADING_LABEL_MIN_WIDTH: f32 = 60.0;
+    if loading_rect.width() >= LOADING_LABEL_MIN_WIDTH {
+        ui.painter().with_clip_rect(loading_rect).text(
+            loading_rect.center(),
+            Align2::CENTER_CENTER,
+            "loading…",
+            TextStyle::Small.resolve(ui.style()),
+            color,
+        );
+    }
+}
+
+impl Slot {
+    fn rows(&self) -> u64 {
+        const UNEXPANDED_ROWS: u64 = 2;
+        if self.expanded {
+            self.max_rows.at_least(UNEXPANDED_ROWS)
+        } else {
+            UNEXPANDED_ROWS
+        }
+    }
+
+    fn clear(&mut self) {
+        self.tile_ids.clear();
+        self.tiles.clear();
+        self.tile_metas.clear();
+        self.item_index.clear();
+        self.meta_index.clear();
+    }
+
+    // Stores a tile that arrived, if we still want it
+    fn receive_tile(&mut self, tile_id: TileID, tile: Result<SlotTileData>) {
+        let Some(entry) = self.tiles.get_mut(&tile_id) else {
+            return;
+        };
+        if let Ok(tile) = &tile {
+            index_items(&mut self.item_index, tile_id, &tile.items, |item| {
+                item.item_uid
+            });
+        }
+        *entry = Some(tile);
+    }
+
+    fn receive_meta_tile(&mut self, tile_id: TileID, tile: Result<SlotMetaTileData>) {
+        let Some(entry) = self.tile_metas.get_mut(&tile_id) else {
+            return;
+        };
+        if let Ok(tile) = &tile {
+            index_items(&mut self.meta_index, tile_id, &tile.items, |item| {
+                item.item_uid
+            });
+        }
+        *entry = Some(tile);
+    }
+
+    // Returns the loaded tile containing the item, and the item's row in it
+    fn lookup_item(&self, item_uid: ItemUID) -> Option<(&SlotTileData, usize)> {
+        let (tile_id, row, _) = self.item_index.get(&item_uid)?;
+        match self.tiles.get(tile_id) {
+            Some(Some(Ok(tile))) => Some((tile, *row)),
+            _ => None,
+        }
+    }
+
+    fn lookup_item_meta(&self, item_uid: ItemUID) -> Option<(&SlotMetaTileData, usize, usize)> {
+        let (tile_id, row, index) = self.meta_index.get(&item_uid)?;
+        match self.tile_metas.get(tile_id) {
+            Some(Some(Ok(tile))) => Some((tile, *row, *index)),
+            _ => None,
+        }
+    }
+
+    fn inflate(&mut self, config: &mut Config, cx: &mut Context) {
+        let tile_ids = config.request_tiles(cx.view_interval);
+        let requests = tile_requests(&self.entry_id, &tile_ids);
+        config.data_source.fetch_slot_tiles(&requests, false);
+        for tile_id in tile_ids {
+            self.tile_ids.push(tile_id);
+            self.tiles.insert(tile_id, None);
+        }
+    }
+
+    fn retry_tile(&mut self, tile_id: TileID, config: &mut Config) {
+        config
+            .data_source
+            .fetch_slot_tile(&self.entry_id, tile_id, false);
+        self.tiles.insert(tile_id, None);
+    }
+
+    // Statistics of the items in loaded meta tiles that overlap the view
+    fn item_stats(&self, view: Interval) -> ItemStats {
+        // Items that span tiles show up once per tile
+        let mut seen = BTreeSet::new();
+        let intervals = self
+            .tile_metas
+            .values()
+            .flatten()
+            .flatten()
+            .flat_map(|tile| tile.items.iter().flatten())
+            .filter(|item| item.original_interval.overlaps(view) && seen.insert(item.item_uid))
+            .map(|item| item.original_interval)
+            .collect();
+        ItemStats::new(view, intervals)
+    }
+
+    // Busy time within the view across all rows, or None until every tile
+    // in the view has loaded
+    fn busy_intervals(&self, view: Interval) -> Option<Vec<Interval>> {
+        if !self.expanded || self.tile_ids.is_empty() {
+            return None;
+        }
+        let mut tiles = Vec::new();
+        for tile_id in &self.tile_ids {
+            match self.tiles.get(tile_id)? {
+                Some(Ok(tile)) => tiles.push(tile),
+                _ => return None,
+            }
+        }
+        let items = tiles
+            .into_iter()
+            .flat_map(|tile| tile.items.iter().flatten())
+            .map(|item| item.interval);
+        Some(busy_intervals(view, items))
+    }
+
+    fn render_idle(&self, ui: &mut egui::Ui, rect: Rect, cx: &Context) {
+        let Some(busy) = self.busy_intervals(cx.view_interval) else {
+            return;
+        };
+        let color = ui.visuals().weak_text_color().gamma_multiply(0.15);
+        for idle in idle_intervals(cx.view_interval, &busy) {
+            let min = rect.lerp_inside(Vec2::new(cx.time_axis().unlerp(idle.start), 0.0));
+            let max = rect.lerp_inside(Vec2::new(cx.time_axis().unlerp(idle.stop), 1.0));
+            ui.painter()
+                .rect(Rect::from_min_max(min, max), 0.0, color, Stroke::NONE);
+        }
+    }
+
+    // Finds an item in the loaded tiles, returning its row (reversed, as in
+    // ItemLocator::irow) and full interval
+    fn find_item(&self, item_uid: ItemUID) -> Option<(usize, Interval)> {
+        // Metadata has the item's original interval, so check it first
+        if let Some((tile, row, index)) = self.lookup_item_meta(item_uid) {
+            let item = &tile.items[row][index];
+            return Some((tile.items.len() - row - 1, item.original_interval));
+        }
+
+        // Otherwise, items may be split across tiles, so reassemble them
+        let mut result: Option<(usize, Interval)> = None;
+        for tile in self.tiles.values() {
+            let Some(Ok(tile)) = tile else {
+                continue;
+            };
+            for (row, items) in tile.items.iter().enumerate() {
+                for item in items.iter().filter(|item| item.item_uid == item_uid) {
+                    let irow = tile.items.len() - row - 1;
+                    result = Some(match result {
+                        Some((_, interval)) => (irow, interval.union(item.interval)),
+                        None => (irow, item.interval),
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    fn render_gridlines(ui: &mut egui::Ui, rect: Rect, cx: &Context) {
+        if !cx.show_ruler {
+            return;
+        }
+        let axis = cx.time_axis();
+        let color = ui.visuals().widgets.noninteractive.bg_stroke.color;
+        let stroke = Stroke::new(1.0, color.linear_multiply(0.5));
+        for tick in ruler_ticks(axis, rect.width()) {
+            let x = rect.min.x + axis.unlerp(tick) * rect.width();
+            ui.painter().vline(x, rect.y_range(), stroke);
+        }
+    }
+
+    fn render_row_guides(rows: u64, ui: &mut egui::Ui, rect: Rect, viewport: Rect, cx: &Context) {
+        if cx.row_guides == RowGuides::None {
+            return;
+        }
+
+        let visuals = ui.style().visuals.clone();
+        for irow in 0..rows {
+            let row_min = rect.lerp_inside(Vec2::new(0.0, irow as f32 / rows as f32));
+            let row_max = rect.lerp_inside(Vec2::new(1.0, (irow + 1) as f32 / rows as f32));
+
+            // Cull if out of bounds
+            // Note: need to shift by rect.min to get to viewport space
+            if row_max.y - rect.min.y < viewport.min.y {
+                continue;
+            } else if row_min.y - rect.min.y > viewport.max.y {
+                break;
+            }
+
+            let row_rect = Rect::from_min_max(row_min, row_max);
+            match cx.row_guides {
+                RowGuides::None => unreachable!(),
+                RowGuides::Separators => {
+                    if irow > 0 {
+                        ui.painter().hline(
+                            row_rect.x_range(),
+                            row_rect.min.y,
+                            visuals.widgets.noninteractive.bg_stroke,
+                        );
+                    }
+                }
+                RowGuides::Alternating => {
+                    if irow % 2 == 1 {
+                        ui.painter()
+                            .rect(row_rect, 0.0, visuals.faint_bg_color, Stroke::NONE);
+                    }
+                }
+            }
+        }
+    }
+
+    fn fetch_meta_tile(
+        &mut self,
+        tile_id: TileID,
+        config: &mut Config,
+    ) -> Option<&SlotMetaTileData> {
+        self.tile_metas
+            .entry(tile_id)
+            .or_insert_with(|| {
+                config
+                    .data_source
+                    .fetch_slot_meta_tile(&self.entry_id, tile_id, false);
+                None
+            })
+            .as_ref()
+            .and_then(|tile| tile.as_ref().ok())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_tile(
+        &mut self,
+        tile_index: usize,
+        rows: u64,
+        mut hover_pos: Option<Pos2>,
+        ui: &mut egui::Ui,
+        rect: Rect,
+        viewport: Rect,
+        config: &mut Config,
+        cx: &mut Context,
+    ) -> Option<Pos2> {
+        // Hack: can't pass this as an argument because it aliases self.
+        let tile_id = self.tile_ids[tile_index];
+        let show_titles = cx.show_item_titles
+            && self
+                .tiles
+                .get(&tile_id)
+                .and_then(|tile| tile.as_ref()?.as_ref().ok())
+                .is_some_and(|tile| {
+                    tile.items.iter().flatten().any(|item| {
+                        item_view_span(cx.time_axis(), item.interval).is_some_and(
+                            |(start, stop)| (stop - start) * rect.width() >= ITEM_TITLE_MIN_WIDTH,
+                        )
+                    })
+                });
+        if config.color_by.is_some() || show_titles || !cx.linked_titles.is_empty() {
+            // Coloring by a field, showing titles, or matching titles to
+            // other profiles requires the item metadata
+            self.fetch_meta_tile(tile_id, config);
+        }
+        let tile = self.tiles.get(&tile_id).unwrap();
+        let tile_meta = self
+            .tile_metas
+            .get(&tile_id)
+            .and_then(|tile| tile.as_ref()?.as_ref().ok());
+
+        if !tile.is_some() {
+            // Tile hasn't finished loading.
+            show_tile_loading(ui, rect, tile_id.0, cx);
+            return hover_pos;
+        }
+        let tile = match tile.as_ref().unwrap() {
+            Ok(tile) => tile,
+            Err(error) => {
+                if show_tile_error(ui, rect, tile_id.0, error, &mut hover_pos, cx) {
+                    self.retry_tile(tile_id, config);
+                }
+                return hover_pos;
+            }
+        };
+
+        if !cx.view_interval.overlaps(tile_id.0) {
+            return hover_pos;
+        }
+
+        // Track which item, if any, we're interacting with
+        let mut interact_item = None;
+
+        for (row, row_items) in tile.items.iter().enumerate() {
+            // Need to reverse the rows because we're working in screen space
+            let irow = rows - (row as u64) - 1;
 
             // We want to do this first on rows, so that we can cut the
             // entire row if we don't need it
@@ -697,40 +2811,73 @@ impl Slot {
             let row_rect = Rect::from_min_max(row_min, row_max);
             let row_hover = hover_pos.map_or(false, |h| row_rect.contains(h));
 
+            let dense = cx.dense_rows.enabled && {
+                let (start, stop) = item_view_span(cx.time_axis(), tile_id.0).unwrap_or_default();
+                let pixels = (stop - start) * rect.width();
+                row_items.len() as f32 > cx.dense_rows.items_per_pixel * pixels
+            };
+            if dense {
+                let row_metas = tile_meta.and_then(|meta| meta.items.get(row));
+                let hover = hover_pos.filter(|_| row_hover);
+                let hovered =
+                    render_dense_row(ui, row_rect, row_items, row_metas, hover, config, cx);
+                if let Some((item_idx, column_rect)) = hovered {
+                    hover_pos = None;
+                    interact_item = Some((row, item_idx, column_rect, tile_id));
+                }
+                continue;
+            }
+
             // Now handle the items
             for (item_idx, item) in row_items.iter().enumerate() {
-                if !cx.view_interval.overlaps(item.interval) {
+                let Some((start, stop)) = item_view_span(cx.time_axis(), item.interval) else {
                     continue;
-                }
-
-                // Note: the interval is EXCLUSIVE. This turns out to be what
-                // we want here, because in screen coordinates interval.stop
-                // is the BEGINNING of the interval.stop nanosecond.
-                let start = cx.view_interval.unlerp(item.interval.start).at_least(0.0);
-                let stop = cx.view_interval.unlerp(item.interval.stop).at_most(1.0);
+                };
                 let min = rect.lerp_inside(Vec2::new(start, (irow as f32 + 0.05) / rows as f32));
                 let max = rect.lerp_inside(Vec2::new(stop, (irow as f32 + 0.95) / rows as f32));
 
-                let item_rect = Rect::from_min_max(min, max);
+                let mut item_rect = Rect::from_min_max(min, max);
+                if item_rect.width() < cx.min_item_width {
+                    item_rect = Rect::from_center_size(
+                        item_rect.center(),
+                        Vec2::new(cx.min_item_width, item_rect.height()),
+                    );
+                }
                 if row_hover && hover_pos.map_or(false, |h| item_rect.contains(h)) {
                     hover_pos = None;
                     interact_item = Some((row, item_idx, item_rect, tile_id));
                 }
 
-                let highlight = config.items_selected.contains_key(&item.item_uid);
+                let item_meta = tile_meta.and_then(|meta| meta.items.get(row)?.get(item_idx));
+                let color = item_color(ui, item, item_meta, config, cx);
 
-                let mut color = item.color;
-                if !config.search_state.query.is_empty() {
-                    if config.search_state.result_set.contains(&item.item_uid) || highlight {
-                        color = Color32::RED;
-                    } else {
-                        color = color.gamma_multiply(0.2);
+                let (draw_rect, stroke) = if item_rect.width() < ITEM_BORDER_MIN_WIDTH {
+                    (item_rect, Stroke::NONE)
+                } else {
+                    match cx.item_borders {
+                        ItemBorders::None => (item_rect, Stroke::NONE),
+                        ItemBorders::Outline => {
+                            let border = contrasting_color(color).gamma_multiply(0.5);
+                            (item_rect.shrink(0.5), Stroke::new(1.0, border))
+                        }
+                        ItemBorders::Gap => (item_rect.shrink2(Vec2::new(0.5, 0.0)), Stroke::NONE),
+                    }
+                };
+                ui.painter().rect(draw_rect, 0.0, color, stroke);
+
+                if show_titles && item_rect.width() >= ITEM_TITLE_MIN_WIDTH {
+                    if let Some(meta) = item_meta {
+                        let text_color = contrasting_color(color);
+                        let clip_rect = item_rect.shrink(2.0).intersect(ui.clip_rect());
+                        ui.painter().with_clip_rect(clip_rect).text(
+                            clip_rect.left_center(),
+                            Align2::LEFT_CENTER,
+                            &meta.title,
+                            TextStyle::Small.resolve(ui.style()),
+                            text_color,
+                        );
                     }
-                } else if highlight {
-                    color = Color32::RED;
                 }
-
-                ui.painter().rect(item_rect, 0.0, color, Stroke::NONE);
             }
         }
 
@@ -744,42 +2891,95 @@ impl Slot {
                     if cx.debug {
                         ui.label(format!("Item UID: {}", item_meta.item_uid.0));
                     }
-                    for (field_id, field, color) in &item_meta.fields {
+                    let fields =
+                        ordered_fields(&item_meta.fields, &config.field_schema, &cx.field_order);
+                    for (field_id, field, color) in fields {
                         let name = config.field_schema.get_name(*field_id).unwrap();
-                        let text = format!("{}", FieldWithName(name, field));
+                        if cx.hidden_fields.contains(name) {
+                            continue;
+                        }
+                        let text = format!("{}", FieldWithName(name, field, cx));
                         if let Some(color) = color {
                             ui.label(RichText::new(text).color(*color));
                         } else {
                             ui.label(text);
                         }
                     }
-                    ui.label("(Click to show details.)");
+                    ui.label("(Click to show details, or hold Alt to pin.)");
                 });
 
+                // Pin the tooltip so its links can be clicked
+                let pin = ui
+                    .input(|i| i.modifiers.alt)
+                    .then(|| ui.ctx().pointer_hover_pos());
+                if let Some(Some(pos)) = pin {
+                    let pinned = config.pinned_tooltip.as_ref();
+                    if pinned.map_or(true, |(p, _)| p.loc.item_uid != item_meta.item_uid) {
+                        let loc = ItemLocator {
+                            entry_id: entry_id.clone(),
+                            irow: Some(rows as usize - row - 1),
+                            row: None,
+                            item_uid: item_meta.item_uid,
+                        };
+                        let detail = ItemDetail {
+                            meta: Some(item_meta.clone()),
+                            loc,
+                        };
+                        config.pinned_tooltip = Some((detail, pos));
+                    }
+                }
+
+                if ui.input(|i| i.pointer.secondary_clicked()) {
+                    if let Some(pos) = ui.ctx().pointer_interact_pos() {
+                        let loc = ItemLocator {
+                            entry_id: entry_id.clone(),
+                            irow: Some(rows as usize - row - 1),
+                            row: None,
+                            item_uid: item_meta.item_uid,
+                        };
+                        config.item_menu = Some((loc, item_meta.original_interval, pos));
+                    }
+                }
+
                 // Also mark task as selected if the mouse has been clicked
                 ui.input(|i| {
                     // A "click" is measured on *release*, assuming certain
                     // properties hold (e.g., the button was held less than
                     // some duration, and it moved less than some amount).
-                    if i.pointer.any_click() && i.pointer.primary_released() {
+                    if cx.is_item_click(i) {
                         let irow = Some(rows as usize - row - 1);
-                        match config.items_selected.entry(item_meta.item_uid) {
-                            std::collections::btree_map::Entry::Vacant(e) => {
-                                e.insert(ItemDetail {
+                        if config.items_selected.contains_key(&item_meta.item_uid) {
+                            config.deselect_item(item_meta.item_uid);
+                        } else {
+                            let loc = ItemLocator {
+                                entry_id,
+                                irow,
+                                row: None,
+                                item_uid: item_meta.item_uid,
+                            };
+                            config.last_selected = Some(loc.clone());
+                            config.items_selected.insert(
+                                item_meta.item_uid,
+                                ItemDetail {
                                     meta: Some(item_meta.clone()),
-                                    loc: ItemLocator {
-                                        entry_id,
-                                        irow,
-                                        item_uid: item_meta.item_uid,
-                                    },
-                                });
-                            }
-                            std::collections::btree_map::Entry::Occupied(e) => {
-                                e.remove_entry();
-                            }
+                                    loc,
+                                },
+                            );
                         }
                     }
                 });
+            } else if let Some(Some(Err(error))) = self.tile_metas.get(&tile_id) {
+                ui.show_tooltip_ui("task_tooltip", &item_rect, |ui| {
+                    ui.label(RichText::new("Failed to load item details").strong());
+                    ui.label(error.to_string());
+                    ui.label("(Click to retry.)");
+                });
+
+                // Removing the failed tile causes it to be fetched again the
+                // next time it's needed
+                if ui.input(|i| i.pointer.any_click() && i.pointer.primary_released()) {
+                    self.tile_metas.remove(&tile_id);
+                }
             }
         }
 
@@ -804,6 +3004,8 @@ impl Entry for Slot {
                 tile_ids: Vec::new(),
                 tiles: BTreeMap::new(),
                 tile_metas: BTreeMap::new(),
+                item_index: BTreeMap::new(),
+                meta_index: BTreeMap::new(),
                 last_view_interval: None,
             }
         } else {
@@ -837,6 +3039,10 @@ impl Entry for Slot {
         unreachable!()
     }
 
+    fn summary_mut(&mut self) -> Option<&mut Summary> {
+        None
+    }
+
     fn expand_slot(&mut self, entry_id: &EntryID, level: u64) {
         assert_eq!(entry_id.level(), level);
         assert!(entry_id.slot_index(level - 1).is_some());
@@ -844,18 +3050,71 @@ impl Entry for Slot {
     }
 
     fn inflate_meta(&mut self, config: &mut Config, cx: &mut Context) {
+        let mut tile_ids = Vec::new();
         for tile_id in config.request_tiles(cx.view_interval) {
-            self.fetch_meta_tile(tile_id, config);
+            if let btree_map::Entry::Vacant(e) = self.tile_metas.entry(tile_id) {
+                e.insert(None);
+                tile_ids.push(tile_id);
+            }
+        }
+        let requests = tile_requests(&self.entry_id, &tile_ids);
+        config.data_source.fetch_slot_meta_tiles(&requests, false);
+    }
+
+    fn hover_details(&self, cx: &Context) -> Option<String> {
+        let view = cx.view_interval;
+        let busy = self.busy_intervals(view)?;
+        let busy_ns: i64 = busy.iter().map(|i| i.duration_ns()).sum();
+        let idle_ns = view.duration_ns() - busy_ns;
+        let fraction = idle_ns as f64 / view.duration_ns().max(1) as f64;
+        Some(format!(
+            "Idle: {:.1}% of view ({})",
+            fraction * 100.0,
+            cx.format_duration(idle_ns)
+        ))
+    }
+
+    fn evict_meta_tiles(&mut self, keep: &BTreeSet<TileID>, loaded_too: bool) {
+        // If a tile is still loading, the response will be dropped on arrival
+        let before = self.tile_metas.len();
+        self.tile_metas
+            .retain(|tile_id, tile| keep.contains(tile_id) && !(loaded_too && tile.is_some()));
+        if self.tile_metas.len() == before {
+            return;
+        }
+
+        // Items span tiles, so an evicted entry may still be in another one
+        self.meta_index.clear();
+        for (tile_id, tile) in &self.tile_metas {
+            if let Some(Ok(tile)) = tile {
+                index_items(&mut self.meta_index, *tile_id, &tile.items, |item| {
+                    item.item_uid
+                });
+            }
         }
     }
 
+    fn clear_tiles(&mut self) {
+        self.clear();
+    }
+
+    fn meta_tiles_size(&self) -> usize {
+        self.tile_metas
+            .values()
+            .map(|tile| match tile {
+                Some(Ok(tile)) => estimate_meta_tile_size(tile),
+                _ => 0,
+            })
+            .sum()
+    }
+
     fn search(&mut self, config: &mut Config) {
         if !config.search_state.start_entry(self) {
             return;
         }
 
         for (tile_id, tile) in &self.tile_metas {
-            if let Some(tile) = tile {
+            if let Some(Ok(tile)) = tile {
                 if !config.search_state.start_tile(self, *tile_id) {
                     continue;
                 }
@@ -873,6 +3132,18 @@ impl Entry for Slot {
         }
     }
 
+    fn item_durations(&self, _config: &Config, view: Interval, durations: &mut Vec<i64>) {
+        // Items that span tiles show up once per tile
+        let mut seen = BTreeSet::new();
+        for tile in self.tile_metas.values().flatten().flatten() {
+            for item in tile.items.iter().flatten() {
+                if item.original_interval.overlaps(view) && seen.insert(item.item_uid) {
+                    durations.push(item.original_interval.duration_ns());
+                }
+            }
+        }
+    }
+
     fn content(
         &mut self,
         ui: &mut egui::Ui,
@@ -895,7 +3166,11 @@ impl Entry for Slot {
             }
             self.last_view_interval = Some(cx.view_interval);
             if self.tiles.is_empty() {
+                #[cfg(not(target_arch = "wasm32"))]
+                let timer = cx.start_timer();
                 self.inflate(config, cx);
+                #[cfg(not(target_arch = "wasm32"))]
+                cx.stop_timer("Inflate", timer);
             }
 
             let style = ui.style();
@@ -904,10 +3179,19 @@ impl Entry for Slot {
                 .rect(rect, 0.0, visuals.bg_fill, visuals.bg_stroke);
 
             let rows = self.rows();
+            Self::render_row_guides(rows, ui, rect, viewport, cx);
+            Self::render_gridlines(ui, rect, cx);
+            if cx.shade_idle {
+                self.render_idle(ui, rect, cx);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            let timer = cx.start_timer();
             for tile_index in 0..self.tile_ids.len() {
                 hover_pos =
                     self.render_tile(tile_index, rows, hover_pos, ui, rect, viewport, config, cx);
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            cx.stop_timer("Render tiles", timer);
         }
     }
 
@@ -915,6 +3199,58 @@ impl Entry for Slot {
         self.rows() as f32 * cx.row_height
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(
+        &self,
+        svg: &mut SvgWriter,
+        rect: Rect,
+        ui: &egui::Ui,
+        config: &mut Config,
+        cx: &Context,
+    ) {
+        if !self.expanded {
+            return;
+        }
+        svg.rect(rect, ui.visuals().widgets.inactive.bg_fill);
+
+        // Same mapping as render_tile (minus dense rows, which become
+        // individual items)
+        let rows = self.rows();
+        svg.begin_clip(rect);
+        for tile_id in &self.tile_ids {
+            let Some(Some(Ok(tile))) = self.tiles.get(tile_id) else {
+                continue;
+            };
+            let tile_meta = self
+                .tile_metas
+                .get(tile_id)
+                .and_then(|m| m.as_ref()?.as_ref().ok());
+            for (row, row_items) in tile.items.iter().enumerate() {
+                let irow = rows - (row as u64) - 1;
+                for (item_idx, item) in row_items.iter().enumerate() {
+                    let Some((start, stop)) = item_view_span(cx.time_axis(), item.interval) else {
+                        continue;
+                    };
+                    let min =
+                        rect.lerp_inside(Vec2::new(start, (irow as f32 + 0.05) / rows as f32));
+                    let max = rect.lerp_inside(Vec2::new(stop, (irow as f32 + 0.95) / rows as f32));
+
+                    let mut item_rect = Rect::from_min_max(min, max);
+                    if item_rect.width() < cx.min_item_width {
+                        item_rect = Rect::from_center_size(
+                            item_rect.center(),
+                            Vec2::new(cx.min_item_width, item_rect.height()),
+                        );
+                    }
+
+                    let item_meta = tile_meta.and_then(|meta| meta.items.get(row)?.get(item_idx));
+                    svg.rect(item_rect, item_color(ui, item, item_meta, config, cx));
+                }
+            }
+        }
+        svg.end_clip();
+    }
+
     fn is_expandable(&self) -> bool {
         true
     }
@@ -924,7 +3260,27 @@ impl Entry for Slot {
     }
 }
 
+// Layout of the slots in a panel: each row has a label on the left and the
+// slot's content to the right of it, and rows are spaced apart vertically
+const LABEL_WIDTH: f32 = 60.0;
+const COL_PADDING: f32 = 4.0;
+const ROW_PADDING: f32 = 4.0;
+
 impl<S: Entry> Panel<S> {
+    // The label and content of a slot spanning min_y to max_y
+    fn slot_rects(rect: Rect, min_y: f32, max_y: f32) -> (Rect, Rect) {
+        let label_min = rect.min.x;
+        let label_max = (rect.min.x + LABEL_WIDTH).at_most(rect.max.x);
+        let content_min = (label_max + COL_PADDING).at_most(rect.max.x);
+        let content_max = rect.max.x;
+
+        let label_subrect =
+            Rect::from_min_max(Pos2::new(label_min, min_y), Pos2::new(label_max, max_y));
+        let content_subrect =
+            Rect::from_min_max(Pos2::new(content_min, min_y), Pos2::new(content_max, max_y));
+        (label_subrect, content_subrect)
+    }
+
     fn render<T: Entry>(
         ui: &mut egui::Ui,
         rect: Rect,
@@ -934,10 +3290,6 @@ impl<S: Entry> Panel<S> {
         config: &mut Config,
         cx: &mut Context,
     ) -> bool {
-        const LABEL_WIDTH: f32 = 60.0;
-        const COL_PADDING: f32 = 4.0;
-        const ROW_PADDING: f32 = 4.0;
-
         // Compute the size of this slot
         // This is in screen (i.e., rect) space
         let min_y = *y;
@@ -953,15 +3305,7 @@ impl<S: Entry> Panel<S> {
         }
 
         // Draw label and content
-        let label_min = rect.min.x;
-        let label_max = (rect.min.x + LABEL_WIDTH).at_most(rect.max.x);
-        let content_min = (label_max + COL_PADDING).at_most(rect.max.x);
-        let content_max = rect.max.x;
-
-        let label_subrect =
-            Rect::from_min_max(Pos2::new(label_min, min_y), Pos2::new(label_max, max_y));
-        let content_subrect =
-            Rect::from_min_max(Pos2::new(content_min, min_y), Pos2::new(content_max, max_y));
+        let (label_subrect, content_subrect) = Self::slot_rects(rect, min_y, max_y);
 
         // Shift viewport up by the amount consumed
         // Invariant: (0, 0) in viewport is rect.min
@@ -969,13 +3313,75 @@ impl<S: Entry> Panel<S> {
         // Note: viewport.min is NOT necessarily (0, 0)
         let content_viewport = viewport.translate(Vec2::new(0.0, rect.min.y - min_y));
 
-        slot.content(ui, content_subrect, content_viewport, config, cx);
-        slot.label(ui, label_subrect, cx);
+        #[cfg(not(target_arch = "wasm32"))]
+        let draw = cx.render_budget.draw_next();
+        #[cfg(target_arch = "wasm32")]
+        let draw = true;
+        if draw {
+            slot.content(ui, content_subrect, content_viewport, config, cx);
+        } else {
+            // Out of time this frame, see RenderBudget
+            ui.painter()
+                .rect_filled(content_subrect, 0.0, ui.visuals().faint_bg_color);
+        }
+        slot.label(ui, label_subrect, config, cx);
 
         false
     }
 
+    // Lays out the slot like render, but always "draws" it
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg_entry<T: Entry>(
+        svg: &mut SvgWriter,
+        rect: Rect,
+        slot: &T,
+        y: &mut f32,
+        ui: &egui::Ui,
+        config: &mut Config,
+        cx: &Context,
+    ) {
+        let min_y = *y;
+        let max_y = min_y + slot.height(None, config, cx);
+        *y = max_y + ROW_PADDING;
+
+        let (label_subrect, content_subrect) = Self::slot_rects(rect, min_y, max_y);
+
+        slot.export_svg(svg, content_subrect, ui, config, cx);
+
+        let style = ui.style();
+        let visuals = if slot.is_expandable() {
+            style.visuals.widgets.inactive
+        } else {
+            *style.noninteractive()
+        };
+        let font_size = TextStyle::Body.resolve(style).size;
+        let text_pos =
+            label_subrect.min + style.spacing.item_spacing * Vec2::new(1.0, cx.scale_factor);
+        svg.rect(label_subrect, visuals.bg_fill);
+        svg.begin_clip(label_subrect);
+        svg.text(
+            text_pos + Vec2::new(0.0, font_size),
+            slot.label_text(),
+            font_size,
+            visuals.text_color(),
+        );
+        svg.end_clip();
+    }
+
+    // The children that search (and inflate_meta) would visit
+    fn searched_slots<'a>(&'a self, config: &'a Config) -> impl Iterator<Item = &'a S> {
+        let force = config.search_state.include_collapsed_entries;
+        self.slots
+            .iter()
+            .filter(move |slot| force || (self.expanded && Self::is_slot_visible(slot, config)))
+    }
+
     fn is_slot_visible(slot: &S, config: &Config) -> bool {
+        // Apply solo/mute overrides.
+        if !config.is_entry_visible(slot.entry_id()) {
+            return false;
+        }
+
         let level = slot.entry_id().level();
         if level == 1 {
             // Apply node filter.
@@ -1054,6 +3460,10 @@ impl<S: Entry> Entry for Panel<S> {
         }
     }
 
+    fn summary_mut(&mut self) -> Option<&mut Summary> {
+        self.summary.as_mut()
+    }
+
     fn expand_slot(&mut self, entry_id: &EntryID, level: u64) {
         self.slots
             .get_mut(entry_id.slot_index(level).unwrap() as usize)
@@ -1062,7 +3472,40 @@ impl<S: Entry> Entry for Panel<S> {
         self.expanded = true;
     }
 
-    fn inflate_meta(&mut self, config: &mut Config, cx: &mut Context) {
+    fn inflate_meta(&mut self, config: &mut Config, cx: &mut Context) {
+        let force = config.search_state.include_collapsed_entries;
+        if self.expanded || force {
+            for slot in &mut self.slots {
+                // Apply visibility settings
+                if !force && !Self::is_slot_visible(slot, config) {
+                    continue;
+                }
+
+                slot.inflate_meta(config, cx);
+            }
+        }
+    }
+
+    fn evict_meta_tiles(&mut self, keep: &BTreeSet<TileID>, loaded_too: bool) {
+        for slot in &mut self.slots {
+            slot.evict_meta_tiles(keep, loaded_too);
+        }
+    }
+
+    fn clear_tiles(&mut self) {
+        if let Some(summary) = &mut self.summary {
+            summary.clear_tiles();
+        }
+        for slot in &mut self.slots {
+            slot.clear_tiles();
+        }
+    }
+
+    fn meta_tiles_size(&self) -> usize {
+        self.slots.iter().map(|slot| slot.meta_tiles_size()).sum()
+    }
+
+    fn search(&mut self, config: &mut Config) {
         let force = config.search_state.include_collapsed_entries;
         if self.expanded || force {
             for slot in &mut self.slots {
@@ -1071,21 +3514,21 @@ impl<S: Entry> Entry for Panel<S> {
                     continue;
                 }
 
-                slot.inflate_meta(config, cx);
+                slot.search(config);
             }
         }
     }
 
-    fn search(&mut self, config: &mut Config) {
+    fn item_durations(&self, config: &Config, view: Interval, durations: &mut Vec<i64>) {
         let force = config.search_state.include_collapsed_entries;
         if self.expanded || force {
-            for slot in &mut self.slots {
+            for slot in &self.slots {
                 // Apply visibility settings
                 if !force && !Self::is_slot_visible(slot, config) {
                     continue;
                 }
 
-                slot.search(config);
+                slot.item_durations(config, view, durations);
             }
         }
     }
@@ -1099,7 +3542,22 @@ impl<S: Entry> Entry for Panel<S> {
         cx: &mut Context,
     ) {
         let mut y = rect.min.y;
-        if let Some(summary) = &mut self.summary {
+        let show_summary = cx.summary_rows != SummaryRows::Hidden;
+        if let Some(summary) = self.summary.as_mut().filter(|_| show_summary) {
+            // Break the utilization down by child while it's being hovered.
+            // The children's tiles are only fetched when needed.
+            summary.breakdown.clear();
+            if summary.hovered {
+                for slot in &mut self.slots {
+                    let label = slot.label_text().to_owned();
+                    if let Some(child) = slot.summary_mut() {
+                        child.update_tiles(config, cx);
+                        let mut utilization = child.utilization();
+                        utilization.sort_by_key(|p| p.time);
+                        summary.breakdown.push((label, utilization));
+                    }
+                }
+            }
             Self::render(ui, rect, viewport, summary, &mut y, config, cx);
         }
 
@@ -1119,11 +3577,11 @@ impl<S: Entry> Entry for Panel<S> {
 
     fn height(&self, prefix: Option<&EntryID>, config: &Config, cx: &Context) -> f32 {
         const UNEXPANDED_ROWS: u64 = 2;
-        const ROW_PADDING: f32 = 4.0;
 
         let mut total = 0.0;
         let mut rows: i64 = 0;
-        if let Some(summary) = &self.summary {
+        let show_summary = cx.summary_rows != SummaryRows::Hidden;
+        if let Some(summary) = self.summary.as_ref().filter(|_| show_summary) {
             total += summary.height(None, config, cx);
             rows += 1;
         } else if !self.expanded {
@@ -1164,6 +3622,30 @@ impl<S: Entry> Entry for Panel<S> {
         total
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(
+        &self,
+        svg: &mut SvgWriter,
+        rect: Rect,
+        ui: &egui::Ui,
+        config: &mut Config,
+        cx: &Context,
+    ) {
+        let mut y = rect.min.y;
+        let show_summary = cx.summary_rows != SummaryRows::Hidden;
+        if let Some(summary) = self.summary.as_ref().filter(|_| show_summary) {
+            Self::export_svg_entry(svg, rect, summary, &mut y, ui, config, cx);
+        }
+
+        if self.expanded {
+            for slot in &self.slots {
+                if Self::is_slot_visible(slot, config) {
+                    Self::export_svg_entry(svg, rect, slot, &mut y, ui, config, cx);
+                }
+            }
+        }
+    }
+
     fn is_expandable(&self) -> bool {
         !self.slots.is_empty()
     }
@@ -1174,7 +3656,7 @@ impl<S: Entry> Entry for Panel<S> {
 }
 
 impl SearchState {
-    fn new(title_id: FieldID) -> Self {
+    fn new(title_id: FieldID, numeric_fields: BTreeSet<FieldID>) -> Self {
         Self {
             title_field: title_id,
 
@@ -1184,17 +3666,67 @@ impl SearchState {
             last_search_field: title_id,
             whole_word: false,
             last_whole_word: false,
-            last_word_regex: None,
+            regex_mode: false,
+            last_regex_mode: false,
+            last_regex: None,
+            regex_error: None,
+            positive_terms: Vec::new(),
+            negative_terms: Vec::new(),
+            positive_words: Vec::new(),
+            negative_words: Vec::new(),
+            numeric_fields,
+            range: None,
+            range_min: String::new(),
+            range_max: String::new(),
+            other_query: String::new(),
+            duration_range: None,
+            last_duration_range: None,
             include_collapsed_entries: false,
             last_include_collapsed_entries: false,
+            restrict_to_view: false,
+            last_restrict_to_view: false,
+            focus_result: false,
             last_view_interval: None,
 
+            uid_query: String::new(),
+            uid_status: None,
+
             result_set: BTreeSet::new(),
             result_cache: BTreeMap::new(),
             entry_tree: BTreeMap::new(),
+
+            #[cfg(not(target_arch = "wasm32"))]
+            export_path: "search_results.csv".to_owned(),
+            #[cfg(not(target_arch = "wasm32"))]
+            export_status: None,
         }
     }
 
+    fn options(&self) -> SearchOptions {
+        SearchOptions {
+            search_field: self.search_field,
+            whole_word: self.whole_word,
+            include_collapsed_entries: self.include_collapsed_entries,
+            regex_mode: self.regex_mode,
+        }
+    }
+
+    fn restore_options(&mut self, options: SearchOptions, field_schema: &FieldSchema) {
+        // The profile may have changed since the options were saved
+        if field_schema.searchable().contains(&options.search_field)
+            || field_schema.numeric().contains(&options.search_field)
+        {
+            self.search_field = options.search_field;
+            self.last_search_field = options.search_field;
+        }
+        self.whole_word = options.whole_word;
+        self.last_whole_word = options.whole_word;
+        self.regex_mode = options.regex_mode && !options.whole_word;
+        self.last_regex_mode = self.regex_mode;
+        self.include_collapsed_entries = options.include_collapsed_entries;
+        self.last_include_collapsed_entries = options.include_collapsed_entries;
+    }
+
     fn clear(&mut self) {
         self.result_set.clear();
         self.result_cache.clear();
@@ -1222,6 +3754,18 @@ impl SearchState {
             self.last_whole_word = self.whole_word;
         }
 
+        // Invalidate when the regex setting changes.
+        if self.regex_mode != self.last_regex_mode {
+            invalidate = true;
+            self.last_regex_mode = self.regex_mode;
+        }
+
+        // Invalidate when the duration filter changes.
+        if self.duration_range != self.last_duration_range {
+            invalidate = true;
+            self.last_duration_range = self.duration_range;
+        }
+
         // Invalidate when EXCLUDING collapsed entries. (I.e., because the
         // searched set shrinks. Growing is ok because search is monotonic.)
         if self.include_collapsed_entries != self.last_include_collapsed_entries
@@ -1231,6 +3775,12 @@ impl SearchState {
             self.last_include_collapsed_entries = self.include_collapsed_entries;
         }
 
+        // Invalidate when the restrict to view setting changes.
+        if self.restrict_to_view != self.last_restrict_to_view {
+            invalidate = true;
+            self.last_restrict_to_view = self.restrict_to_view;
+        }
+
         // Invalidate when the view interval changes.
         if self.last_view_interval != Some(cx.view_interval) {
             invalidate = true;
@@ -1238,41 +3788,153 @@ impl SearchState {
         }
 
         if invalidate {
+            self.last_regex = None;
+            self.regex_error = None;
+            (self.positive_terms, self.negative_terms) = parse_search_terms(&self.query);
+            self.range = parse_search_range(&self.query);
+            let words = |terms: &[String]| {
+                terms
+                    .iter()
+                    .map(|term| Regex::new(&format!("\\b{}\\b", escape(term))).unwrap())
+                    .collect()
+            };
             if self.whole_word {
-                let regex_string = format!("\\b{}\\b", escape(&self.query));
-                self.last_word_regex = Some(Regex::new(&regex_string).unwrap());
+                self.positive_words = words(&self.positive_terms);
+                self.negative_words = words(&self.negative_terms);
+            } else if self.regex_mode {
+                // The query is whatever the user typed, so it may not compile
+                match Regex::new(&self.query) {
+                    Ok(regex) => self.last_regex = Some(regex),
+                    Err(error) => self.regex_error = Some(error.to_string()),
+                }
             }
 
             self.clear();
         }
     }
 
+    fn is_active(&self) -> bool {
+        (!self.query.is_empty() && !self.is_exclude_only()) || self.duration_range.is_some()
+    }
+
+    // A query of only excluded words would match nearly every item, so it
+    // doesn't start a search on its own
+    fn is_exclude_only(&self) -> bool {
+        !self.regex_mode
+            && !self.is_numeric()
+            && self.positive_terms.is_empty()
+            && !self.negative_terms.is_empty()
+    }
+
+    // Text queries don't make sense as ranges and vice versa, so each is kept
+    // aside while the other kind of field is searched
+    fn set_search_field(&mut self, field: FieldID) {
+        if self.numeric_fields.contains(&field) != self.is_numeric() {
+            std::mem::swap(&mut self.query, &mut self.other_query);
+        }
+        self.search_field = field;
+    }
+
     fn is_string_match(&self, s: &str) -> bool {
-        if self.whole_word {
-            let Some(regex) = &self.last_word_regex else {
-                unreachable!();
+        self.is_strings_match(&[s])
+    }
+
+    // Every positive term must be in one of the strings, and no negative
+    // term may be in any of them. There must be at least one positive term,
+    // unless a duration range narrows the search already.
+    fn is_strings_match(&self, strings: &[&str]) -> bool {
+        if self.regex_mode {
+            // An invalid regex matches nothing
+            let Some(regex) = &self.last_regex else {
+                return false;
             };
-            regex.is_match(s)
+            return strings.iter().any(|s| regex.is_match(s));
+        }
+        if self.positive_terms.is_empty() && self.duration_range.is_none() {
+            return false;
+        }
+        let found = |matches: &dyn Fn(&str) -> bool| strings.iter().any(|s| matches(s));
+        if self.whole_word {
+            self.positive_words
+                .iter()
+                .all(|r| found(&|s| r.is_match(s)))
+                && !self
+                    .negative_words
+                    .iter()
+                    .any(|r| found(&|s| r.is_match(s)))
         } else {
-            s.contains(&self.query)
+            self.positive_terms
+                .iter()
+                .all(|t| found(&|s| s.contains(t.as_str())))
+                && !self
+                    .negative_terms
+                    .iter()
+                    .any(|t| found(&|s| s.contains(t.as_str())))
         }
     }
 
+    fn is_numeric(&self) -> bool {
+        self.numeric_fields.contains(&self.search_field)
+    }
+
+    fn is_number_match(&self, field: &Field) -> bool {
+        // An invalid range matches nothing
+        let Some((min, max)) = self.range else {
+            return false;
+        };
+        // Wide enough to compare i64 and u64 values
+        let value = match field {
+            Field::I64(value) => *value as i128,
+            Field::U64(value) => *value as i128,
+            Field::Vec(fields) => return fields.iter().any(|f| self.is_number_match(f)),
+            _ => return false,
+        };
+        min.map_or(true, |min| value >= min as i128) && max.map_or(true, |max| value <= max as i128)
+    }
+
     fn is_field_match(&self, field: &Field) -> bool {
-        match field {
-            Field::String(s) => self.is_string_match(s),
-            Field::ItemLink(ItemLink { title, .. }) => self.is_string_match(title),
-            Field::Vec(fields) => fields.iter().any(|f| self.is_field_match(f)),
-            _ => false,
+        fn collect<'a>(field: &'a Field, strings: &mut Vec<&'a str>) {
+            match field {
+                Field::String(s) => strings.push(s),
+                Field::ItemLink(ItemLink { title, .. }) => strings.push(title),
+                Field::Vec(fields) => fields.iter().for_each(|f| collect(f, strings)),
+                _ => {}
+            }
         }
+        let mut strings = Vec::new();
+        collect(field, &mut strings);
+        !strings.is_empty() && self.is_strings_match(&strings)
     }
 
     fn is_match(&self, item: &ItemMeta) -> bool {
+        if self.restrict_to_view {
+            let in_view = self
+                .last_view_interval
+                .map_or(true, |view| view.overlaps(item.original_interval));
+            if !in_view {
+                return false;
+            }
+        }
+
+        if let Some((min, max)) = self.duration_range {
+            let duration = item.original_interval.duration_ns();
+            if duration < min || duration >= max {
+                return false;
+            }
+            if self.query.is_empty() {
+                return true;
+            }
+        }
+
         let field = self.search_field;
         if field == self.title_field {
             self.is_string_match(&item.title)
         } else if let Some((_, value, _)) = item.fields.iter().find(|(x, _, _)| *x == field) {
-            self.is_field_match(value)
+            if self.is_numeric() {
+                self.is_number_match(value)
+            } else {
+                self.is_field_match(value)
+            }
         } else {
             false
         }
@@ -1356,17 +4018,21 @@ impl SearchState {
 }
 
 impl Config {
-    fn new(data_source: Box<dyn DeferredDataSource>, info: DataSourceInfo) -> Self {
+    fn new(
+        data_source: SharedDeferredDataSource<Box<dyn DeferredDataSource>>,
+        info: DataSourceInfo,
+    ) -> Self {
         let max_node = info.entry_info.nodes();
         let kinds = info.entry_info.kinds();
         let interval = info.interval;
         let tile_set = info.tile_set;
         let warning_message = info.warning_message;
+        let epoch = info.epoch;
 
         let mut field_schema = info.field_schema;
         assert!(!field_schema.contains_name("Title"));
         let title_id = field_schema.insert("Title".to_owned(), true);
-        let search_state = SearchState::new(title_id);
+        let search_state = SearchState::new(title_id, field_schema.numeric().clone());
 
         Self {
             field_schema,
@@ -1374,17 +4040,69 @@ impl Config {
             max_node,
             kinds,
             kind_filter: BTreeSet::new(),
+            visibility_overrides: BTreeMap::new(),
+            saved_layout: None,
             interval,
             tile_set,
             warning_message,
+            epoch,
+            color_by: None,
+            color_by_range: None,
             data_source: CountingDeferredDataSource::new(data_source),
             search_state,
             items_selected: BTreeMap::new(),
+            last_selected: None,
+            detail_index: 0,
+            detail_last_selected: None,
+            pinned_tooltip: None,
+            compare_items: false,
+            histogram_selected_slot: false,
+            stats_by_processor: false,
+            stats_sort: (StatsColumn::Name, false),
+            stats_cache: None,
+            item_menu: None,
             scroll_to_item: None,
             scroll_to_item_retry: None,
+            scroll_offset: 0.0,
+            restore_scroll_offset: None,
+            last_evict_interval: None,
+            last_evict_tiles: BTreeSet::new(),
+            tile_level: None,
             last_request_interval: None,
             request_tile_cache: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            view_width: 0.0,
+            #[cfg(not(target_arch = "wasm32"))]
+            svg_path: "timeline.svg".to_owned(),
+            #[cfg(not(target_arch = "wasm32"))]
+            svg_status: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            trace_path: "trace.json".to_owned(),
+            #[cfg(not(target_arch = "wasm32"))]
+            trace_export: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            trace_status: None,
+        }
+    }
+
+    fn is_entry_visible(&self, entry_id: &EntryID) -> bool {
+        // Muting an entry hides everything inside it
+        let overrides = &self.visibility_overrides;
+        if overrides
+            .iter()
+            .any(|(id, v)| *v == EntryVisibility::Mute && entry_id.has_prefix(id))
+        {
+            return false;
         }
+
+        // When anything is soloed, show only soloed entries, their contents,
+        // and the panels containing them
+        let mut solo = overrides
+            .iter()
+            .filter(|(_, v)| **v == EntryVisibility::Solo)
+            .peekable();
+        solo.peek().is_none()
+            || solo.any(|(id, _)| entry_id.has_prefix(id) || id.has_prefix(entry_id))
     }
 
     fn request_tiles(&mut self, view_interval: Interval) -> Vec<TileID> {
@@ -1393,35 +4111,9 @@ impl Config {
             return self.request_tile_cache.clone();
         }
 
-        if self.tile_set.tiles.is_empty() {
-            // For dynamic profiles, just return the request as one tile.
-            self.request_tile_cache = vec![TileID(request_interval)];
-            return self.request_tile_cache.clone();
-        }
-
-        // We're in a static profile. Estimate the best zoom level, where
-        // "best" minimizes the ratio of the tile size to request size.
-        let request_duration = request_interval.duration_ns();
-        let chosen_level = self
+        self.request_tile_cache = self
             .tile_set
-            .tiles
-            .iter()
-            .min_by_key(|level| {
-                let d = level.first().unwrap().0.duration_ns();
-                if d < request_duration {
-                    request_duration / d
-                } else {
-                    d / request_duration
-                }
-            })
-            .unwrap();
-
-        // Now filter to just tiles overlapping the requested interval.
-        self.request_tile_cache = chosen_level
-            .iter()
-            .filter(|tile| request_interval.overlaps(tile.0))
-            .copied()
-            .collect();
+            .covering_tiles_at_level(request_interval, self.tile_level);
         self.request_tile_cache.clone()
     }
 
@@ -1429,6 +4121,7 @@ impl Config {
         self.scroll_to_item = Some(item_loc.clone());
         self.scroll_to_item_retry = None;
 
+        self.last_selected = Some(item_loc.clone());
         self.items_selected
             .entry(item_loc.item_uid)
             .or_insert_with(|| ItemDetail {
@@ -1436,14 +4129,128 @@ impl Config {
                 loc: item_loc,
             });
     }
+
+    fn clear_selection(&mut self) {
+        self.items_selected.clear();
+        self.last_selected = None;
+    }
+
+    // Smallest interval covering the selected items whose details are loaded
+    fn selection_interval(&self) -> Option<Interval> {
+        self.items_selected
+            .values()
+            .filter_map(|item| item.meta.as_ref())
+            .map(|meta| meta.original_interval)
+            .reduce(Interval::union)
+    }
+
+    fn deselect_item(&mut self, item_uid: ItemUID) {
+        self.items_selected.remove(&item_uid);
+        if self
+            .last_selected
+            .as_ref()
+            .is_some_and(|loc| loc.item_uid == item_uid)
+        {
+            self.last_selected = None;
+        }
+    }
 }
 
 impl Window {
     fn new(data_source: Box<dyn DeferredDataSource>, info: DataSourceInfo, index: u64) -> Self {
+        Self::with_shared_source(SharedDeferredDataSource::new(data_source), info, index)
+    }
+
+    fn with_shared_source(
+        data_source: SharedDeferredDataSource<Box<dyn DeferredDataSource>>,
+        info: DataSourceInfo,
+        index: u64,
+    ) -> Self {
         Self {
             panel: Panel::new(&info.entry_info, EntryID::root()),
             index,
-            config: Config::new(data_source, info),
+            config: Config::new(data_source, info.clone()),
+            info,
+            copy: 0,
+            view: None,
+        }
+    }
+
+    // A fresh view onto the same data source, with its own layout, filters
+    // and view interval (starting from this window's). Tiles are fetched
+    // separately for each window.
+    fn duplicate(&self, index: u64, copy: u64, cx: &Context) -> Self {
+        let data_source = self.config.data_source.data_source().handle();
+        let mut window = Self::with_shared_source(data_source, self.info.clone(), index);
+        window.copy = copy;
+        window.view = Some(self.view.clone().unwrap_or_else(|| WindowView {
+            interval: cx.view_interval,
+            history: cx.view_interval_history.clone(),
+            select_state: cx.interval_select_state.clone(),
+        }));
+        window
+    }
+
+    // Puts this window's view (if it has one) in the context in place of the
+    // shared view. Call again to swap them back.
+    fn swap_view(&mut self, cx: &mut Context) {
+        if let Some(view) = &mut self.view {
+            std::mem::swap(&mut view.interval, &mut cx.view_interval);
+            std::mem::swap(&mut view.history, &mut cx.view_interval_history);
+            std::mem::swap(&mut view.select_state, &mut cx.interval_select_state);
+        }
+    }
+
+    fn source_locator(&self) -> String {
+        self.config
+            .data_source
+            .fetch_description()
+            .source_locator
+            .join(",")
+    }
+
+    // Identifies the window across sessions. Copies of a profile each get
+    // their own key.
+    fn locator_key(&self) -> String {
+        if self.copy == 0 {
+            self.source_locator()
+        } else {
+            format!("{}#{}", self.source_locator(), self.copy)
+        }
+    }
+
+    // Applies the parts of a link that are specific to a profile
+    fn apply_start_options(&mut self, options: &mut StartOptions) {
+        if let Some(query) = options.initial_search.take() {
+            self.config.search_state.query = query;
+        }
+        if let Some((min_node, max_node)) = options.initial_nodes.take() {
+            let total = self.panel.slots.len().saturating_sub(1) as u64;
+            self.config.max_node = max_node.min(total);
+            self.config.min_node = min_node.min(self.config.max_node);
+        }
+        if let Some((entry_id, item_uid)) = options.initial_item.take() {
+            // Links may be stale, so check that the slot still exists
+            if entry_id.level() == 3 && self.find_slot(&entry_id).is_some() {
+                self.expand_slot(&entry_id);
+                self.config.scroll_to_item(ItemLocator {
+                    entry_id,
+                    irow: None,
+                    row: None,
+                    item_uid,
+                });
+            }
+        }
+    }
+
+    // Sets the initial expanded state of each kind panel
+    fn apply_kind_expanded(&mut self, kind_expanded: &BTreeMap<String, bool>) {
+        for node in &mut self.panel.slots {
+            for kind in &mut node.slots {
+                if let Some(expanded) = kind_expanded.get(&kind.label_text().to_lowercase()) {
+                    kind.expanded = *expanded;
+                }
+            }
         }
     }
 
@@ -1471,102 +4278,363 @@ impl Window {
 
     fn find_item_irow(&self, entry_id: &EntryID, item_uid: ItemUID) -> Option<usize> {
         let slot = self.find_slot(entry_id)?;
-        for tile in slot.tiles.values() {
-            let Some(tile) = tile else {
-                continue;
-            };
-            for (row, items) in tile.items.iter().enumerate() {
-                for item in items {
-                    if item.item_uid == item_uid {
-                        let rows = tile.items.len();
-                        return Some(rows - row - 1);
-                    }
-                }
+        let (tile, row) = slot.lookup_item(item_uid)?;
+        Some(tile.items.len() - row - 1)
+    }
+
+    fn find_item_by_uid(&self, item_uid: ItemUID) -> Option<(ItemLocator, Interval)> {
+        let slots = self.panel.slots.iter().flat_map(|node| &node.slots);
+        for slot in slots.flat_map(|kind| &kind.slots) {
+            if let Some((irow, interval)) = slot.find_item(item_uid) {
+                let loc = ItemLocator {
+                    entry_id: slot.entry_id.clone(),
+                    irow: Some(irow),
+                    row: None,
+                    item_uid,
+                };
+                return Some((loc, interval));
             }
         }
         None
     }
 
+    // Meta tiles are only needed for the current view (by tooltips, search,
+    // etc.), but slots that aren't being rendered (e.g., collapsed ones
+    // included in a search) never clear them. Drop the rest when the zoom
+    // level changes, or when they take up too much memory, so that memory
+    // doesn't grow as the user moves around. (Panning back and forth at one
+    // level keeps them, so they don't need to be fetched again.)
+    fn evict_meta_tiles(&mut self, cx: &Context) {
+        const META_TILE_BUDGET: usize = 256 << 20;
+
+        if self.config.last_evict_interval == Some(cx.view_interval) {
+            return;
+        }
+        self.config.last_evict_interval = Some(cx.view_interval);
+
+        let keep: BTreeSet<_> = self
+            .config
+            .request_tiles(cx.view_interval)
+            .into_iter()
+            .collect();
+        if keep == self.config.last_evict_tiles {
+            return;
+        }
+        let tile_size = |tiles: &BTreeSet<TileID>| tiles.first().map(|t| t.0.duration_ns());
+        let zoomed = tile_size(&keep) != tile_size(&self.config.last_evict_tiles);
+        if zoomed || cx.bypass_tile_cache || self.panel.meta_tiles_size() > META_TILE_BUDGET {
+            self.panel.evict_meta_tiles(&keep, cx.bypass_tile_cache);
+        }
+        self.config.last_evict_tiles = keep;
+    }
+
+    // Converts a row (as in SlotTileData::items) into screen space
+    fn row_to_irow(&self, entry_id: &EntryID, row: u64) -> Option<usize> {
+        let rows = self.find_slot(entry_id)?.rows();
+        (row < rows).then(|| (rows - row - 1) as usize)
+    }
+
     fn find_item_meta(&self, entry_id: &EntryID, item_uid: ItemUID) -> Option<&ItemMeta> {
         let slot = self.find_slot(entry_id)?;
-        for tile in slot.tile_metas.values() {
-            let Some(tile) = tile else {
+        let (tile, row, index) = slot.lookup_item_meta(item_uid)?;
+        Some(&tile.items[row][index])
+    }
+
+    // Finds the item adjacent (in time) to the last selected item, within the
+    // same row of the same slot. Only considers tiles that are already loaded.
+    fn find_adjacent_item(&self, forward: bool) -> Option<(ItemLocator, Interval)> {
+        let loc = self.config.last_selected.as_ref()?;
+        let slot = self.find_slot(&loc.entry_id)?;
+        let irow = self.find_item_irow(&loc.entry_id, loc.item_uid)?;
+        let rows = slot.rows() as usize;
+        let row = rows - irow - 1;
+
+        // Items may be split across tiles, so reassemble their full intervals
+        let mut items: BTreeMap<ItemUID, Interval> = BTreeMap::new();
+        for tile in slot.tiles.values() {
+            let Some(Ok(tile)) = tile else {
+                continue;
+            };
+            let Some(row_items) = tile.items.get(row) else {
                 continue;
             };
-            for items in &tile.items {
-                for item in items {
-                    if item.item_uid == item_uid {
-                        return Some(item);
+            for item in row_items {
+                items
+                    .entry(item.item_uid)
+                    .and_modify(|i| *i = i.union(item.interval))
+                    .or_insert(item.interval);
+            }
+        }
+
+        let mut items: Vec<_> = items.into_iter().collect();
+        items.sort_by_key(|(item_uid, interval)| (interval.start, interval.stop, *item_uid));
+        let index = items.iter().position(|(uid, _)| *uid == loc.item_uid)?;
+        let (item_uid, interval) = if forward {
+            items.get(index + 1)?
+        } else {
+            items.get(index.checked_sub(1)?)?
+        };
+        Some((
+            ItemLocator {
+                entry_id: loc.entry_id.clone(),
+                irow: Some(irow),
+                row: None,
+                item_uid: *item_uid,
+            },
+            *interval,
+        ))
+    }
+
+    // Items overlapping the view in the loaded tiles of the slots being shown
+    fn visible_items(&self, view: Interval) -> BTreeMap<ItemUID, ItemLocator> {
+        let config = &self.config;
+        let mut result = BTreeMap::new();
+        for node in &self.panel.slots {
+            if !node.expanded || !Panel::is_slot_visible(node, config) {
+                continue;
+            }
+            for kind in &node.slots {
+                if !kind.expanded || !Panel::is_slot_visible(kind, config) {
+                    continue;
+                }
+                for slot in &kind.slots {
+                    if !slot.expanded || !Panel::is_slot_visible(slot, config) {
+                        continue;
+                    }
+                    let rows = slot.rows() as usize;
+                    for tile in slot.tiles.values() {
+                        let Some(Ok(tile)) = tile else {
+                            continue;
+                        };
+                        for (row, items) in tile.items.iter().enumerate() {
+                            for item in items.iter().filter(|item| item.interval.overlaps(view)) {
+                                result.entry(item.item_uid).or_insert_with(|| ItemLocator {
+                                    entry_id: slot.entry_id.clone(),
+                                    irow: Some(rows - row - 1),
+                                    row: None,
+                                    item_uid: item.item_uid,
+                                });
+                            }
+                        }
                     }
                 }
             }
         }
-        None
+        result
+    }
+
+    // Toggles the selection of every item in view. Items outside the view
+    // keep their selection.
+    fn invert_selection_in_view(&mut self, view: Interval) {
+        // Each selected item gets a window (unless shown in the pane), so
+        // don't select an unreasonable number at once
+        const MAX_NEW_ITEMS: usize = 100;
+
+        let visible = self.visible_items(view);
+        let config = &mut self.config;
+        let mut added = 0;
+        for (item_uid, loc) in visible {
+            if config.items_selected.remove(&item_uid).is_none() && added < MAX_NEW_ITEMS {
+                let detail = ItemDetail { meta: None, loc };
+                config.items_selected.insert(item_uid, detail);
+                added += 1;
+            }
+        }
+        config.last_selected = None;
+    }
+
+    fn step_selected_item(&mut self, forward: bool, cx: &mut Context) {
+        let Some((item_loc, interval)) = self.find_adjacent_item(forward) else {
+            return;
+        };
+
+        // Replace the current item with the new one
+        if let Some(last) = self.config.last_selected.take() {
+            self.config.items_selected.remove(&last.item_uid);
+        }
+        self.config.last_selected = Some(item_loc.clone());
+        self.config.items_selected.insert(
+            item_loc.item_uid,
+            ItemDetail {
+                meta: None,
+                loc: item_loc,
+            },
+        );
+
+        // Pan just enough to keep the new item in view
+        let view = cx.view_interval;
+        let shift = if interval.start < view.start {
+            interval.start.0 - view.start.0
+        } else if interval.stop > view.stop {
+            (interval.stop.0 - view.stop.0).min(interval.start.0 - view.start.0)
+        } else {
+            return;
+        };
+        ProfApp::update_view_interval(cx, view.translate(shift), IntervalOrigin::Pan);
+        ProfApp::update_interval_select_state(cx);
+    }
+
+    fn selection_actions(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        let count = self.config.items_selected.len();
+        if count == 0 {
+            return;
+        }
+        ui.separator();
+        ui.label(format!("{} selected", count));
+        if ui.small_button("Zoom to Selected").clicked() {
+            if let Some(interval) = self.config.selection_interval() {
+                ProfApp::zoom(cx, interval.grow((interval.duration_ns() / 20).max(1)));
+            }
+        }
+        if ui
+            .small_button("Invert in View")
+            .on_hover_text("Select the items in view that aren't selected, and deselect the rest")
+            .clicked()
+        {
+            self.invert_selection_in_view(cx.view_interval);
+        }
+        if count >= 2 && ui.small_button("Compare").clicked() {
+            self.config.compare_items = true;
+        }
+        if ui.small_button("Clear").clicked() {
+            self.config.clear_selection();
+        }
     }
 
     fn content(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        cx.linked_titles = cx
+            .selected_titles
+            .iter()
+            .filter(|(_, windows)| windows.iter().any(|index| *index != self.index))
+            .map(|(title, _)| title.clone())
+            .collect();
+
         ui.horizontal(|ui| {
             ui.heading(format!("Profile {}", self.index));
-            ui.label(cx.view_interval.to_string());
+            ui.label(cx.format_interval(cx.view_interval));
             if let Some(message) = &self.config.warning_message {
                 ui.label(RichText::new(message).color(Color32::RED));
             }
+            self.selection_actions(ui, cx);
         });
 
-        ScrollArea::vertical()
-            .auto_shrink([false; 2])
-            .show_viewport(ui, |ui, viewport| {
-                let height = self.panel.height(None, &self.config, cx);
-                ui.set_height(height);
-                ui.set_width(ui.available_width());
-
-                let rect = Rect::from_min_size(ui.min_rect().min, viewport.size());
-
-                let scroll_to = |irow, prefix_height| {
-                    let mut item_rect =
-                        rect.translate(Vec2::new(0.0, prefix_height + irow as f32 * cx.row_height));
-                    item_rect.set_height(cx.row_height);
-                    ui.scroll_to_rect(item_rect, Some(egui::Align::Center));
-                };
+        if self.config.interval.duration_ns() <= 0 {
+            ui.label(format!(
+                "This profile is empty: it covers no time ({}), so there is nothing to show.",
+                cx.format_interval(self.config.interval)
+            ));
+            return;
+        }
 
-                // First scroll attempt goes to the processor
-                if let Some(ItemLocator {
-                    ref entry_id, irow, ..
-                }) = self.config.scroll_to_item
-                {
-                    let prefix_height = self.panel.height(Some(entry_id), &self.config, cx);
-                    scroll_to(irow.unwrap_or(0), prefix_height);
-                    if irow.is_none() {
-                        let mut item = None;
-                        std::mem::swap(&mut item, &mut self.config.scroll_to_item);
-                        self.config.scroll_to_item_retry = item;
-                    }
-                    self.config.scroll_to_item = None;
+        let mut scroll_area = ScrollArea::vertical().auto_shrink([false; 2]);
+        if let Some(offset) = self.config.restore_scroll_offset.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let output = scroll_area.show_viewport(ui, |ui, viewport| {
+            let height = self.panel.height(None, &self.config, cx);
+            ui.set_height(height);
+            ui.set_width(ui.available_width());
+
+            let rect = Rect::from_min_size(ui.min_rect().min, viewport.size());
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                self.config.view_width = rect.width();
+            }
+
+            let scroll_to = |irow, prefix_height| {
+                let mut item_rect =
+                    rect.translate(Vec2::new(0.0, prefix_height + irow as f32 * cx.row_height));
+                item_rect.set_height(cx.row_height);
+                ui.scroll_to_rect(item_rect, Some(egui::Align::Center));
+            };
+
+            // First scroll attempt goes to the processor, unless the item
+            // link told us the row (then we go straight to the item)
+            if let Some(ItemLocator {
+                ref entry_id,
+                irow,
+                row,
+                ..
+            }) = self.config.scroll_to_item
+            {
+                let irow = irow.or_else(|| self.row_to_irow(entry_id, row?));
+                let prefix_height = self.panel.height(Some(entry_id), &self.config, cx);
+                scroll_to(irow.unwrap_or(0), prefix_height);
+                if irow.is_none() {
+                    let mut item = None;
+                    std::mem::swap(&mut item, &mut self.config.scroll_to_item);
+                    self.config.scroll_to_item_retry = item;
                 }
+                self.config.scroll_to_item = None;
+            }
 
-                // If we're able to find the item, we do a second scroll to the item
-                let mut found_irow = None;
-                if let Some(ItemLocator {
-                    ref entry_id,
-                    irow,
-                    item_uid,
-                }) = self.config.scroll_to_item_retry
-                {
-                    assert!(irow.is_none());
-                    found_irow = self.find_item_irow(entry_id, item_uid);
+            // If we're able to find the item, we do a second scroll to the item
+            let mut found_irow = None;
+            if let Some(ItemLocator {
+                ref entry_id,
+                irow,
+                item_uid,
+                ..
+            }) = self.config.scroll_to_item_retry
+            {
+                assert!(irow.is_none());
+                found_irow = self.find_item_irow(entry_id, item_uid);
+            }
+
+            if let Some(ItemLocator { ref entry_id, .. }) = self.config.scroll_to_item_retry {
+                if let Some(irow) = found_irow {
+                    let prefix_height = self.panel.height(Some(entry_id), &self.config, cx);
+                    scroll_to(irow, prefix_height);
+                    self.config.scroll_to_item_retry = None;
                 }
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            cx.render_budget.scroll_to(self.index, viewport.min.y);
 
-                if let Some(ItemLocator { ref entry_id, .. }) = self.config.scroll_to_item_retry {
-                    if let Some(irow) = found_irow {
-                        let prefix_height = self.panel.height(Some(entry_id), &self.config, cx);
-                        scroll_to(irow, prefix_height);
-                        self.config.scroll_to_item_retry = None;
-                    }
-                }
+            // Root panel has no label
+            self.panel.content(ui, rect, viewport, &mut self.config, cx);
+        });
+        self.config.scroll_offset = output.state.offset.y;
+    }
 
-                // Root panel has no label
-                self.panel.content(ui, rect, viewport, &mut self.config, cx);
+    fn info(&self, ui: &mut egui::Ui, cx: &mut Context) {
+        ui.subheading("Info", cx);
+        let locator = self.source_locator();
+        let nodes = self.panel.slots.len();
+        let slots: usize = self
+            .panel
+            .slots
+            .iter()
+            .flat_map(|node| &node.slots)
+            .map(|kind| kind.slots.len())
+            .sum();
+        egui::Grid::new(("profile_info", self.index))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Source:");
+                ui.label(&locator).on_hover_text(&locator);
+                ui.end_row();
+                ui.label("Duration:");
+                ui.label(cx.format_interval(self.config.interval));
+                ui.end_row();
+                ui.label("Nodes:");
+                ui.label(nodes.to_string());
+                ui.end_row();
+                ui.label("Kinds:");
+                ui.label(self.config.kinds.join(", "));
+                ui.end_row();
+                ui.label("Slots:");
+                ui.label(slots.to_string());
+                ui.end_row();
             });
+        if ui
+            .button("Duplicate Window")
+            .on_hover_text("Open another view of this profile")
+            .clicked()
+        {
+            cx.duplicate_window = Some(self.index);
+        }
     }
 
     fn node_selection(&mut self, ui: &mut egui::Ui, cx: &Context) {
@@ -1600,9 +4668,44 @@ impl Window {
                 }
             }
         });
+        ui.horizontal(|ui| {
+            let any = !self.config.visibility_overrides.is_empty();
+            if ui
+                .add_enabled(any, egui::Button::new("Clear Solo/Mute"))
+                .on_hover_text("Right-click a label to solo or mute it")
+                .clicked()
+            {
+                self.config.visibility_overrides.clear();
+            }
+            if ui
+                .add_enabled(self.has_filters(), egui::Button::new("Clear All Filters"))
+                .on_hover_text("Show all nodes and kinds, and clear solo/mute")
+                .clicked()
+            {
+                self.clear_filters();
+            }
+        });
+    }
+
+    fn has_filters(&self) -> bool {
+        let total = self.panel.slots.len().saturating_sub(1) as u64;
+        let config = &self.config;
+        config.min_node != 0
+            || config.max_node != total
+            || !config.kind_filter.is_empty()
+            || !config.visibility_overrides.is_empty()
+    }
+
+    // Undoes node selection, kind filtering and solo/mute all at once
+    fn clear_filters(&mut self) {
+        let total = self.panel.slots.len().saturating_sub(1) as u64;
+        self.config.min_node = 0;
+        self.config.max_node = total;
+        self.config.kind_filter.clear();
+        self.config.visibility_overrides.clear();
     }
 
-    fn expand_collapse(&mut self, ui: &mut egui::Ui, cx: &Context) {
+    fn expand_collapse(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
         let mut toggle_all = |label, toggle| {
             for node in &mut self.panel.slots {
                 for kind in &mut node.slots {
@@ -1630,14 +4733,264 @@ impl Window {
                 }
             }
         });
+        ui.label("Expand when opened:");
+        ui.horizontal_wrapped(|ui| {
+            for kind in &self.config.kinds {
+                let key = kind.to_lowercase();
+                let mut expanded = cx.kind_expanded.get(&key).copied().unwrap_or(false);
+                if ui.checkbox(&mut expanded, kind).changed() {
+                    cx.kind_expanded.insert(key, expanded);
+                }
+            }
+        });
+    }
+
+    // Loads meta tiles like search does (only while open), so this is limited
+    // to the same entries
+    fn duration_histogram(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        ui.subheading("Item Durations", cx);
+        egui::CollapsingHeader::new("Histogram of Items in View")
+            .id_source(("Duration Histogram", self.index))
+            .show(ui, |ui| {
+                let selected = self
+                    .config
+                    .last_selected
+                    .as_ref()
+                    .map(|loc| loc.entry_id.clone());
+                ui.horizontal(|ui| {
+                    let scope = &mut self.config.histogram_selected_slot;
+                    ui.radio_value(scope, false, "All visible");
+                    ui.add_enabled_ui(selected.is_some(), |ui| {
+                        ui.radio_value(scope, true, "Selected item's processor");
+                    });
+                });
+
+                let mut durations = Vec::new();
+                match selected.filter(|_| self.config.histogram_selected_slot) {
+                    Some(entry_id) => {
+                        self.inflate_meta(&entry_id, cx);
+                        let slot = self.find_slot(&entry_id).unwrap();
+                        slot.item_durations(&self.config, cx.view_interval, &mut durations);
+                    }
+                    None => {
+                        self.panel.inflate_meta(&mut self.config, cx);
+                        let panel = &self.panel;
+                        panel.item_durations(&self.config, cx.view_interval, &mut durations);
+                    }
+                }
+
+                let histogram = duration_histogram(&durations);
+                if histogram.is_empty() {
+                    ui.label("No items loaded in view.");
+                    return;
+                }
+                let search_state = &mut self.config.search_state;
+                if let Some(bucket) =
+                    Self::histogram_chart(ui, &histogram, search_state.duration_range, cx)
+                {
+                    search_state.duration_range = Some(bucket);
+                }
+                ui.label(format!(
+                    "{} items, {} to {}. Click a bar to search for its items.",
+                    durations.len(),
+                    cx.format_duration(histogram[0].0 .0),
+                    cx.format_duration(histogram[histogram.len() - 1].0 .1)
+                ));
+            });
+    }
+
+    // Like duration_histogram, computed from whatever meta tiles have loaded
+    // so far, and refined as the rest arrive
+    fn statistics(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        ui.subheading("Statistics", cx);
+        egui::CollapsingHeader::new("Busy Time and Durations in View")
+            .id_source(("Statistics", self.index))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let by_processor = &mut self.config.stats_by_processor;
+                    ui.label("Group by:");
+                    ui.radio_value(by_processor, false, "Kind");
+                    ui.radio_value(by_processor, true, "Processor");
+                });
+
+                // Only recompute when something has changed
+                let config = &self.config;
+                let mut hasher = DefaultHasher::new();
+                let slots = self
+                    .panel
+                    .searched_slots(config)
+                    .flat_map(|node| node.searched_slots(config))
+                    .flat_map(|kind| kind.searched_slots(config));
+                for slot in slots {
+                    slot.entry_id.hash(&mut hasher);
+                    let loaded = slot.tile_metas.values().filter(|t| t.is_some()).count();
+                    loaded.hash(&mut hasher);
+                }
+                let key = (cx.view_interval, config.stats_by_processor, hasher.finish());
+                let mut rows = match self.config.stats_cache.take() {
+                    Some((cached, rows)) if cached == key => rows,
+                    _ => {
+                        self.panel.inflate_meta(&mut self.config, cx);
+                        self.group_stats(cx.view_interval)
+                    }
+                };
+                let (column, descending) = self.config.stats_sort;
+                sort_stats(&mut rows, column, descending);
+
+                if self.config.data_source.outstanding_requests() > 0 {
+                    ui.label("Computing…");
+                }
+                if rows.is_empty() {
+                    ui.label("No processors shown.");
+                } else {
+                    ui.push_id(("Statistics Table", self.index), |ui| {
+                        Self::stats_table(ui, &rows, &mut self.config.stats_sort, cx);
+                    });
+                }
+                self.config.stats_cache = Some((key, rows));
+            });
+    }
+
+    // Statistics of the items in view, grouped by kind or processor
+    fn group_stats(&self, view: Interval) -> Vec<(String, ItemStats)> {
+        let config = &self.config;
+        let mut groups: BTreeMap<String, ItemStats> = BTreeMap::new();
+        for node in self.panel.searched_slots(config) {
+            for kind in node.searched_slots(config) {
+                for slot in kind.searched_slots(config) {
+                    let stats = slot.item_stats(view);
+                    let name = if config.stats_by_processor {
+                        &slot.long_name
+                    } else {
+                        kind.label_text()
+                    };
+                    groups.entry(name.to_owned()).or_default().merge(stats);
+                }
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(name, mut stats)| {
+                stats.sort();
+                (name, stats)
+            })
+            .collect()
+    }
+
+    // Clicking a column header sorts by it, or reverses the order if it's
+    // already sorted by it
+    fn stats_table(
+        ui: &mut egui::Ui,
+        rows: &[(String, ItemStats)],
+        sort: &mut (StatsColumn, bool),
+        cx: &Context,
+    ) {
+        let font_id = TextStyle::Body.resolve(ui.style());
+        let row_height = ui.fonts(|f| f.row_height(&font_id));
+        let duration = |ns: Option<i64>| ns.map_or_else(String::new, |ns| cx.format_duration(ns));
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(300.0)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto().clip(true))
+            .columns(Column::auto(), StatsColumn::ALL.len() - 1)
+            .header(row_height, |mut header| {
+                for column in StatsColumn::ALL {
+                    header.col(|ui| {
+                        let mut text = column.label_text().to_owned();
+                        if sort.0 == column {
+                            text.push_str(if sort.1 { " ⏷" } else { " ⏶" });
+                        }
+                        if ui.button(text).clicked() {
+                            *sort = (column, sort.0 == column && !sort.1);
+                        }
+                    });
+                }
+            })
+            .body(|mut body| {
+                for (name, stats) in rows {
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            ui.label(name);
+                        });
+                        row.col(|ui| {
+                            ui.label(cx.format_duration(stats.busy_ns));
+                        });
+                        row.col(|ui| {
+                            ui.label(stats.durations.len().to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(duration(stats.mean_ns()));
+                        });
+                        row.col(|ui| {
+                            ui.label(duration(stats.median_ns()));
+                        });
+                    });
+                }
+            });
+    }
+
+    // Draws the histogram as a bar chart, and returns the bucket clicked
+    fn histogram_chart(
+        ui: &mut egui::Ui,
+        histogram: &[((i64, i64), usize)],
+        selected: Option<(i64, i64)>,
+        cx: &Context,
+    ) -> Option<(i64, i64)> {
+        const HEIGHT: f32 = 80.0;
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(ui.available_width(), HEIGHT),
+            egui::Sense::click(),
+        );
+        let rect = response.rect;
+        let visuals = ui.visuals();
+        painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+
+        let max_count = histogram.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        let width = rect.width() / histogram.len() as f32;
+        let mut hovered = None;
+        for (i, (bucket, count)) in histogram.iter().enumerate() {
+            let left = rect.min.x + i as f32 * width;
+            let column = Rect::from_x_y_ranges(left..=left + width, rect.y_range());
+            let height = rect.height() * *count as f32 / max_count.max(1) as f32;
+            let bar = Rect::from_min_max(
+                Pos2::new(column.min.x + 1.0, rect.max.y - height),
+                Pos2::new(column.max.x - 1.0, rect.max.y),
+            );
+            let hover = response.hover_pos().is_some_and(|h| column.contains(h));
+            let color = if selected == Some(*bucket) {
+                visuals.selection.bg_fill
+            } else if hover {
+                visuals.widgets.hovered.fg_stroke.color
+            } else {
+                visuals.widgets.inactive.fg_stroke.color
+            };
+            painter.rect_filled(bar, 0.0, color);
+            if hover {
+                hovered = Some((*bucket, *count));
+            }
+        }
+
+        let ((min, max), count) = hovered?;
+        let clicked = response.clicked();
+        response.on_hover_text(format!(
+            "{} to {}: {} items",
+            cx.format_duration(min),
+            cx.format_duration(max),
+            count
+        ));
+        clicked.then_some((min, max))
     }
 
     fn select_interval(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        const TIME_SYNTAX_HELP: &str =
+            "A number followed by a unit (ns, us, ms or s), e.g. 1.5 ms or 1500us";
         ui.subheading("Interval", cx);
         let start_res = ui
             .horizontal(|ui| {
                 ui.label("Start:");
                 ui.text_edit_singleline(&mut cx.interval_select_state.start_buffer)
+                    .on_hover_text(TIME_SYNTAX_HELP)
             })
             .inner;
 
@@ -1649,6 +5002,7 @@ impl Window {
             .horizontal(|ui| {
                 ui.label("Stop:");
                 ui.text_edit_singleline(&mut cx.interval_select_state.stop_buffer)
+                    .on_hover_text(TIME_SYNTAX_HELP)
             })
             .inner;
 
@@ -1657,9 +5011,9 @@ impl Window {
         }
 
         if start_res.lost_focus()
-            && cx.interval_select_state.start_buffer != cx.view_interval.start.to_string()
+            && cx.interval_select_state.start_buffer != cx.format_timestamp(cx.view_interval.start)
         {
-            match Timestamp::parse(&cx.interval_select_state.start_buffer) {
+            match cx.parse_timestamp(&cx.interval_select_state.start_buffer) {
                 Ok(start) => {
                     // validate timestamp
                     if start > cx.view_interval.stop {
@@ -1681,9 +5035,9 @@ impl Window {
             }
         }
         if stop_res.lost_focus()
-            && cx.interval_select_state.stop_buffer != cx.view_interval.stop.to_string()
+            && cx.interval_select_state.stop_buffer != cx.format_timestamp(cx.view_interval.stop)
         {
-            match Timestamp::parse(&cx.interval_select_state.stop_buffer) {
+            match cx.parse_timestamp(&cx.interval_select_state.stop_buffer) {
                 Ok(stop) => {
                     // validate timestamp
                     if stop < cx.view_interval.start {
@@ -1701,17 +5055,340 @@ impl Window {
         }
     }
 
+    fn view_history(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        let mut jump = None;
+        egui::CollapsingHeader::new("History")
+            .id_source(("view_history", self.index))
+            .show(ui, |ui| {
+                ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    let history = &cx.view_interval_history;
+                    // Most recent first
+                    let entries = history.levels.iter().zip(&history.origins).enumerate();
+                    for (index, (interval, origin)) in entries.rev() {
+                        let origin = match origin {
+                            IntervalOrigin::Zoom => "Zoom",
+                            IntervalOrigin::Pan => "Pan",
+                        };
+                        let text = format!(
+                            "{}: {} ({})",
+                            origin,
+                            cx.format_interval(*interval),
+                            cx.format_duration(interval.duration_ns())
+                        );
+                        if ui.selectable_label(index == history.index, text).clicked() {
+                            jump = Some(index);
+                        }
+                    }
+                });
+            });
+        if let Some(index) = jump {
+            ProfApp::jump_to_history(cx, index);
+        }
+    }
+
     fn controls(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
         const WIDGET_PADDING: f32 = 8.0;
         ui.heading(format!("Profile {}: Controls", self.index));
         ui.add_space(WIDGET_PADDING);
+        self.info(ui, cx);
+        ui.add_space(WIDGET_PADDING);
         self.node_selection(ui, cx);
         ui.add_space(WIDGET_PADDING);
         self.filter_by_kind(ui, cx);
         ui.add_space(WIDGET_PADDING);
         self.expand_collapse(ui, cx);
         ui.add_space(WIDGET_PADDING);
+        // Dynamic profiles make tiles to order, so there's nothing to pick
+        if self.config.tile_set.tiles.len() > 1 {
+            self.tile_level(ui, cx);
+            ui.add_space(WIDGET_PADDING);
+        }
+        self.color_by(ui, cx);
+        ui.add_space(WIDGET_PADDING);
+        self.item_fields(ui, cx);
+        ui.add_space(WIDGET_PADDING);
+        self.duration_histogram(ui, cx);
+        ui.add_space(WIDGET_PADDING);
+        self.statistics(ui, cx);
+        ui.add_space(WIDGET_PADDING);
         self.select_interval(ui, cx);
+        self.view_history(ui, cx);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.add_space(WIDGET_PADDING);
+            self.export_svg_ui(ui, cx);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg_ui(&mut self, ui: &mut egui::Ui, cx: &Context) {
+        ui.subheading("Export SVG", cx);
+        ui.horizontal(|ui| {
+            ui.label("Export to:");
+            ui.text_edit_singleline(&mut self.config.svg_path);
+            if ui
+                .button("Export SVG")
+                .on_hover_text("Write the current view, as drawn, to a vector image")
+                .clicked()
+            {
+                let path = self.config.svg_path.clone();
+                let status = match self.export_svg(Path::new(&path), ui, cx) {
+                    Ok(()) => format!("Wrote {}", path),
+                    Err(e) => format!("Export failed: {}", e),
+                };
+                self.config.svg_status = Some(status);
+            }
+        });
+        if let Some(status) = &self.config.svg_status {
+            ui.label(status);
+        }
+    }
+
+    // Writes the timeline as it's laid out on screen, with whatever tiles are
+    // currently loaded. Horizontally this covers the view interval, and
+    // vertically every visible entry (not just the ones scrolled into view).
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_svg(&mut self, path: &Path, ui: &egui::Ui, cx: &Context) -> io::Result<()> {
+        let width = self.config.view_width.at_least(1.0);
+        let height = self.panel.height(None, &self.config, cx);
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(width, height));
+
+        let mut svg = SvgWriter::new();
+        svg.rect(rect, ui.visuals().panel_fill);
+        self.panel
+            .export_svg(&mut svg, rect, ui, &mut self.config, cx);
+        std::fs::write(path, svg.finish(width, height))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_chrome_trace_ui(&mut self, ui: &mut egui::Ui, cx: &Context) {
+        let idle = self.config.trace_export.is_none();
+        ui.add_enabled_ui(idle, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Profile {} to:", self.index));
+                ui.text_edit_singleline(&mut self.config.trace_path);
+                if ui
+                    .button("Export Chrome Trace")
+                    .on_hover_text(
+                        "Write every item in the profile, for chrome://tracing or Perfetto",
+                    )
+                    .clicked()
+                {
+                    self.start_chrome_trace(cx);
+                }
+            });
+        });
+        if let Some(status) = &self.config.trace_status {
+            ui.label(status);
+        }
+    }
+
+    // Starts writing the entire profile (not just the view), fetching full
+    // meta tiles through a separate handle so that the window's own requests
+    // are left alone. See poll_chrome_trace.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_chrome_trace(&mut self, cx: &Context) {
+        let data_source = self.config.data_source.data_source().handle();
+        let writer = ChromeTraceWriter::new(data_source, &self.config.trace_path, true)
+            .with_hidden_fields(cx.hidden_fields.clone());
+        self.config.trace_export = Some(writer);
+        self.config.trace_status = Some("Exporting...".to_owned());
+    }
+
+    // Advances the Chrome trace export, if any, by whatever tiles have
+    // arrived. Returns true while it's still running.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poll_chrome_trace(&mut self) -> bool {
+        let Some(writer) = &mut self.config.trace_export else {
+            return false;
+        };
+        let status = match writer.poll() {
+            Ok(false) => {
+                let (written, total) = writer.progress();
+                if total > 0 {
+                    self.config.trace_status =
+                        Some(format!("Exporting: {} of {} tiles", written, total));
+                }
+                return true;
+            }
+            Ok(true) => format!("Wrote {}", self.config.trace_path),
+            Err(e) => format!("Export failed: {}", e),
+        };
+        self.config.trace_export = None;
+        self.config.trace_status = Some(status);
+        false
+    }
+
+    // Shows one of the selected items, with buttons to step through the rest
+    fn detail_pane(&mut self, ui: &mut egui::Ui, cx: &Context) -> Option<(ItemLocator, Interval)> {
+        let config = &mut self.config;
+        let count = config.items_selected.len();
+        if count == 0 {
+            return None;
+        }
+
+        let last_selected = config.last_selected.as_ref().map(|loc| loc.item_uid);
+        if last_selected != config.detail_last_selected {
+            if let Some(uid) = last_selected {
+                if let Some(index) = config.items_selected.keys().position(|k| *k == uid) {
+                    config.detail_index = index;
+                }
+            }
+            config.detail_last_selected = last_selected;
+        }
+        config.detail_index = config.detail_index.min(count - 1);
+
+        ui.subheading(format!("Profile {}: Selected Items", self.index), cx);
+        let mut deselect = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(config.detail_index > 0, egui::Button::new("⏴ Prev"))
+                .clicked()
+            {
+                config.detail_index -= 1;
+            }
+            ui.label(format!("{} of {}", config.detail_index + 1, count));
+            if ui
+                .add_enabled(config.detail_index + 1 < count, egui::Button::new("Next ⏵"))
+                .clicked()
+            {
+                config.detail_index += 1;
+            }
+            if ui.button("Deselect").clicked() {
+                deselect = config
+                    .items_selected
+                    .keys()
+                    .nth(config.detail_index)
+                    .copied();
+            }
+        });
+
+        let item = config.items_selected.values().nth(config.detail_index)?;
+        let target = ui
+            .push_id(self.index, |ui| {
+                ProfApp::display_item_details(ui, item, &config.field_schema, cx)
+            })
+            .inner;
+        if let Some(uid) = deselect {
+            config.deselect_item(uid);
+        }
+        target
+    }
+
+    fn tile_level(&mut self, ui: &mut egui::Ui, cx: &Context) {
+        let levels = &self.config.tile_set.tiles;
+        ui.subheading("Detail Level", cx);
+        let tile_level = &mut self.config.tile_level;
+        let before = *tile_level;
+        let text = |level: usize| format!("Level {} ({} tiles)", level, levels[level].len());
+        egui::ComboBox::from_id_source(("Detail Level", self.index))
+            .selected_text(tile_level.map_or("Auto".to_owned(), text))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(tile_level, None, "Auto");
+                for level in 0..levels.len() {
+                    ui.selectable_value(tile_level, Some(level), text(level));
+                }
+            })
+            .response
+            .on_hover_text("Finer levels show more detail when zoomed out, but load more data");
+        if *tile_level != before {
+            self.config.last_evict_interval = None;
+            self.config.search_state.clear();
+            self.panel.clear_tiles();
+        }
+    }
+
+    fn color_by(&mut self, ui: &mut egui::Ui, cx: &Context) {
+        ui.subheading("Color by Field", cx);
+        let schema = &self.config.field_schema;
+        let color_by = &mut self.config.color_by;
+        let before = *color_by;
+        let selected = color_by.map_or("None (item colors)", |field_id| {
+            schema.get_name(field_id).unwrap()
+        });
+        egui::ComboBox::from_id_source("Color by")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(color_by, None, "None (item colors)");
+                for (field_id, name) in schema.fields() {
+                    ui.selectable_value(color_by, Some(field_id), name);
+                }
+            });
+        if *color_by != before {
+            self.config.color_by_range = None;
+        }
+    }
+
+    fn item_fields(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        ui.subheading("Item Fields", cx);
+        let schema = &self.config.field_schema;
+        let hidden = &mut cx.hidden_fields;
+        let order = &mut cx.field_order;
+        egui::CollapsingHeader::new(format!(
+            "Shown in Details ({} hidden)",
+            schema
+                .fields()
+                .filter(|(_, name)| hidden.contains(*name))
+                .count()
+        ))
+        .id_source(("Item Fields", self.index))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Show All").clicked() {
+                    for (_, name) in schema.fields() {
+                        hidden.remove(name);
+                    }
+                }
+                if ui.button("Hide All").clicked() {
+                    for (_, name) in schema.fields() {
+                        hidden.insert(name.to_owned());
+                    }
+                }
+                if ui.button("Reset Order").clicked() {
+                    order.retain(|name| !schema.contains_name(name));
+                }
+            });
+            ScrollArea::vertical()
+                .id_source(("Item Fields Scroll", self.index))
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    let mut names: Vec<_> = schema.fields().map(|(_, name)| name).collect();
+                    names.sort_by_key(|name| field_rank(order, name));
+                    let mut swap = None;
+                    for (i, name) in names.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            let up = ui.add_enabled(i > 0, egui::Button::new("⏶").small());
+                            if up.clicked() {
+                                swap = Some(i - 1);
+                            }
+                            let down =
+                                ui.add_enabled(i + 1 < names.len(), egui::Button::new("⏷").small());
+                            if down.clicked() {
+                                swap = Some(i);
+                            }
+                            let mut shown = !hidden.contains(*name);
+                            if ui.checkbox(&mut shown, *name).changed() {
+                                if shown {
+                                    hidden.remove(*name);
+                                } else {
+                                    hidden.insert(name.to_string());
+                                }
+                            }
+                        });
+                    }
+                    // Moving a field pins the order of all of this profile's
+                    // fields, keeping those of other profiles after them
+                    if let Some(i) = swap {
+                        names.swap(i, i + 1);
+                        let rest = order.iter().filter(|name| !schema.contains_name(name));
+                        *order = names
+                            .iter()
+                            .map(|name| name.to_string())
+                            .chain(rest.cloned())
+                            .collect();
+                    }
+                });
+        });
     }
 
     fn search(&mut self, cx: &mut Context) {
@@ -1721,7 +5398,7 @@ impl Window {
         // If search query empty, skip search. (Note: do this after
         // invalidating cache, otherwise we get leftover search results when
         // clearing the query.)
-        if self.config.search_state.query.is_empty() {
+        if !self.config.search_state.is_active() {
             return;
         }
 
@@ -1729,12 +5406,81 @@ impl Window {
         self.panel.inflate_meta(&mut self.config, cx);
 
         // Search whatever data we have. Results are cached by entry/tile.
+        #[cfg(not(target_arch = "wasm32"))]
+        let timer = cx.start_timer();
         self.panel.search(&mut self.config);
+        #[cfg(not(target_arch = "wasm32"))]
+        cx.stop_timer("Search", timer);
 
         // Cache is now full and we can highlight/render the entries.
     }
 
     fn search_box(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        if self.config.search_state.is_numeric() {
+            self.search_range_box(ui);
+        } else {
+            self.search_query_box(ui);
+        }
+        ui.horizontal(|ui| {
+            ui.label("Search field:");
+            let schema = &self.config.field_schema;
+            let search_state = &mut self.config.search_state;
+            let mut selected = search_state.search_field;
+            egui::ComboBox::from_id_source("Search field")
+                .selected_text(schema.get_name(selected).unwrap())
+                .show_ui(ui, |ui| {
+                    for field in schema.searchable().union(schema.numeric()) {
+                        let name = schema.get_name(*field).unwrap();
+                        ui.selectable_value(&mut selected, *field, name);
+                    }
+                });
+            search_state.set_search_field(selected);
+        });
+        if !self.config.search_state.is_numeric() {
+            let search_state = &mut self.config.search_state;
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut search_state.whole_word, "Match whole words only")
+                    .changed()
+                    && search_state.whole_word
+                {
+                    search_state.regex_mode = false;
+                }
+                if ui
+                    .checkbox(&mut search_state.regex_mode, "Regular expression")
+                    .changed()
+                    && search_state.regex_mode
+                {
+                    search_state.whole_word = false;
+                }
+            });
+        }
+        ui.checkbox(
+            &mut self.config.search_state.include_collapsed_entries,
+            "Include collapsed processors",
+        );
+        ui.checkbox(
+            &mut self.config.search_state.restrict_to_view,
+            "Only items in the current view",
+        );
+        let search_state = &mut self.config.search_state;
+        if let Some((min, max)) = search_state.duration_range {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Duration: {} to {}",
+                    cx.format_duration(min),
+                    cx.format_duration(max)
+                ));
+                if ui.button("✖").clicked() {
+                    search_state.duration_range = None;
+                }
+            });
+        }
+
+        self.search(cx);
+    }
+
+    fn search_query_box(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             // Hack: need to estimate the button width or else the text box
             // overflows. Refer to the source for egui::widgets::Button::ui
@@ -1750,38 +5496,76 @@ impl Window {
             let query_size = ui.available_size().x - button_size.x - ui.spacing().item_spacing.x;
             egui::TextEdit::singleline(&mut self.config.search_state.query)
                 .desired_width(query_size)
-                .show(ui);
+                .show(ui)
+                .response
+                .on_hover_text(
+                    "Prefix a word with - to exclude it, or \\- to match a leading dash",
+                );
             if ui.button(button_label).clicked() {
                 self.config.search_state.query.clear();
             }
         });
-        ui.horizontal(|ui| {
-            ui.label("Search field:");
-            let schema = &self.config.field_schema;
-            let search_field = &mut self.config.search_state.search_field;
-            egui::ComboBox::from_id_source("Search field")
-                .selected_text(schema.get_name(*search_field).unwrap())
-                .show_ui(ui, |ui| {
-                    for field in schema.searchable() {
-                        let name = schema.get_name(*field).unwrap();
-                        ui.selectable_value(search_field, *field, name);
-                    }
-                });
-        });
-        ui.checkbox(
-            &mut self.config.search_state.whole_word,
-            "Match whole words only",
-        );
-        ui.checkbox(
-            &mut self.config.search_state.include_collapsed_entries,
-            "Include collapsed processors",
-        );
+        let search_state = &self.config.search_state;
+        if let Some(error) = &search_state.regex_error {
+            ui.label(RichText::new(error).color(Color32::RED).monospace());
+        } else if search_state.is_exclude_only() && search_state.duration_range.is_none() {
+            ui.label("Add a word to search for; excluded words only narrow a search");
+        }
+    }
+
+    // For numeric fields: the query is min..max, edited as two boxes
+    fn search_range_box(&mut self, ui: &mut egui::Ui) {
+        let search_state = &mut self.config.search_state;
+        let min = &mut search_state.range_min;
+        let max = &mut search_state.range_max;
+
+        // Follow the query if it changed some other way (e.g., from a link),
+        // as long as it's a range
+        if search_state.query != format_search_range(min, max) {
+            if let Some((new_min, new_max)) = search_state.query.split_once("..") {
+                *min = new_min.trim().to_owned();
+                *max = new_max.trim().to_owned();
+            }
+        }
 
-        self.search(cx);
+        let changed = ui
+            .horizontal(|ui| {
+                ui.label("From");
+                let mut changed = egui::TextEdit::singleline(min)
+                    .hint_text("min")
+                    .desired_width(80.0)
+                    .show(ui)
+                    .response
+                    .changed();
+                ui.label("to");
+                changed |= egui::TextEdit::singleline(max)
+                    .hint_text("max")
+                    .desired_width(80.0)
+                    .show(ui)
+                    .response
+                    .changed();
+                if ui.button("✖").clicked() {
+                    min.clear();
+                    max.clear();
+                    changed = true;
+                }
+                changed
+            })
+            .inner;
+        // Only overwrite the query when the range is edited
+        if changed {
+            search_state.query = format_search_range(min, max);
+        }
+        if !search_state.query.is_empty() && search_state.range.is_none() {
+            ui.label(
+                RichText::new("Enter whole numbers, optionally with a unit like 1MB or 4KiB")
+                    .color(Color32::RED),
+            );
+        }
     }
 
     fn search_results(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
-        if self.config.search_state.query.is_empty() {
+        if !self.config.search_state.is_active() {
             ui.label("Enter a search to see results displayed here.");
             return;
         }
@@ -1803,8 +5587,29 @@ impl Window {
             ui.label(format!("Found {} results.", num_results));
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        self.export_search_results_ui(ui);
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.config.search_state.focus_result,
+                "Show only the processor of a clicked result",
+            );
+            if ui
+                .add_enabled(
+                    self.config.saved_layout.is_some(),
+                    egui::Button::new("Restore Layout"),
+                )
+                .clicked()
+            {
+                self.restore_layout();
+            }
+        });
+
         self.config.search_state.build_entry_tree();
 
+        let focus = self.config.search_state.focus_result;
+        let mut newly_expanded = Vec::new();
         let mut scroll_target = None;
         ScrollArea::vertical()
             // Hack: estimate size of bottom UI.
@@ -1836,8 +5641,31 @@ impl Window {
                                                     scroll_target = Some(ItemLocator {
                                                         entry_id: level2_slot.entry_id.clone(),
                                                         irow: Some(item.irow),
+                                                        row: None,
                                                         item_uid: item.item_uid,
                                                     });
+                                                    if focus {
+                                                        let ancestors = [
+                                                            (
+                                                                level0_slot.expanded,
+                                                                &level0_slot.entry_id,
+                                                            ),
+                                                            (
+                                                                level1_slot.expanded,
+                                                                &level1_slot.entry_id,
+                                                            ),
+                                                            (
+                                                                level2_slot.expanded,
+                                                                &level2_slot.entry_id,
+                                                            ),
+                                                        ];
+                                                        for (expanded, entry_id) in ancestors {
+                                                            if !expanded {
+                                                                newly_expanded
+                                                                    .push(entry_id.clone());
+                                                            }
+                                                        }
+                                                    }
                                                     level2_slot.expanded = true;
                                                     level1_slot.expanded = true;
                                                     level0_slot.expanded = true;
@@ -1852,18 +5680,189 @@ impl Window {
                 }
             });
         if let Some(target) = scroll_target {
+            if focus {
+                self.focus_entry(&target.entry_id, newly_expanded);
+            }
             self.config.scroll_to_item(target);
         }
     }
 
+    // Solos the given entry, remembering the layout so it can be restored
+    fn focus_entry(&mut self, entry_id: &EntryID, expanded: Vec<EntryID>) {
+        let config = &mut self.config;
+        let layout = config.saved_layout.get_or_insert_with(|| SavedLayout {
+            visibility_overrides: config.visibility_overrides.clone(),
+            expanded: Vec::new(),
+        });
+        layout.expanded.extend(expanded);
+        config.visibility_overrides = BTreeMap::from([(entry_id.clone(), EntryVisibility::Solo)]);
+    }
+
+    fn restore_layout(&mut self) {
+        let Some(layout) = self.config.saved_layout.take() else {
+            return;
+        };
+        self.config.visibility_overrides = layout.visibility_overrides;
+        for entry_id in &layout.expanded {
+            self.collapse(entry_id);
+        }
+    }
+
+    fn collapse(&mut self, entry_id: &EntryID) {
+        if entry_id.level() == 3 {
+            if let Some(slot) = self.find_slot_mut(entry_id) {
+                slot.expanded = false;
+            }
+            return;
+        }
+        let index = |level| entry_id.slot_index(level).map(|i| i as usize);
+        let Some(node) = index(0).and_then(|i| self.panel.slots.get_mut(i)) else {
+            return;
+        };
+        match index(1).and_then(|i| node.slots.get_mut(i)) {
+            Some(kind) => kind.expanded = false,
+            None => node.expanded = false,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_search_results_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Export to:");
+            ui.text_edit_singleline(&mut self.config.search_state.export_path);
+            if ui.button("Export").clicked() {
+                let path = self.config.search_state.export_path.clone();
+                let status = match self.export_search_results(Path::new(&path)) {
+                    Ok((count, false)) => format!("Wrote {} results to {}", count, path),
+                    Ok((count, true)) => format!(
+                        "Wrote {} results to {} (truncated to the first {} matches)",
+                        count,
+                        path,
+                        SearchState::MAX_SEARCH_RESULTS
+                    ),
+                    Err(e) => format!("Export failed: {}", e),
+                };
+                self.config.search_state.export_status = Some(status);
+            }
+        });
+        if let Some(status) = &self.config.search_state.export_status {
+            ui.label(status);
+        }
+    }
+
+    // Writes all search results to a file, returning the number of results
+    // written and whether they were limited to MAX_SEARCH_RESULTS. (JSON files
+    // record the latter too; CSV files are kept to plain rows.)
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_search_results(&self, path: &Path) -> io::Result<(usize, bool)> {
+        let state = &self.config.search_state;
+        let mut records = Vec::new();
+        for (entry_id, cache) in &state.result_cache {
+            let Some(slot) = self.find_slot(entry_id) else {
+                continue;
+            };
+            for item in cache.values().flat_map(|tile_cache| tile_cache.values()) {
+                records.push(SearchResultRecord {
+                    entry: &slot.long_name,
+                    title: &item.title,
+                    start_ns: item.interval.start.0,
+                    stop_ns: item.interval.stop.0,
+                    duration_ns: item.interval.duration_ns(),
+                    item_uid: item.item_uid.0,
+                });
+            }
+        }
+        records.sort_by_key(|r| (r.start_ns, r.stop_ns, r.item_uid));
+        let truncated = state.result_set.len() >= SearchState::MAX_SEARCH_RESULTS;
+
+        let mut f = BufWriter::new(File::create(path)?);
+        if path.extension().is_some_and(|ext| ext == "json") {
+            #[derive(Serialize)]
+            struct SearchResults<'a> {
+                query: &'a str,
+                truncated: bool,
+                results: &'a [SearchResultRecord<'a>],
+            }
+            let results = SearchResults {
+                query: &state.query,
+                truncated,
+                results: &records,
+            };
+            serde_json::to_writer_pretty(&mut f, &results)?;
+            writeln!(f)?;
+        } else {
+            fn quote(s: &str) -> Cow<'_, str> {
+                if s.contains([',', '"', '\n', '\r']) {
+                    Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+                } else {
+                    Cow::Borrowed(s)
+                }
+            }
+            writeln!(f, "entry,title,start_ns,stop_ns,duration_ns,item_uid")?;
+            for r in &records {
+                writeln!(
+                    f,
+                    "{},{},{},{},{},{}",
+                    quote(r.entry),
+                    quote(r.title),
+                    r.start_ns,
+                    r.stop_ns,
+                    r.duration_ns,
+                    r.item_uid
+                )?;
+            }
+        }
+        f.flush()?;
+        Ok((records.len(), truncated))
+    }
+
     fn search_controls(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
         const WIDGET_PADDING: f32 = 8.0;
         ui.heading(format!("Profile {}: Search", self.index));
         ui.add_space(WIDGET_PADDING);
         self.search_box(ui, cx);
         ui.add_space(WIDGET_PADDING);
+        self.go_to_uid(ui, cx);
+        ui.add_space(WIDGET_PADDING);
         self.search_results(ui, cx);
     }
+
+    fn go_to_uid(&mut self, ui: &mut egui::Ui, cx: &mut Context) {
+        let go = ui
+            .horizontal(|ui| {
+                ui.label("Go to UID:");
+                let state = &mut self.config.search_state;
+                let response = egui::TextEdit::singleline(&mut state.uid_query)
+                    .desired_width(80.0)
+                    .show(ui)
+                    .response;
+                let entered =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.button("Go").clicked() || entered
+            })
+            .inner;
+        if go {
+            let status = match self.config.search_state.uid_query.trim().parse::<u64>() {
+                Err(_) => Some("Not a valid UID".to_owned()),
+                Ok(uid) => match self.find_item_by_uid(ItemUID(uid)) {
+                    // Only tiles that have been fetched can be searched
+                    None => Some(format!("UID {} not found in loaded tiles", uid)),
+                    Some((item_loc, interval)) => {
+                        // Pad the item, even if it has no duration
+                        let padding = (interval.duration_ns() / 20).max(1);
+                        ProfApp::zoom(cx, interval.grow(padding));
+                        self.expand_slot(&item_loc.entry_id);
+                        self.config.scroll_to_item(item_loc);
+                        None
+                    }
+                },
+            };
+            self.config.search_state.uid_status = status;
+        }
+        if let Some(status) = &self.config.search_state.uid_status {
+            ui.label(RichText::new(status).color(ui.visuals().warn_fg_color));
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
@@ -1877,29 +5876,45 @@ impl ProfApp {
     pub fn new(
         cc: &eframe::CreationContext<'_>,
         mut data_sources: Vec<Box<dyn DeferredDataSource>>,
+        mut options: StartOptions,
     ) -> Self {
         // This is also where you can customized the look at feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        let mut result: Self = if let Some(storage) = cc.storage {
-            eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
-        } else {
-            Default::default()
+        let (mut result, notice) = match cc.storage {
+            Some(storage) => Self::load_state(storage),
+            None => (Default::default(), None),
         };
-
-        for data_source in &mut data_sources {
-            data_source.fetch_info();
+        if let Some(notice) = notice {
+            log::warn!("{}", notice);
+            result.cx.state_notice = Some(notice);
         }
+
+        // Beyond this many profiles, ask which ones to open
+        const MAX_PROFILES_WITHOUT_PICKER: usize = 8;
+
         result.pending_data_sources.clear();
-        result.pending_data_sources.extend(data_sources);
+        result.picker_data_sources.clear();
+        if data_sources.len() > MAX_PROFILES_WITHOUT_PICKER {
+            let sources = data_sources.into_iter().map(|source| (source, false));
+            result.picker_data_sources.extend(sources);
+        } else {
+            for data_source in &mut data_sources {
+                data_source.fetch_info();
+            }
+            result.pending_data_sources.extend(data_sources);
+        }
 
         result.windows.clear();
 
         result.cx.scale_factor = 1.0;
         result.cx.row_scroll_delta = 0;
 
+        result.cx.field_renderers = std::mem::take(&mut options.field_renderers);
+        result.cx.start_options = options;
+
         #[cfg(not(target_arch = "wasm32"))]
         {
             result.last_update = Some(Instant::now());
@@ -1912,6 +5927,8 @@ impl ProfApp {
         };
         cc.egui_ctx.set_visuals(theme);
 
+        ProfApp::install_fonts(&cc.egui_ctx, &mut result.cx);
+
         // Set solid scroll bar (default from egui pre-0.24)
         // The new default "thin" style isn't clickable with our canvas widget
         cc.egui_ctx.style_mut(|style| {
@@ -1921,9 +5938,77 @@ impl ProfApp {
         result
     }
 
+    // Loads the state saved by the last session. If it no longer parses
+    // (e.g., because a setting changed type), recovers what it can and
+    // returns a notice for the user saying what was lost.
+    fn load_state(storage: &dyn eframe::Storage) -> (Self, Option<String>) {
+        let Some(text) = storage.get_string(eframe::APP_KEY) else {
+            return (Default::default(), None);
+        };
+        match ron::from_str::<Self>(&text) {
+            Ok(state) => {
+                if state.version != STATE_VERSION {
+                    log::info!(
+                        "loaded saved state version {} (current is {})",
+                        state.version,
+                        STATE_VERSION
+                    );
+                }
+                return (state, None);
+            }
+            Err(e) => log::warn!("unable to load saved state: {}", e),
+        }
+
+        match Self::migrate_state(&text) {
+            Some((state, reset)) if reset.is_empty() => (state, None),
+            Some((state, reset)) => {
+                let notice = format!(
+                    "Some saved settings could not be loaded and were reset: {}",
+                    reset.join(", ")
+                );
+                (state, Some(notice))
+            }
+            None => {
+                let notice = "Saved settings could not be loaded and were reset".to_owned();
+                (Default::default(), Some(notice))
+            }
+        }
+    }
+
+    // Rebuilds the Context from saved state one setting at a time, starting
+    // from the defaults. Returns the state along with the settings that
+    // could not be kept.
+    fn migrate_state(text: &str) -> Option<(Self, Vec<String>)> {
+        let saved = split_ron_struct(text)?;
+        let (_, saved_cx) = saved.iter().find(|(name, _)| name == "cx")?;
+        let saved_cx = split_ron_struct(saved_cx)?;
+
+        let defaults = ron::to_string(&Context::default()).ok()?;
+        let mut fields = split_ron_struct(&defaults)?;
+        let mut reset = Vec::new();
+        for (name, value) in saved_cx {
+            // Settings that no longer exist are dropped
+            let Some(i) = fields.iter().position(|(n, _)| *n == name) else {
+                continue;
+            };
+            let default = std::mem::replace(&mut fields[i].1, value);
+            if ron::from_str::<Context>(&join_ron_struct(&fields)).is_err() {
+                fields[i].1 = default;
+                reset.push(name);
+            }
+        }
+
+        let cx = ron::from_str(&join_ron_struct(&fields)).ok()?;
+        let state = Self {
+            cx,
+            ..Default::default()
+        };
+        Some((state, reset))
+    }
+
     fn update_interval_select_state(cx: &mut Context) {
-        cx.interval_select_state.start_buffer = cx.view_interval.start.to_string();
-        cx.interval_select_state.stop_buffer = cx.view_interval.stop.to_string();
+        cx.interval_select_state.start_buffer = cx.format_timestamp(cx.view_interval.start);
+        cx.interval_select_state.stop_buffer = cx.format_timestamp(cx.view_interval.stop);
         cx.interval_select_state.start_error = None;
         cx.interval_select_state.stop_error = None;
     }
@@ -1950,6 +6035,48 @@ impl ProfApp {
         history.index = history.levels.len() - 1;
     }
 
+    fn apply_startup_view(cx: &mut Context, windows: &[Window]) {
+        if let Some(interval) = cx.start_options.initial_interval.take() {
+            cx.startup_view_applied = true;
+            // Ignore intervals that miss the profiles entirely
+            if cx.total_interval.overlaps(interval) {
+                ProfApp::zoom(cx, interval.intersection(cx.total_interval));
+            }
+            return;
+        }
+
+        if cx.startup_view_applied {
+            return;
+        }
+        cx.startup_view_applied = true;
+        if cx.startup_zoom != StartupZoom::Remembered {
+            return;
+        }
+        let Some(saved) = cx.saved_views.get(&ProfApp::view_key(windows)) else {
+            return;
+        };
+        match clamp_saved_view(saved, cx.total_interval) {
+            Some((interval, Some(history))) => {
+                cx.view_interval = interval;
+                cx.view_interval_history = history;
+                ProfApp::update_interval_select_state(cx);
+            }
+            Some((interval, None)) => ProfApp::zoom(cx, interval),
+            None => {}
+        }
+    }
+
+    // Identifies the set of open profiles across sessions. Duplicates have
+    // their own views, so they aren't part of it.
+    fn view_key(windows: &[Window]) -> String {
+        let keys: Vec<_> = windows
+            .iter()
+            .filter(|w| w.copy == 0)
+            .map(|w| w.locator_key())
+            .collect();
+        keys.join(";")
+    }
+
     fn pan(cx: &mut Context, percent: PercentageInteger, dir: PanDirection) {
         if percent.value() == 0 {
             return;
@@ -1970,6 +6097,11 @@ impl ProfApp {
         if cx.view_interval == interval {
             return;
         }
+        // Nothing would be visible in an empty view, unless the profiles are
+        // empty themselves
+        if interval.duration_ns() <= 0 && cx.total_interval.duration_ns() > 0 {
+            return;
+        }
 
         ProfApp::update_view_interval(cx, interval, IntervalOrigin::Zoom);
         ProfApp::update_interval_select_state(cx);
@@ -1979,27 +6111,83 @@ impl ProfApp {
         if cx.view_interval_history.index == 0 {
             return;
         }
-        cx.view_interval_history.index -= 1;
-        cx.view_interval = cx.view_interval_history.levels[cx.view_interval_history.index];
-        ProfApp::update_interval_select_state(cx);
+        ProfApp::jump_to_history(cx, cx.view_interval_history.index - 1);
     }
 
     fn redo_pan_zoom(cx: &mut Context) {
-        if cx.view_interval_history.index + 1 >= cx.view_interval_history.levels.len() {
+        ProfApp::jump_to_history(cx, cx.view_interval_history.index + 1);
+    }
+
+    // Like undo/redo, but any number of steps at once
+    fn jump_to_history(cx: &mut Context, index: usize) {
+        if index >= cx.view_interval_history.levels.len() {
             return;
         }
-        cx.view_interval_history.index += 1;
-        cx.view_interval = cx.view_interval_history.levels[cx.view_interval_history.index];
+        cx.view_interval_history.index = index;
+        cx.view_interval = cx.view_interval_history.levels[index];
         ProfApp::update_interval_select_state(cx);
     }
 
+    // Keeps the page URL in sync with the view, so that it can be shared
+    #[cfg(target_arch = "wasm32")]
+    fn update_location(ctx: &egui::Context, cx: &mut Context, windows: &[Window]) {
+        // Browsers limit how often the URL can change, so wait for the view to
+        // stop moving
+        const SETTLE_SECONDS: f64 = 0.5;
+
+        let params = view_link_params(cx, windows);
+        if params == cx.written_link {
+            cx.pending_link = None;
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        match &cx.pending_link {
+            Some((pending, since)) if *pending == params => {
+                if now - since < SETTLE_SECONDS {
+                    return;
+                }
+            }
+            _ => {
+                cx.pending_link = Some((params, now));
+                ctx.request_repaint_after(Duration::from_secs_f64(SETTLE_SECONDS));
+                return;
+            }
+        }
+
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let Ok(href) = window.location().href() else {
+            return;
+        };
+        let Ok(url) = web_sys::Url::new(&href) else {
+            return;
+        };
+        let search = url.search_params();
+        for key in VIEW_LINK_KEYS {
+            search.delete(key);
+        }
+        for (key, value) in &params {
+            search.set(key, value);
+        }
+        if let Ok(history) = window.history() {
+            let data = wasm_bindgen::JsValue::NULL;
+            if let Err(e) = history.replace_state_with_url(&data, "", Some(&url.href())) {
+                log::warn!("unable to update URL: {:?}", e);
+            }
+        }
+        cx.written_link = params;
+        cx.pending_link = None;
+    }
+
     fn zoom_in(cx: &mut Context) {
         let quarter = -cx.view_interval.duration_ns() / 4;
         Self::zoom(cx, cx.view_interval.grow(quarter));
     }
 
     fn zoom_out(cx: &mut Context) {
-        let half = cx.view_interval.duration_ns() / 2;
+        // At least 1 ns, so that tiny views can still grow
+        let half = (cx.view_interval.duration_ns() / 2).max(1);
         Self::zoom(
             cx,
             cx.view_interval.grow(half).intersection(cx.total_interval),
@@ -2014,105 +6202,445 @@ impl ProfApp {
         cx.scale_factor = 1.0;
     }
 
-    fn reset_ui(cx: &mut Context, windows: &mut [Window]) {
-        cx.show_controls = false;
-        for window in windows.iter_mut() {
-            window.config.items_selected.clear();
+    // Adds the extra fonts after the built-in ones, so they're only used for
+    // characters the built-in fonts don't have. Takes effect next frame.
+    fn install_fonts(ctx: &egui::Context, cx: &mut Context) {
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))] // only added to on native
+        let mut extra_fonts = cx.start_options.extra_fonts.clone();
+        cx.font_errors.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        for path in &cx.font_paths {
+            match std::fs::read(path) {
+                Ok(data) => extra_fonts.push((path.clone(), data)),
+                Err(e) => {
+                    cx.font_errors.insert(path.clone(), e.to_string());
+                }
+            }
+        }
+        if extra_fonts.is_empty() && cx.font_errors.is_empty() {
+            return;
+        }
+
+        let mut fonts = egui::FontDefinitions::default();
+        for (name, data) in extra_fonts {
+            // egui panics on data it can't parse
+            if !is_font_data(&data) {
+                cx.font_errors
+                    .insert(name, "not a TrueType or OpenType font".to_owned());
+                continue;
+            }
+            fonts
+                .font_data
+                .insert(name.clone(), egui::FontData::from_owned(data));
+            for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+                fonts.families.entry(family).or_default().push(name.clone());
+            }
+        }
+        for (name, error) in &cx.font_errors {
+            log::warn!("unable to load font {}: {}", name, error);
+        }
+        ctx.set_fonts(fonts);
+    }
+
+    // Return to the state the view starts in: the entire profile, at the
+    // default vertical spacing
+    fn reset_view(cx: &mut Context) {
+        ProfApp::reset_scale_factor(cx);
+        cx.wheel_remainder = 0.0;
+        cx.scroll_pending = 0.0;
+        ProfApp::zoom(cx, cx.total_interval);
+    }
+
+    fn reset_ui(cx: &mut Context, windows: &mut [Window]) {
+        cx.show_controls = false;
+        for window in windows.iter_mut() {
+            window.config.items_selected.clear();
+            window.config.last_selected = None;
+            window.config.pinned_tooltip = None;
+            window.config.item_menu = None;
+        }
+    }
+
+    // Runs f on the view of the window under the pointer if it has its own,
+    // otherwise on the shared view
+    fn with_active_view(cx: &mut Context, windows: &mut [Window], f: impl FnOnce(&mut Context)) {
+        let active = windows
+            .iter_mut()
+            .find(|w| w.view.is_some() && Some(w.index) == cx.active_window);
+        match active {
+            Some(window) => {
+                window.swap_view(cx);
+                f(cx);
+                window.swap_view(cx);
+            }
+            None => f(cx),
+        }
+    }
+
+    fn keyboard(ctx: &egui::Context, cx: &mut Context, windows: &mut [Window], had_focus: bool) {
+        // Clicking the binding's button focuses it, so check this first
+        if ProfApp::capture_key_binding(ctx, cx) {
+            return;
+        }
+
+        // Focus is elsewhere, don't check any keys. Note: this checks the
+        // focus at the start of the frame, because egui will give focus to
+        // the first widget when Tab is pressed, which we use ourselves.
+        if had_focus {
+            return;
+        }
+
+        let action = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => cx.key_bindings.action(*key, *modifiers),
+                _ => None,
+            })
+        });
+        let Some(action) = action else {
+            return;
+        };
+        let bindings = &cx.key_bindings;
+        // Percentage::from panics past 100, and the steps come from storage
+        let pan_step = Percentage::from(bindings.pan_step.min(100));
+        let fine_pan_step = Percentage::from(bindings.fine_pan_step.min(100));
+        match action {
+            KeyAction::PanLeft => ProfApp::with_active_view(cx, windows, |cx| {
+                ProfApp::pan(cx, pan_step, PanDirection::Left)
+            }),
+            KeyAction::PanRight => ProfApp::with_active_view(cx, windows, |cx| {
+                ProfApp::pan(cx, pan_step, PanDirection::Right)
+            }),
+            KeyAction::FinePanLeft => ProfApp::with_active_view(cx, windows, |cx| {
+                ProfApp::pan(cx, fine_pan_step, PanDirection::Left)
+            }),
+            KeyAction::FinePanRight => ProfApp::with_active_view(cx, windows, |cx| {
+                ProfApp::pan(cx, fine_pan_step, PanDirection::Right)
+            }),
+            KeyAction::ScrollUp => cx.row_scroll_delta = bindings.scroll_step,
+            KeyAction::ScrollDown => cx.row_scroll_delta = -bindings.scroll_step,
+            KeyAction::FineScrollUp => cx.row_scroll_delta = bindings.fine_scroll_step,
+            KeyAction::FineScrollDown => cx.row_scroll_delta = -bindings.fine_scroll_step,
+            KeyAction::PageUp => cx.row_scroll_delta = cx.page_rows,
+            KeyAction::PageDown => cx.row_scroll_delta = -cx.page_rows,
+            KeyAction::ZoomIn => ProfApp::with_active_view(cx, windows, ProfApp::zoom_in),
+            KeyAction::ZoomOut => ProfApp::with_active_view(cx, windows, ProfApp::zoom_out),
+            KeyAction::UndoZoom => ProfApp::with_active_view(cx, windows, ProfApp::undo_pan_zoom),
+            KeyAction::RedoZoom => ProfApp::with_active_view(cx, windows, ProfApp::redo_pan_zoom),
+            KeyAction::ResetZoom => {
+                ProfApp::with_active_view(cx, windows, |cx| ProfApp::zoom(cx, cx.total_interval))
+            }
+            KeyAction::ExpandVertical => ProfApp::multiply_scale_factor(cx, 2.0),
+            KeyAction::ShrinkVertical => ProfApp::multiply_scale_factor(cx, 0.5),
+            KeyAction::ResetVertical => ProfApp::reset_scale_factor(cx),
+            KeyAction::ResetView => ProfApp::with_active_view(cx, windows, ProfApp::reset_view),
+            KeyAction::ToggleControls => cx.show_controls = !cx.show_controls,
+            KeyAction::ResetUI => ProfApp::reset_ui(cx, windows),
+            KeyAction::ClearFilters => {
+                for window in windows.iter_mut() {
+                    window.clear_filters();
+                }
+            }
+            KeyAction::AddMarker => {
+                if let Some(time) = cx.hover_time {
+                    let label = format!("Marker {}", cx.markers.len() + 1);
+                    cx.markers.push((time, label));
+                }
+            }
+            KeyAction::NextItem | KeyAction::PreviousItem => {
+                let forward = action == KeyAction::NextItem;
+                for window in windows.iter_mut() {
+                    window.swap_view(cx);
+                    window.step_selected_item(forward, cx);
+                    window.swap_view(cx);
+                }
+                // Undo the focus change egui made in response to Tab
+                ctx.memory_mut(|m| m.stop_text_input());
+            }
+        }
+    }
+
+    // Rebinds the action being edited in the controls window to the next key
+    // pressed. Returns true while waiting for that key.
+    fn capture_key_binding(ctx: &egui::Context, cx: &mut Context) -> bool {
+        let Some(action) = cx.capturing_key else {
+            return false;
+        };
+        let press = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
+        if let Some((key, modifiers)) = press {
+            // Escape cancels
+            if key != egui::Key::Escape {
+                let binding = KeyBinding::from_press(key, modifiers);
+                cx.key_bindings.set(action, binding);
+            }
+            cx.capturing_key = None;
+        }
+        true
+    }
+
+    // Collects the warnings from all profiles in one place, so they're hard to
+    // miss when there are many profiles open
+    fn warning_banner(ctx: &egui::Context, cx: &mut Context, windows: &[Window]) {
+        let warnings: Vec<_> = windows
+            .iter()
+            .filter(|window| !cx.dismissed_warnings.contains(&window.index))
+            .filter_map(|window| {
+                let message = window.config.warning_message.as_ref()?;
+                let locator = window
+                    .config
+                    .data_source
+                    .fetch_description()
+                    .source_locator
+                    .join(", ");
+                Some((window.index, locator, message))
+            })
+            .collect();
+        if warnings.is_empty() && cx.load_errors.is_empty() && cx.state_notice.is_none() {
+            return;
+        }
+
+        egui::TopBottomPanel::top("warning_banner").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    if let Some(notice) = &cx.state_notice {
+                        ui.label(RichText::new(format!("⚠ {notice}")).color(Color32::RED));
+                    }
+                    for error in &cx.load_errors {
+                        ui.label(RichText::new(format!("⚠ {error}")).color(Color32::RED));
+                    }
+                    for (index, locator, message) in &warnings {
+                        let text = format!("⚠ Profile {index} ({locator}): {message}");
+                        ui.label(RichText::new(text).color(Color32::RED));
+                    }
+                });
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Dismiss").clicked() {
+                        cx.dismissed_warnings
+                            .extend(warnings.iter().map(|(index, _, _)| *index));
+                        cx.load_errors.clear();
+                        cx.state_notice = None;
+                    }
+                });
+            });
+        });
+    }
+
+    // Pans the view along with the pointer when dragging with the middle
+    // button, or with the primary button while holding Space. Returns true
+    // if such a drag is in progress.
+    fn drag_pan(
+        ui: &mut egui::Ui,
+        rect: Rect,
+        response: &egui::Response,
+        cx: &mut Context,
+    ) -> bool {
+        let space = ui.input(|i| i.key_down(egui::Key::Space));
+        let panning = response.dragged_by(egui::PointerButton::Middle)
+            || (space && response.dragged_by(egui::PointerButton::Primary));
+        if !panning {
+            return false;
+        }
+
+        let delta = response.drag_delta().x;
+        if delta != 0.0 {
+            // Dragging right moves the view back in time
+            let view = cx.view_interval;
+            let shift = cx.time_axis().lerp(-delta / rect.width()).0 - view.start.0;
+            ProfApp::update_view_interval(cx, view.translate(shift), IntervalOrigin::Pan);
+            ProfApp::update_interval_select_state(cx);
+        }
+        true
+    }
+
+    // Zooms to the region dragged over within rect (a timeline or a
+    // utilization plot). Returns the interval while the drag is in progress.
+    fn drag_zoom(
+        ui: &mut egui::Ui,
+        rect: Rect,
+        response: &egui::Response,
+        cx: &mut Context,
+    ) -> Option<Interval> {
+        let is_active_drag = response.dragged_by(egui::PointerButton::Primary);
+        if is_active_drag && response.drag_started() {
+            // On the beginning of a drag, save our position so we can
+            // calculate the delta
+            cx.drag_origin = response.interact_pointer_pos();
+        }
+
+        // Ignore drags that belong to some other widget
+        if !is_active_drag && !response.drag_released() {
+            return None;
+        }
+
+        // We're in a drag, calculate the drag inetrval
+        let origin = cx.drag_origin?;
+        let current = response.interact_pointer_pos()?;
+        let min = origin.x.min(current.x);
+        let max = origin.x.max(current.x);
+
+        let start = (min - rect.left()) / rect.width();
+        let start = cx.time_axis().lerp(start);
+        let stop = (max - rect.left()) / rect.width();
+        let stop = cx.time_axis().lerp(stop);
+
+        let interval = Interval::new(start, stop);
+
+        if is_active_drag {
+            // Still in drag, draw a rectangle to show the dragged region
+            let drag_rect =
+                Rect::from_min_max(Pos2::new(min, rect.min.y), Pos2::new(max, rect.max.y));
+            let color = Color32::DARK_GRAY.linear_multiply(0.5);
+            ui.painter().rect(drag_rect, 0.0, color, Stroke::NONE);
+
+            return Some(interval);
+        }
+
+        // Only set view interval if the drag was a certain amount, and not
+        // so short that it was a click on an item
+        const MIN_DRAG_DISTANCE: f32 = 4.0;
+        if max - min > MIN_DRAG_DISTANCE.max(cx.click_thresholds.max_distance) {
+            if ui.input(|i| i.modifiers.shift) {
+                cx.measurement = Some(interval);
+            } else {
+                ProfApp::zoom(cx, interval);
+            }
+        }
+
+        cx.drag_origin = None;
+        None
+    }
+
+    fn ruler(ui: &mut egui::Ui, cx: &Context) {
+        const TICK_LENGTH: f32 = 4.0;
+        let font_id = TextStyle::Small.resolve(ui.style());
+        let height = ui.fonts(|f| f.row_height(&font_id)) + TICK_LENGTH;
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(ui.available_width(), height),
+            egui::Sense::hover(),
+        );
+
+        // Line up with the slots, as laid out in the last frame
+        let Some(slot_rect) = cx.slot_rect else {
+            return;
+        };
+        let rect = Rect::from_x_y_ranges(slot_rect.x_range(), response.rect.y_range());
+        let axis = cx.time_axis();
+        let units = cx.display_unit.interval_units(cx.view_interval);
+        let color = ui.visuals().text_color();
+        let stroke = Stroke::new(1.0, color);
+        painter.hline(rect.x_range(), rect.max.y, stroke);
+        for tick in ruler_ticks(axis, rect.width()) {
+            let x = rect.min.x + axis.unlerp(tick) * rect.width();
+            painter.vline(x, (rect.max.y - TICK_LENGTH)..=rect.max.y, stroke);
+            let label = TimestampDisplay {
+                timestamp: tick,
+                units,
+                include_units: true,
+                epoch: cx.display_epoch(),
+            };
+            painter.text(
+                Pos2::new(x + 2.0, rect.min.y),
+                Align2::LEFT_TOP,
+                label.to_string(),
+                font_id.clone(),
+                color,
+            );
         }
     }
 
-    fn keyboard(ctx: &egui::Context, cx: &mut Context, windows: &mut [Window]) {
-        // Focus is elsewhere, don't check any keys
-        if ctx.memory(|m| m.focus().is_some()) {
+    fn measurement(ui: &mut egui::Ui, area: Rect, cx: &mut Context) {
+        if ui.input(|i| i.pointer.primary_pressed()) {
+            cx.measurement = None;
+        }
+        // Same hack as in cursor
+        let (Some(interval), Some(slot_rect)) = (cx.measurement, cx.slot_rect) else {
+            return;
+        };
+        if !cx.view_interval.overlaps(interval) {
             return;
         }
+        let axis = cx.time_axis();
+        let rect = Rect::from_x_y_ranges(slot_rect.x_range(), area.y_range());
+        let visible = interval.intersection(cx.view_interval);
+        let left = rect.min.x + axis.unlerp(visible.start) * rect.width();
+        let right = rect.min.x + axis.unlerp(visible.stop) * rect.width();
+        let measured = Rect::from_x_y_ranges(left..=right, rect.y_range());
+
+        let color = ui.visuals().selection.bg_fill;
+        let painter = ui.painter().with_clip_rect(rect);
+        painter.rect(
+            measured,
+            0.0,
+            color.linear_multiply(0.2),
+            Stroke::new(1.0, color),
+        );
+        let text_color = ui.visuals().strong_text_color();
+        let galley = painter.layout(
+            cx.format_interval(interval),
+            TextStyle::Body.resolve(ui.style()),
+            text_color,
+            rect.width(),
+        );
+        let label = Rect::from_min_size(
+            Pos2::new(
+                (measured.center().x - galley.size().x / 2.0).clamp(
+                    rect.min.x,
+                    (rect.max.x - galley.size().x).at_least(rect.min.x),
+                ),
+                rect.min.y + 4.0,
+            ),
+            galley.size(),
+        );
+        painter.rect_filled(label.expand(2.0), 2.0, ui.visuals().extreme_bg_color);
+        painter.galley(label.min, galley, text_color);
+    }
 
-        enum Actions {
-            ZoomIn,
-            ZoomOut,
-            UndoZoom,
-            RedoZoom,
-            ResetZoom,
-            Pan(PercentageInteger, PanDirection),
-            Scroll(i32),
-            ExpandVertical,
-            ShrinkVertical,
-            ResetVertical,
-            ToggleControls,
-            ResetUI,
-            NoAction,
-        }
-        let action = ctx.input(|i| {
-            if i.modifiers.ctrl {
-                if i.modifiers.alt {
-                    if i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals) {
-                        Actions::ExpandVertical
-                    } else if i.key_pressed(egui::Key::Minus) {
-                        Actions::ShrinkVertical
-                    } else if i.key_pressed(egui::Key::Num0) {
-                        Actions::ResetVertical
-                    } else {
-                        Actions::NoAction
-                    }
-                } else if i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals) {
-                    Actions::ZoomIn
-                } else if i.key_pressed(egui::Key::Minus) {
-                    Actions::ZoomOut
-                } else if i.key_pressed(egui::Key::ArrowLeft) {
-                    Actions::UndoZoom
-                } else if i.key_pressed(egui::Key::ArrowRight) {
-                    Actions::RedoZoom
-                } else if i.key_pressed(egui::Key::Num0) {
-                    Actions::ResetZoom
-                } else {
-                    Actions::NoAction
-                }
-            } else if i.modifiers.shift {
-                if i.key_pressed(egui::Key::ArrowLeft) {
-                    Actions::Pan(Percentage::from(1), PanDirection::Left)
-                } else if i.key_pressed(egui::Key::ArrowRight) {
-                    Actions::Pan(Percentage::from(1), PanDirection::Right)
-                } else if i.key_pressed(egui::Key::ArrowUp) {
-                    Actions::Scroll(1)
-                } else if i.key_pressed(egui::Key::ArrowDown) {
-                    Actions::Scroll(-1)
-                } else {
-                    Actions::NoAction
-                }
-            } else if i.key_pressed(egui::Key::H) {
-                Actions::ToggleControls
-            } else if i.key_pressed(egui::Key::Escape) {
-                Actions::ResetUI
-            } else if i.key_pressed(egui::Key::ArrowLeft) {
-                Actions::Pan(Percentage::from(5), PanDirection::Left)
-            } else if i.key_pressed(egui::Key::ArrowRight) {
-                Actions::Pan(Percentage::from(5), PanDirection::Right)
-            } else if i.key_pressed(egui::Key::ArrowUp) {
-                Actions::Scroll(5)
-            } else if i.key_pressed(egui::Key::ArrowDown) {
-                Actions::Scroll(-5)
-            } else {
-                Actions::NoAction
+    fn markers(ui: &mut egui::Ui, area: Rect, cx: &Context) {
+        // Same hack as in cursor
+        let Some(slot_rect) = cx.slot_rect else {
+            return;
+        };
+        let axis = cx.time_axis();
+        let color = ui.visuals().warn_fg_color;
+        let stroke = Stroke::new(1.0, color);
+        let font_id = TextStyle::Small.resolve(ui.style());
+        let painter = ui
+            .painter()
+            .with_clip_rect(Rect::from_x_y_ranges(slot_rect.x_range(), area.y_range()));
+        for (time, label) in &cx.markers {
+            if !cx.view_interval.contains(*time) {
+                continue;
             }
-        });
-        match action {
-            Actions::ZoomIn => ProfApp::zoom_in(cx),
-            Actions::ZoomOut => ProfApp::zoom_out(cx),
-            Actions::UndoZoom => ProfApp::undo_pan_zoom(cx),
-            Actions::RedoZoom => ProfApp::redo_pan_zoom(cx),
-            Actions::ResetZoom => ProfApp::zoom(cx, cx.total_interval),
-            Actions::Pan(percent, dir) => ProfApp::pan(cx, percent, dir),
-            Actions::Scroll(rows) => cx.row_scroll_delta = rows,
-            Actions::ExpandVertical => ProfApp::multiply_scale_factor(cx, 2.0),
-            Actions::ShrinkVertical => ProfApp::multiply_scale_factor(cx, 0.5),
-            Actions::ResetVertical => ProfApp::reset_scale_factor(cx),
-            Actions::ToggleControls => cx.show_controls = !cx.show_controls,
-            Actions::ResetUI => ProfApp::reset_ui(cx, windows),
-            Actions::NoAction => {}
-        }
-    }
-
-    fn cursor(ui: &mut egui::Ui, cx: &mut Context) {
+            let x = slot_rect.min.x + axis.unlerp(*time) * slot_rect.width();
+            let top = Pos2::new(x, area.min.y);
+            let bottom = Pos2::new(x, area.max.y);
+            painter.extend(egui::Shape::dashed_line(&[top, bottom], stroke, 6.0, 3.0));
+            painter.text(
+                top + Vec2::new(3.0, 0.0),
+                Align2::LEFT_TOP,
+                label,
+                font_id.clone(),
+                color,
+            );
+        }
+    }
+
+    // Draws the cursor over area, which shows a single view
+    fn cursor(ui: &mut egui::Ui, area: Rect, cx: &mut Context) {
         // Hack: the UI rect we have at this point is not where the
         // timeline is being drawn. So fish out the coordinates we
         // need to draw the correct rect.
@@ -2122,56 +6650,17 @@ impl ProfApp {
             return;
         }
 
-        let ui_rect = ui.min_rect();
         let slot_rect = cx.slot_rect.unwrap();
-        let rect = Rect::from_min_max(
-            Pos2::new(slot_rect.min.x, ui_rect.min.y),
-            Pos2::new(slot_rect.max.x, ui_rect.max.y),
-        );
+        let rect = Rect::from_x_y_ranges(slot_rect.x_range(), area.y_range());
 
         let response = ui.allocate_rect(rect, egui::Sense::drag());
 
         // Handle drag detection
-        let mut drag_interval = None;
-
-        let is_active_drag = response.dragged_by(egui::PointerButton::Primary);
-        if is_active_drag && response.drag_started() {
-            // On the beginning of a drag, save our position so we can
-            // calculate the delta
-            cx.drag_origin = response.interact_pointer_pos();
-        }
-
-        if let Some(origin) = cx.drag_origin {
-            // We're in a drag, calculate the drag inetrval
-            let current = response.interact_pointer_pos().unwrap();
-            let min = origin.x.min(current.x);
-            let max = origin.x.max(current.x);
-
-            let start = (min - rect.left()) / rect.width();
-            let start = cx.view_interval.lerp(start);
-            let stop = (max - rect.left()) / rect.width();
-            let stop = cx.view_interval.lerp(stop);
-
-            let interval = Interval::new(start, stop);
-
-            if is_active_drag {
-                // Still in drag, draw a rectangle to show the dragged region
-                let drag_rect =
-                    Rect::from_min_max(Pos2::new(min, rect.min.y), Pos2::new(max, rect.max.y));
-                let color = Color32::DARK_GRAY.linear_multiply(0.5);
-                ui.painter().rect(drag_rect, 0.0, color, Stroke::NONE);
-
-                drag_interval = Some(interval);
-            } else if response.drag_released() {
-                // Only set view interval if the drag was a certain amount
-                const MIN_DRAG_DISTANCE: f32 = 4.0;
-                if max - min > MIN_DRAG_DISTANCE {
-                    ProfApp::zoom(cx, interval);
-                }
-
-                cx.drag_origin = None;
-            }
-        }
+        let drag_interval = if Self::drag_pan(ui, rect, &response, cx) {
+            None
+        } else {
+            Self::drag_zoom(ui, rect, &response, cx)
+        };
 
         // Handle hover detection
         if let Some(hover) = response.hover_pos() {
@@ -2179,10 +6668,10 @@ impl ProfApp {
 
             // Draw vertical line through cursor
             const RADIUS: f32 = 12.0;
-            let top = Pos2::new(hover.x, ui.min_rect().min.y);
-            let mid_top = Pos2::new(hover.x, (hover.y - RADIUS).at_least(ui.min_rect().min.y));
-            let mid_bottom = Pos2::new(hover.x, (hover.y + RADIUS).at_most(ui.min_rect().max.y));
-            let bottom = Pos2::new(hover.x, ui.min_rect().max.y);
+            let top = Pos2::new(hover.x, area.min.y);
+            let mid_top = Pos2::new(hover.x, (hover.y - RADIUS).at_least(area.min.y));
+            let mid_bottom = Pos2::new(hover.x, (hover.y + RADIUS).at_most(area.max.y));
+            let bottom = Pos2::new(hover.x, area.max.y);
             ui.painter().line_segment([top, mid_top], visuals.fg_stroke);
             ui.painter()
                 .line_segment([mid_bottom, bottom], visuals.fg_stroke);
@@ -2191,16 +6680,18 @@ impl ProfApp {
 
             const HOVER_PADDING: f32 = 8.0;
             let time = (hover.x - rect.left()) / rect.width();
-            let time = cx.view_interval.lerp(time);
+            let time = cx.time_axis().lerp(time);
+            cx.hover_time = Some(time);
 
             let label_text = if let Some(drag) = drag_interval {
-                format!("{drag}")
+                cx.format_interval(drag)
             } else {
-                let units: TimestampUnits = cx.view_interval.into();
+                let units = cx.display_unit.interval_units(cx.view_interval);
                 let time_units = TimestampDisplay {
                     timestamp: time,
                     units,
                     include_units: true,
+                    epoch: cx.display_epoch(),
                 };
                 format!("t={time_units}")
             };
@@ -2224,9 +6715,9 @@ impl ProfApp {
             );
             // This is a hack to keep the time viewer on the screen when we
             // approach the right edge.
-            if popup_rect.right() > ui.min_rect().right() {
-                popup_rect = popup_rect
-                    .translate(Vec2::new(ui.min_rect().right() - popup_rect.right(), 0.0));
+            if popup_rect.right() > area.right() {
+                popup_rect =
+                    popup_rect.translate(Vec2::new(area.right() - popup_rect.right(), 0.0));
             }
             let mut popup_ui = egui::Ui::new(
                 ui.ctx().clone(),
@@ -2241,7 +6732,7 @@ impl ProfApp {
         }
     }
 
-    fn display_controls(ui: &mut egui::Ui, mode: &mut ItemLinkNavigationMode) {
+    fn display_controls(ui: &mut egui::Ui, cx: &mut Context) {
         fn show_row_ui(
             body: &mut egui_extras::TableBody<'_>,
             label: &str,
@@ -2255,32 +6746,88 @@ impl ProfApp {
             });
         }
 
+        ui.horizontal(|ui| {
+            ui.label("Filter shortcuts:");
+            ui.text_edit_singleline(&mut cx.controls_filter);
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let egui_ctx = ui.ctx().clone();
+
         TableBuilder::new(ui)
             .striped(true)
             .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
             .column(Column::auto())
             .column(Column::remainder())
             .body(|mut body| {
-                let mut show_row = |a, b| {
-                    show_row_ui(&mut body, a, |ui| {
-                        ui.label(b);
-                    });
+                let filter = cx.controls_filter.to_lowercase();
+                let matches_filter = |a: &str, b: &str| {
+                    a.to_lowercase().contains(&filter) || b.to_lowercase().contains(&filter)
                 };
-                show_row("Zoom to Interval", "Click and Drag");
-                show_row("Pan 5%", "Left/Right Arrow");
-                show_row("Pan 1%", "Shift + Left/Right Arrow");
-                show_row("Vertical Scroll", "Up/Down Arrow");
-                show_row("Fine Vertical Scroll", "Shift + Up/Down Arrow");
-                show_row("Zoom In", "Ctrl + Plus/Equals");
-                show_row("Zoom Out", "Ctrl + Minus");
-                show_row("Undo Pan/Zoom", "Ctrl + Left Arrow");
-                show_row("Redo Pan/Zoom", "Ctrl + Right Arrow");
-                show_row("Reset Pan/Zoom", "Ctrl + 0");
-                show_row("Expand Vertical Spacing", "Ctrl + Alt + Plus/Equals");
-                show_row("Shrink Vertical Spacing", "Ctrl + Alt + Minus");
-                show_row("Reset Vertical Spacing", "Ctrl + Alt + 0");
-                show_row("Toggle This Window", "H");
+                if matches_filter("Zoom to Interval", "Click and Drag") {
+                    show_row_ui(&mut body, "Zoom to Interval", |ui| {
+                        ui.label("Click and Drag");
+                    });
+                }
+                if matches_filter("Measure Interval", "Shift + Click and Drag") {
+                    show_row_ui(&mut body, "Measure Interval", |ui| {
+                        ui.label("Shift + Click and Drag");
+                    });
+                }
+                const PAN_GESTURE: &str = "Middle-Click and Drag, or Space + Click and Drag";
+                if matches_filter("Pan", PAN_GESTURE) {
+                    show_row_ui(&mut body, "Pan", |ui| {
+                        ui.label(PAN_GESTURE);
+                    });
+                }
+                for action in KeyAction::ALL {
+                    let text = cx
+                        .key_bindings
+                        .get(action)
+                        .iter()
+                        .map(|binding| binding.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" / ");
+                    if !matches_filter(action.label_text(), &text) {
+                        continue;
+                    }
+                    show_row_ui(&mut body, action.label_text(), |ui: &mut _| {
+                        let capturing = cx.capturing_key == Some(action);
+                        let text = if capturing {
+                            "Press a key (Esc to cancel)"
+                        } else {
+                            &text
+                        };
+                        if ui.button(text).on_hover_text("Click to change").clicked() {
+                            cx.capturing_key = Some(action);
+                        }
+                    });
+                }
+                show_row_ui(&mut body, "Pan Step", |ui: &mut _| {
+                    let bindings = &mut cx.key_bindings;
+                    ui.add(Slider::new(&mut bindings.pan_step, 1..=100).suffix("%"))
+                        .on_hover_text("Percent of the view to move by");
+                });
+                show_row_ui(&mut body, "Fine Pan Step", |ui: &mut _| {
+                    let bindings = &mut cx.key_bindings;
+                    ui.add(Slider::new(&mut bindings.fine_pan_step, 1..=100).suffix("%"));
+                });
+                show_row_ui(&mut body, "Vertical Scroll Step", |ui: &mut _| {
+                    let bindings = &mut cx.key_bindings;
+                    ui.add(Slider::new(&mut bindings.scroll_step, 1..=20).suffix(" rows"));
+                });
+                show_row_ui(&mut body, "Fine Vertical Scroll Step", |ui: &mut _| {
+                    let bindings = &mut cx.key_bindings;
+                    ui.add(Slider::new(&mut bindings.fine_scroll_step, 1..=20).suffix(" rows"));
+                });
+                show_row_ui(&mut body, "Key Bindings", |ui: &mut _| {
+                    if ui.button("Reset to Defaults").clicked() {
+                        cx.key_bindings = KeyBindings::default();
+                        cx.capturing_key = None;
+                    }
+                });
                 show_row_ui(&mut body, "Item Link Zoom or Pan", |ui: &mut _| {
+                    let mode = &mut cx.item_link_mode;
                     egui::ComboBox::from_id_source("Item Link Zoom or Pan")
                         .selected_text(format!("{:?}", mode))
                         .show_ui(ui, |ui| {
@@ -2288,6 +6835,264 @@ impl ProfApp {
                             ui.selectable_value(mode, ItemLinkNavigationMode::Pan, "Pan");
                         });
                 });
+                show_row_ui(&mut body, "Minimum Item Width", |ui: &mut _| {
+                    ui.add(Slider::new(&mut cx.min_item_width, 0.0..=5.0).suffix(" px"));
+                });
+                show_row_ui(&mut body, "Utilization Samples per Pixel", |ui: &mut _| {
+                    let sampling = &mut cx.summary_sampling;
+                    ui.checkbox(&mut sampling.enabled, "")
+                        .on_hover_text("Uncheck to draw the data exactly as loaded");
+                    ui.add_enabled(
+                        sampling.enabled,
+                        Slider::new(&mut sampling.samples_per_pixel, 0.1..=4.0),
+                    );
+                });
+                show_row_ui(&mut body, "Utilization Threshold", |ui: &mut _| {
+                    let threshold = &mut cx.util_threshold;
+                    ui.checkbox(&mut threshold.enabled, "")
+                        .on_hover_text("Shade utilization plots where they fall below this");
+                    let mut percent = threshold.value * 100.0;
+                    let slider = Slider::new(&mut percent, 0.0..=100.0).suffix("%");
+                    if ui.add_enabled(threshold.enabled, slider).changed() {
+                        threshold.value = percent / 100.0;
+                    }
+                });
+                show_row_ui(&mut body, "Dense Rows as Heatmap", |ui: &mut _| {
+                    let dense = &mut cx.dense_rows;
+                    ui.checkbox(&mut dense.enabled, "")
+                        .on_hover_text("Blend the items in each pixel instead of drawing each one");
+                    ui.add_enabled(
+                        dense.enabled,
+                        Slider::new(&mut dense.items_per_pixel, 1.0..=32.0)
+                            .logarithmic(true)
+                            .suffix(" items/px"),
+                    );
+                });
+                show_row_ui(&mut body, "Click Distance Tolerance", |ui: &mut _| {
+                    let thresholds = &mut cx.click_thresholds;
+                    ui.add(Slider::new(&mut thresholds.max_distance, 1.0..=20.0).suffix(" px"))
+                        .on_hover_text("Drags longer than this zoom rather than select");
+                });
+                show_row_ui(&mut body, "Click Time Limit", |ui: &mut _| {
+                    let thresholds = &mut cx.click_thresholds;
+                    ui.add(Slider::new(&mut thresholds.max_duration, 0.1..=2.0).suffix(" s"));
+                });
+                show_row_ui(&mut body, "Shade Idle Time", |ui: &mut _| {
+                    ui.checkbox(&mut cx.shade_idle, "")
+                        .on_hover_text("Hover a slot's label to see how idle it is");
+                });
+                show_row_ui(&mut body, "Show Titles on Items", |ui: &mut _| {
+                    ui.checkbox(&mut cx.show_item_titles, "");
+                });
+                show_row_ui(&mut body, "Link Selection Across Profiles", |ui: &mut _| {
+                    ui.checkbox(&mut cx.link_selection, "")
+                        .on_hover_text("Highlight items with the same title as a selected item");
+                });
+                show_row_ui(&mut body, "Selection Color", |ui: &mut _| {
+                    ui.color_edit_button_srgba(&mut cx.highlight_colors.selected);
+                });
+                show_row_ui(&mut body, "Search Match Color", |ui: &mut _| {
+                    ui.color_edit_button_srgba(&mut cx.highlight_colors.search_match);
+                });
+                show_row_ui(&mut body, "Search Dimming", |ui: &mut _| {
+                    ui.add(Slider::new(&mut cx.highlight_colors.dim_factor, 0.0..=1.0));
+                });
+                show_row_ui(&mut body, "Absolute Time (UTC)", |ui: &mut _| {
+                    let checkbox = egui::Checkbox::new(&mut cx.absolute_time, "");
+                    if ui.add_enabled(cx.epoch.is_some(), checkbox).changed() {
+                        ProfApp::update_interval_select_state(cx);
+                    }
+                });
+                show_row_ui(&mut body, "Time Units", |ui: &mut _| {
+                    let unit = &mut cx.display_unit;
+                    let before = *unit;
+                    egui::ComboBox::from_id_source("Time Units")
+                        .selected_text(format!("{:?}", unit))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(unit, DisplayUnit::Auto, "Auto");
+                            ui.selectable_value(unit, DisplayUnit::Nanoseconds, "Nanoseconds");
+                            ui.selectable_value(unit, DisplayUnit::Microseconds, "Microseconds");
+                            ui.selectable_value(unit, DisplayUnit::Milliseconds, "Milliseconds");
+                            ui.selectable_value(unit, DisplayUnit::Seconds, "Seconds");
+                        });
+                    if *unit != before {
+                        ProfApp::update_interval_select_state(cx);
+                    }
+                });
+                show_row_ui(&mut body, "Smooth Scrolling", |ui: &mut _| {
+                    ui.checkbox(&mut cx.smooth_scroll, "");
+                });
+                show_row_ui(&mut body, "Limit Repaint Rate", |ui: &mut _| {
+                    let cap = &mut cx.repaint_cap;
+                    ui.checkbox(&mut cap.enabled, "").on_hover_text(
+                        "Repaint less often while loading or animating, to save power",
+                    );
+                    ui.add_enabled(
+                        cap.enabled,
+                        Slider::new(&mut cap.max_fps, 1.0..=60.0).suffix(" fps"),
+                    );
+                });
+                show_row_ui(&mut body, "Row Guides", |ui: &mut _| {
+                    let guides = &mut cx.row_guides;
+                    egui::ComboBox::from_id_source("Row Guides")
+                        .selected_text(format!("{:?}", guides))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(guides, RowGuides::None, "None");
+                            ui.selectable_value(guides, RowGuides::Separators, "Separators");
+                            ui.selectable_value(guides, RowGuides::Alternating, "Alternating");
+                        });
+                });
+                show_row_ui(&mut body, "Time Ruler", |ui: &mut _| {
+                    ui.checkbox(&mut cx.show_ruler, "")
+                        .on_hover_text("Also draws gridlines through the slots");
+                });
+                show_row_ui(&mut body, "Utilization Plots", |ui: &mut _| {
+                    let rows = &mut cx.summary_rows;
+                    egui::ComboBox::from_id_source("Utilization Plots")
+                        .selected_text(format!("{:?}", rows))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(rows, SummaryRows::Full, "Full");
+                            ui.selectable_value(rows, SummaryRows::Strip, "Strip")
+                                .on_hover_text("One row high");
+                            ui.selectable_value(rows, SummaryRows::Hidden, "Hidden");
+                        });
+                });
+                show_row_ui(&mut body, "Initial Zoom", |ui: &mut _| {
+                    let zoom = &mut cx.startup_zoom;
+                    egui::ComboBox::from_id_source("Initial Zoom")
+                        .selected_text(format!("{:?}", zoom))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(zoom, StartupZoom::Fit, "Fit")
+                                .on_hover_text("Show the whole profile");
+                            ui.selectable_value(zoom, StartupZoom::Remembered, "Remembered")
+                                .on_hover_text(
+                                    "Show the view from the last session with the same profiles",
+                                );
+                        });
+                });
+                show_row_ui(&mut body, "Item Borders", |ui: &mut _| {
+                    let borders = &mut cx.item_borders;
+                    egui::ComboBox::from_id_source("Item Borders")
+                        .selected_text(format!("{:?}", borders))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(borders, ItemBorders::None, "None");
+                            ui.selectable_value(borders, ItemBorders::Outline, "Outline");
+                            ui.selectable_value(borders, ItemBorders::Gap, "Gap");
+                        })
+                        .response
+                        .on_hover_text(format!(
+                            "Only for items at least {} px wide",
+                            ITEM_BORDER_MIN_WIDTH
+                        ));
+                });
+                show_row_ui(&mut body, "Markers", |ui: &mut _| {
+                    let state = &mut cx.marker_state;
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.time_buffer)
+                            .hint_text("Time")
+                            .desired_width(80.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.label_buffer)
+                            .hint_text("Label")
+                            .desired_width(80.0),
+                    );
+                    if ui.button("Add").clicked() {
+                        match cx.parse_timestamp(cx.marker_state.time_buffer.trim()) {
+                            Ok(time) => {
+                                let state = &mut cx.marker_state;
+                                let mut label = std::mem::take(&mut state.label_buffer);
+                                if label.is_empty() {
+                                    label = format!("Marker {}", cx.markers.len() + 1);
+                                }
+                                cx.markers.push((time, label));
+                                state.time_buffer.clear();
+                                state.error = None;
+                            }
+                            Err(e) => cx.marker_state.error = Some(e),
+                        }
+                    }
+                    if let Some(error) = cx.marker_state.error {
+                        ui.colored_label(Color32::RED, error.to_string());
+                    }
+                });
+                let mut remove = None;
+                for (i, (time, label)) in cx.markers.iter().enumerate() {
+                    show_row_ui(&mut body, label, |ui: &mut _| {
+                        ui.label(cx.format_timestamp(*time));
+                        if ui.button("Remove").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove {
+                    cx.markers.remove(i);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let mut fonts_changed = false;
+                    show_row_ui(&mut body, "Extra Fonts", |ui: &mut _| {
+                        let response = ui
+                            .text_edit_singleline(&mut cx.font_path_buffer)
+                            .on_hover_text("Path to a .ttf or .otf file");
+                        let enter =
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        let path = cx.font_path_buffer.trim();
+                        if (ui.button("Add").clicked() || enter) && !path.is_empty() {
+                            cx.font_paths.push(path.to_owned());
+                            cx.font_path_buffer.clear();
+                            fonts_changed = true;
+                        }
+                    });
+                    let mut remove = None;
+                    for (i, path) in cx.font_paths.iter().enumerate() {
+                        let label = std::path::Path::new(path)
+                            .file_name()
+                            .map_or(path.clone(), |name| name.to_string_lossy().into_owned());
+                        show_row_ui(&mut body, &label, |ui: &mut _| {
+                            if ui.button("Remove").on_hover_text(path).clicked() {
+                                remove = Some(i);
+                            }
+                            if let Some(error) = cx.font_errors.get(path) {
+                                ui.colored_label(Color32::RED, error);
+                            }
+                        });
+                    }
+                    if let Some(i) = remove {
+                        cx.font_paths.remove(i);
+                        fonts_changed = true;
+                    }
+                    if fonts_changed {
+                        ProfApp::install_fonts(&egui_ctx, cx);
+                    }
+                }
+                show_row_ui(
+                    &mut body,
+                    "Logarithmic Time (Experimental)",
+                    |ui: &mut _| {
+                        ui.checkbox(&mut cx.log_time, "").on_hover_text(
+                            "Measured from the start of the profile, so the start is magnified \
+                         and later times are compressed. Panning and zooming by keyboard \
+                         still move by fractions of the linear duration.",
+                        );
+                    },
+                );
+                show_row_ui(&mut body, "Item Details", |ui: &mut _| {
+                    let layout = &mut cx.detail_layout;
+                    egui::ComboBox::from_id_source("Item Details")
+                        .selected_text(format!("{:?}", layout))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(layout, DetailLayout::Windows, "Windows")
+                                .on_hover_text("One window per selected item");
+                            ui.selectable_value(layout, DetailLayout::Pane, "Pane")
+                                .on_hover_text("One item at a time, with Prev/Next buttons");
+                        });
+                });
+                show_row_ui(&mut body, "Reset All View Settings", |ui: &mut _| {
+                    if ui.button("Reset View").clicked() {
+                        ProfApp::reset_view(cx);
+                    }
+                });
             });
     }
 
@@ -2374,12 +7179,14 @@ impl ProfApp {
                 item_uid,
                 interval,
                 entry_id,
+                row,
             }) => {
                 if label_button(ui, title, mode.label_text()) {
                     result = Some((
                         ItemLocator {
                             entry_id: entry_id.clone(),
                             irow: None,
+                            row: *row,
                             item_uid: *item_uid,
                         },
                         *interval,
@@ -2402,6 +7209,46 @@ impl ProfApp {
         result
     }
 
+    fn profile_picker(
+        ctx: &egui::Context,
+        picker: &mut Vec<(Box<dyn DeferredDataSource>, bool)>,
+        pending: &mut VecDeque<Box<dyn DeferredDataSource>>,
+    ) {
+        let mut open = false;
+        egui::Window::new(format!("Choose Profiles ({} found)", picker.len()))
+            .collapsible(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Select All").clicked() {
+                        picker.iter_mut().for_each(|(_, checked)| *checked = true);
+                    }
+                    if ui.button("Select None").clicked() {
+                        picker.iter_mut().for_each(|(_, checked)| *checked = false);
+                    }
+                });
+                ui.separator();
+                ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for (source, checked) in picker.iter_mut() {
+                        let locator = source.fetch_description().source_locator.join(", ");
+                        ui.checkbox(checked, locator);
+                    }
+                });
+                ui.separator();
+                let count = picker.iter().filter(|(_, checked)| *checked).count();
+                let button = egui::Button::new(format!("Open {} Profiles", count));
+                open = ui.add_enabled(count > 0, button).clicked();
+            });
+        if open {
+            for (mut source, checked) in picker.drain(..) {
+                if checked {
+                    source.fetch_info();
+                    pending.push_back(source);
+                }
+            }
+        }
+    }
+
     fn display_item_details(
         ui: &mut egui::Ui,
         item: &ItemDetail,
@@ -2426,13 +7273,25 @@ impl ProfApp {
             .column(Column::remainder())
             .body(|mut body| {
                 let mut show_row = |k: &str, field: &Field, color: Option<Color32>| {
+                    let location = match field {
+                        Field::String(value) if k == PROVENANCE_FIELD => {
+                            parse_source_location(value)
+                        }
+                        _ => None,
+                    };
+
                     // We need to manually work out the height of the labels
                     // so that the table knows how large to make each row.
                     let width = body.widths()[1];
 
                     let ui = body.ui_mut();
-                    let height = Self::compute_field_height(field, width, cx.item_link_mode, ui)
-                        .max(row_height);
+                    let height = if let Some((file, line)) = location {
+                        let text = format!("{file}:{line} {SOURCE_LOCATION_BUTTON}");
+                        Self::compute_text_height(text, width, ui)
+                    } else {
+                        Self::compute_field_height(field, width, cx.item_link_mode, ui)
+                    }
+                    .max(row_height);
 
                     body.row(height, |mut row| {
                         row.col(|ui| {
@@ -2443,7 +7302,14 @@ impl ProfApp {
                             }
                         });
                         row.col(|ui| {
-                            if let Some(x) =
+                            if let Some(render) = cx.field_renderers.get(k) {
+                                render(ui, field, color);
+                            } else if let Some((file, line)) = location {
+                                ui.add(egui::Label::new(format!("{file}:{line}")).wrap(true));
+                                if ui.button(SOURCE_LOCATION_BUTTON).clicked() {
+                                    open_source_location(ui, file, line);
+                                }
+                            } else if let Some(x) =
                                 Self::render_field_as_ui(field, color, cx.item_link_mode, ui)
                             {
                                 result = Some(x);
@@ -2456,15 +7322,138 @@ impl ProfApp {
                 if cx.debug {
                     show_row("Item UID", &Field::U64(item_meta.item_uid.0), None);
                 }
-                for (field_id, field, color) in &item_meta.fields {
+                for (field_id, field, color) in
+                    ordered_fields(&item_meta.fields, field_schema, &cx.field_order)
+                {
                     let name = field_schema.get_name(*field_id).unwrap();
-                    show_row(name, field, *color);
+                    if !cx.hidden_fields.contains(name) {
+                        show_row(name, field, *color);
+                    }
                 }
             });
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
             if ui.button(cx.item_link_mode.label_text()).clicked() {
                 result = Some((item.loc.clone(), item_meta.original_interval));
             }
+            if ui
+                .button("Copy")
+                .on_hover_text("Copy the fields shown above as text")
+                .clicked()
+            {
+                let text = item_details_text(item_meta, field_schema, cx);
+                ui.output_mut(|o| o.copied_text = text);
+            }
+        });
+        result
+    }
+
+    // Like display_item_details, but for several items in adjacent columns,
+    // with the names of fields that differ highlighted
+    fn display_item_comparison(
+        ui: &mut egui::Ui,
+        items: &[&ItemDetail],
+        field_schema: &FieldSchema,
+        cx: &Context,
+    ) -> Option<(ItemLocator, Interval)> {
+        let Some(metas) = items
+            .iter()
+            .map(|item| item.meta.as_ref())
+            .collect::<Option<Vec<_>>>()
+        else {
+            ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+                ui.label("Items will be compared once data is available.");
+            });
+            return None;
+        };
+
+        let font_id = TextStyle::Body.resolve(ui.style());
+        let row_height = ui.fonts(|f| f.row_height(&font_id));
+        let mode = cx.item_link_mode;
+
+        let mut result: Option<(ItemLocator, Interval)> = None;
+        TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto())
+            .columns(Column::remainder(), metas.len())
+            .body(|mut body| {
+                let mut show_row = |k: &str, fields: Vec<Option<&Field>>| {
+                    // Compare what the user sees, since fields can't be
+                    // compared directly
+                    let text = |f: Option<&Field>| f.map(|f| Self::render_field_as_text(f, mode));
+                    let first = text(fields[0]);
+                    let differs = fields[1..].iter().any(|f| text(*f) != first);
+
+                    let widths = body.widths()[1..].to_vec();
+                    let ui = body.ui_mut();
+                    let mut height = row_height;
+                    for (field, width) in fields.iter().zip(widths) {
+                        if let Some(field) = field {
+                            height = height.max(Self::compute_field_height(field, width, mode, ui));
+                        }
+                    }
+
+                    body.row(height, |mut row| {
+                        row.col(|ui| {
+                            if differs {
+                                let color = ui.visuals().warn_fg_color;
+                                ui.label(RichText::new(k).color(color).strong());
+                            } else {
+                                ui.strong(k);
+                            }
+                        });
+                        for field in fields {
+                            row.col(|ui| {
+                                let Some(field) = field else {
+                                    return;
+                                };
+                                if let Some(render) = cx.field_renderers.get(k) {
+                                    render(ui, field, None);
+                                } else if let Some(x) =
+                                    Self::render_field_as_ui(field, None, mode, ui)
+                                {
+                                    result = Some(x);
+                                }
+                            });
+                        }
+                    });
+                };
+
+                let titles: Vec<_> = metas
+                    .iter()
+                    .map(|meta| Field::String(meta.title.clone()))
+                    .collect();
+                show_row("Title", titles.iter().map(Some).collect());
+                let durations: Vec<_> = metas
+                    .iter()
+                    .map(|meta| {
+                        let duration = meta.original_interval.duration_ns();
+                        Field::String(cx.format_duration(duration))
+                    })
+                    .collect();
+                show_row("Duration", durations.iter().map(Some).collect());
+                if cx.debug {
+                    let uids: Vec<_> = metas
+                        .iter()
+                        .map(|meta| Field::U64(meta.item_uid.0))
+                        .collect();
+                    show_row("Item UID", uids.iter().map(Some).collect());
+                }
+                let fields: Vec<_> = metas.iter().map(|meta| &meta.fields[..]).collect();
+                for (field_id, row) in aligned_fields(&fields, field_schema, &cx.field_order) {
+                    let name = field_schema.get_name(field_id).unwrap();
+                    if !cx.hidden_fields.contains(name) {
+                        show_row(name, row);
+                    }
+                }
+            });
+        ui.horizontal(|ui| {
+            for (i, (item, meta)) in items.iter().zip(&metas).enumerate() {
+                let label = format!("{} {}", mode.label_text(), i + 1);
+                if ui.button(label).clicked() {
+                    result = Some((item.loc.clone(), meta.original_interval));
+                }
+            }
         });
         result
     }
@@ -2473,6 +7462,24 @@ impl ProfApp {
 impl eframe::App for ProfApp {
     /// Called to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        for window in &self.windows {
+            self.cx
+                .search_options
+                .insert(window.locator_key(), window.config.search_state.options());
+            self.cx
+                .scroll_offsets
+                .insert(window.locator_key(), window.config.scroll_offset);
+        }
+        if !self.windows.is_empty() {
+            let view = (self.cx.view_interval, self.cx.view_interval_history.clone());
+            remember_view(
+                &mut self.cx.saved_views,
+                &mut self.cx.saved_view_order,
+                ProfApp::view_key(&self.windows),
+                view,
+            );
+        }
+        self.version = STATE_VERSION;
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
@@ -2480,6 +7487,7 @@ impl eframe::App for ProfApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let Self {
             pending_data_sources,
+            picker_data_sources,
             windows,
             cx,
             #[cfg(not(target_arch = "wasm32"))]
@@ -2487,56 +7495,134 @@ impl eframe::App for ProfApp {
             ..
         } = self;
 
+        if !picker_data_sources.is_empty() {
+            Self::profile_picker(ctx, picker_data_sources, pending_data_sources);
+        }
+
+        let had_focus = ctx.memory(|m| m.focus().is_some());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let timings = &mut cx.frame_timings;
+            timings.last = std::mem::take(&mut timings.current);
+            let own_views = windows.iter().filter_map(|w| w.view.as_ref());
+            let views = std::iter::once(cx.view_interval)
+                .chain(own_views.map(|view| view.interval))
+                .collect();
+            cx.render_budget.start_frame(views);
+        }
+
+        ctx.input(|i| {
+            if i.pointer.primary_pressed() {
+                cx.press_origin = i.pointer.interact_pos().map(|pos| (pos, i.time));
+            }
+        });
+
         if let Some(mut source) = pending_data_sources.pop_front() {
             // We made one request, so we know there is always zero or one
             // elements in this list.
-            if let Some(info) = source.get_infos().pop() {
-                let window = Window::new(source, info, windows.len() as u64);
-                if windows.is_empty() {
-                    cx.total_interval = window.config.interval;
-                } else {
-                    cx.total_interval = cx.total_interval.union(window.config.interval);
+            let info = source.get_infos().pop();
+            // Catch malformed profiles here, rather than crashing later on
+            let info =
+                info.map(|info| info.and_then(|info| info.entry_info.validate().map(|_| info)));
+            match info {
+                Some(Ok(info)) => {
+                    let mut window = Window::new(source, info, windows.len() as u64);
+                    window.apply_kind_expanded(&cx.kind_expanded);
+                    if let Some(options) = cx.search_options.get(&window.locator_key()) {
+                        let config = &mut window.config;
+                        config
+                            .search_state
+                            .restore_options(*options, &config.field_schema);
+                    }
+                    let offset = cx.scroll_offsets.get(&window.locator_key());
+                    window.config.restore_scroll_offset = offset.copied();
+                    if windows.is_empty() {
+                        window.apply_start_options(&mut cx.start_options);
+                    }
+                    if cx.epoch.is_none() {
+                        cx.epoch = window.config.epoch;
+                    }
+                    if windows.is_empty() {
+                        cx.total_interval = window.config.interval;
+                    } else {
+                        cx.total_interval = cx.total_interval.union(window.config.interval);
+                    }
+                    ProfApp::zoom(cx, cx.total_interval);
+                    windows.push(window);
+                }
+                Some(Err(e)) => {
+                    let locator = source.fetch_description().source_locator;
+                    log::error!("unable to load profile from {:?}: {}", locator, e);
+                    cx.load_errors
+                        .push(format!("Unable to load {}: {}", locator.join(", "), e));
+                }
+                None => {
+                    pending_data_sources.push_front(source);
+                }
+            }
+        }
+        if pending_data_sources.is_empty() && !windows.is_empty() {
+            ProfApp::apply_startup_view(cx, windows);
+        }
+
+        cx.selected_titles.clear();
+        if cx.link_selection && windows.len() > 1 {
+            for window in windows.iter() {
+                for item in window.config.items_selected.values() {
+                    if let Some(meta) = &item.meta {
+                        cx.selected_titles
+                            .entry(meta.title.clone())
+                            .or_default()
+                            .insert(window.index);
+                    }
                 }
-                ProfApp::zoom(cx, cx.total_interval);
-                windows.push(window);
-            } else {
-                pending_data_sources.push_front(source);
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let timer = cx.start_timer();
         for window in windows.iter_mut() {
-            for tile in window.config.data_source.get_summary_tiles() {
-                if let Some(entry) = window.find_summary_mut(&tile.entry_id) {
+            for (tile, req) in window.config.data_source.get_summary_tiles() {
+                if let Some(entry) = window.find_summary_mut(&req.entry_id) {
                     // If the entry doesn't exist, we already zoomed away and
                     // are no longer interested in this tile.
                     entry
                         .tiles
-                        .entry(tile.tile_id)
-                        .and_modify(|t| *t = Some(tile.data));
+                        .entry(req.tile_id)
+                        .and_modify(|t| *t = Some(tile.map(|tile| tile.data)));
                 }
             }
 
-            for tile in window.config.data_source.get_slot_tiles() {
-                if let Some(entry) = window.find_slot_mut(&tile.entry_id) {
+            for (tile, req) in window.config.data_source.get_slot_tiles() {
+                if let Some(entry) = window.find_slot_mut(&req.entry_id) {
                     // If the entry doesn't exist, we already zoomed away and
                     // are no longer interested in this tile.
-                    entry
-                        .tiles
-                        .entry(tile.tile_id)
-                        .and_modify(|t| *t = Some(tile.data));
+                    entry.receive_tile(req.tile_id, tile.map(|tile| tile.data));
                 }
             }
 
-            for tile in window.config.data_source.get_slot_meta_tiles() {
-                if let Some(entry) = window.find_slot_mut(&tile.entry_id) {
+            for (tile, req) in window.config.data_source.get_slot_meta_tiles() {
+                if let Some(entry) = window.find_slot_mut(&req.entry_id) {
                     // If the entry doesn't exist, we already zoomed away and
                     // are no longer interested in this tile.
-                    entry
-                        .tile_metas
-                        .entry(tile.tile_id)
-                        .and_modify(|t| *t = Some(tile.data));
+                    entry.receive_meta_tile(req.tile_id, tile.map(|tile| tile.data));
                 }
             }
+
+            window.swap_view(cx);
+            window.evict_meta_tiles(cx);
+            window.swap_view(cx);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        cx.stop_timer("Receive tiles", timer);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        for window in windows.iter_mut() {
+            // Keep going until the export is done, even with no input
+            if window.poll_chrome_trace() {
+                ctx.request_repaint();
+            }
         }
 
         let mut _fps = 0.0;
@@ -2553,6 +7639,10 @@ impl eframe::App for ProfApp {
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
+                    for window in windows.iter_mut() {
+                        window.export_chrome_trace_ui(ui, cx);
+                    }
+                    ui.separator();
                     if ui.button("Quit").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
@@ -2560,6 +7650,8 @@ impl eframe::App for ProfApp {
             });
         });
 
+        ProfApp::warning_banner(ctx, cx, windows);
+
         egui::SidePanel::left("side_panel").show(ctx, |ui| {
             let body = TextStyle::Body.resolve(ui.style()).size;
             let heading = TextStyle::Heading.resolve(ui.style()).size;
@@ -2572,14 +7664,34 @@ impl eframe::App for ProfApp {
             for window in windows.iter_mut() {
                 egui::Frame::group(ui.style()).show(ui, |ui| {
                     ui.set_width(ui.available_width());
+                    window.swap_view(cx);
                     window.controls(ui, cx);
+                    window.swap_view(cx);
                 });
             }
 
+            if let Some(index) = cx.duplicate_window.take() {
+                if let Some(window) = windows.iter().find(|w| w.index == index) {
+                    let locator = window.source_locator();
+                    let copy = windows
+                        .iter()
+                        .filter(|w| w.source_locator() == locator)
+                        .map(|w| w.copy)
+                        .max()
+                        .unwrap_or(0)
+                        + 1;
+                    let mut window = window.duplicate(windows.len() as u64, copy, cx);
+                    window.apply_kind_expanded(&cx.kind_expanded);
+                    windows.push(window);
+                }
+            }
+
             for window in windows.iter_mut() {
                 egui::Frame::group(ui.style()).show(ui, |ui| {
                     ui.set_width(ui.available_width());
+                    window.swap_view(cx);
                     window.search_controls(ui, cx);
+                    window.swap_view(cx);
                 });
             }
 
@@ -2621,6 +7733,36 @@ impl eframe::App for ProfApp {
                     {
                         if cx.debug {
                             ui.label(format!("FPS: {_fps:.0}"));
+                            for (phase, time) in &cx.frame_timings.last {
+                                let ms = time.as_secs_f64() * 1e3;
+                                ui.label(format!("{phase}: {ms:.1} ms"));
+                            }
+                        }
+                    }
+
+                    if cx.debug {
+                        ui.checkbox(&mut cx.bypass_tile_cache, "Bypass Tile Cache")
+                            .on_hover_text(
+                                "Re-fetch all tiles from the data source on every view change",
+                            );
+
+                        let size: usize = windows.iter().map(|w| w.panel.meta_tiles_size()).sum();
+                        ui.label(format!(
+                            "Meta tiles: {:.1} MiB",
+                            size as f64 / (1 << 20) as f64
+                        ));
+
+                        // Peaks are reached at different times, so they
+                        // can't be added up across profiles
+                        for window in windows.iter() {
+                            let stats = window.config.data_source.stats();
+                            ui.label(format!(
+                                "Profile {} requests: {} ({} outstanding, peak {})",
+                                window.index,
+                                stats.total_requests,
+                                stats.outstanding_requests,
+                                stats.peak_outstanding_requests
+                            ));
                         }
                     }
                 });
@@ -2630,6 +7772,19 @@ impl eframe::App for ProfApp {
             });
         });
 
+        let mut pane_targets: Vec<_> = windows.iter().map(|_| None).collect();
+        if cx.detail_layout == DetailLayout::Pane
+            && windows.iter().any(|w| !w.config.items_selected.is_empty())
+        {
+            egui::SidePanel::right("detail_pane").show(ctx, |ui| {
+                for (window, target) in windows.iter_mut().zip(&mut pane_targets) {
+                    window.swap_view(cx);
+                    *target = window.detail_pane(ui, cx);
+                    window.swap_view(cx);
+                }
+            });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // Use body font to figure out how tall to draw rectangles.
             let font_id = TextStyle::Body.resolve(ui.style());
@@ -2637,39 +7792,100 @@ impl eframe::App for ProfApp {
             // Just set this on every frame for now
             cx.row_height = row_height * cx.scale_factor;
 
-            let y_scroll_delta = cx.row_height * cx.row_scroll_delta as f32;
-            ui.scroll_with_delta(Vec2::new(0.0, y_scroll_delta));
+            // Take over scrolling from the mouse wheel so that it scrolls in
+            // whole rows, like the keyboard does
+            let mut wheel_delta = 0.0;
+            if ui.rect_contains_pointer(ui.max_rect()) {
+                wheel_delta = ui.input(|i| i.scroll_delta.y);
+                cx.wheel_remainder += wheel_delta;
+                let rows = (cx.wheel_remainder / cx.row_height).trunc();
+                cx.wheel_remainder -= rows * cx.row_height;
+                cx.row_scroll_delta += rows as i32;
+            }
+
+            cx.scroll_pending += cx.row_height * cx.row_scroll_delta as f32;
             cx.row_scroll_delta = 0;
+            let y_scroll_delta = if cx.smooth_scroll && cx.scroll_pending.abs() > 1.0 {
+                cx.request_repaint(ctx);
+                cx.scroll_pending * 0.3
+            } else {
+                cx.scroll_pending
+            };
+            cx.scroll_pending -= y_scroll_delta;
+            ui.scroll_with_delta(Vec2::new(0.0, y_scroll_delta - wheel_delta));
+
+            if cx.show_ruler {
+                Self::ruler(ui, cx);
+            }
+
+            let page_height = ui.available_height() / windows.len().max(1) as f32;
+            cx.page_rows = ((page_height / cx.row_height) as i32 - 1).max(1);
 
+            // Where to draw the cursor and markers, with the window whose view
+            // they're for. Consecutive windows on the shared view are grouped,
+            // so that the cursor runs across all of them.
+            let mut areas: Vec<(Option<usize>, Rect)> = Vec::new();
             let mut remaining = windows.len();
             // Only wrap in a frame if more than one profile
             if remaining > 1 {
-                for window in windows.iter_mut() {
-                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                for (i, window) in windows.iter_mut().enumerate() {
+                    let frame = egui::Frame::group(ui.style()).show(ui, |ui| {
                         ui.push_id(window.index, |ui| {
                             ui.set_height(ui.available_height() / (remaining as f32));
                             ui.set_width(ui.available_width());
+                            window.swap_view(cx);
                             window.content(ui, cx);
+                            window.swap_view(cx);
                             remaining -= 1;
                         });
                     });
+                    let rect = frame.response.rect;
+                    match areas.last_mut() {
+                        Some((None, area)) if window.view.is_none() => *area = area.union(rect),
+                        _ => areas.push((window.view.is_some().then_some(i), rect)),
+                    }
                 }
             } else {
                 for window in windows.iter_mut() {
                     window.content(ui, cx);
                 }
+                areas.push((None, ui.min_rect()));
             }
 
-            Self::cursor(ui, cx);
+            cx.hover_time = None;
+            cx.active_window = None;
+            for (i, area) in areas {
+                let mut window = i.map(|i| &mut windows[i]);
+                if let Some(window) = &mut window {
+                    window.swap_view(cx);
+                    if ui.rect_contains_pointer(area) {
+                        cx.active_window = Some(window.index);
+                    }
+                }
+                Self::markers(ui, area, cx);
+                Self::measurement(ui, area, cx);
+                Self::cursor(ui, area, cx);
+                if let Some(window) = window {
+                    window.swap_view(cx);
+                }
+            }
         });
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if cx.render_budget.finish_frame() {
+            cx.request_repaint(ctx);
+        }
+
+        let mut show_controls = cx.show_controls;
         egui::Window::new("Controls")
-            .open(&mut cx.show_controls)
+            .open(&mut show_controls)
             .resizable(false)
-            .show(ctx, |ui| Self::display_controls(ui, &mut cx.item_link_mode));
+            .show(ctx, |ui| Self::display_controls(ui, cx));
+        cx.show_controls = show_controls;
 
-        for window in windows.iter_mut() {
-            let mut zoom_target = None;
+        for (window, pane_target) in windows.iter_mut().zip(pane_targets) {
+            let mut zoom_target = pane_target;
+            window.swap_view(cx);
 
             // Hack: work around mutability conflict
             let mut items_selected = BTreeMap::new();
@@ -2684,6 +7900,10 @@ impl eframe::App for ProfApp {
                     }
                 }
 
+                if cx.detail_layout == DetailLayout::Pane {
+                    return true;
+                }
+
                 let short_title = match &item.meta {
                     Some(meta) => meta.title.chars().take(50).collect(),
                     None => format!("Item <Item UID: {}>", item.loc.item_uid.0),
@@ -2705,6 +7925,91 @@ impl eframe::App for ProfApp {
             });
             std::mem::swap(&mut items_selected, &mut window.config.items_selected);
 
+            if let Some((item, pos)) = window.config.pinned_tooltip.take() {
+                let title = item.meta.as_ref().map_or("", |meta| &meta.title);
+                let mut open = true;
+                egui::Window::new(title.chars().take(50).collect::<String>())
+                    .id(egui::Id::new((
+                        "pinned_tooltip",
+                        window.index,
+                        item.loc.item_uid.0,
+                    )))
+                    .open(&mut open)
+                    .collapsible(false)
+                    .default_pos(pos)
+                    .show(ctx, |ui| {
+                        let target =
+                            Self::display_item_details(ui, &item, &window.config.field_schema, cx);
+                        if target.is_some() {
+                            zoom_target = target;
+                        }
+                    });
+                if open && window.config.pinned_tooltip.is_none() {
+                    window.config.pinned_tooltip = Some((item, pos));
+                }
+            }
+
+            if window.config.compare_items {
+                let items: Vec<_> = window.config.items_selected.values().collect();
+                let mut open = items.len() >= 2;
+                if open {
+                    egui::Window::new("Compare Items")
+                        .id(egui::Id::new(("compare_items", window.index)))
+                        .open(&mut open)
+                        .resizable(true)
+                        .show(ctx, |ui| {
+                            let target = Self::display_item_comparison(
+                                ui,
+                                &items,
+                                &window.config.field_schema,
+                                cx,
+                            );
+                            if target.is_some() {
+                                zoom_target = target;
+                            }
+                        });
+                }
+                window.config.compare_items = open;
+            }
+
+            if let Some((loc, interval, pos)) = window.config.item_menu.take() {
+                let area = egui::Area::new(egui::Id::new(("item_menu", window.index)))
+                    .order(egui::Order::Foreground)
+                    .fixed_pos(pos)
+                    .show(ctx, |ui| {
+                        egui::Frame::menu(ui.style())
+                            .show(ui, |ui| {
+                                ui.button("Copy Link to Item")
+                                    .on_hover_text("Opens the profile on this item")
+                                    .clicked()
+                            })
+                            .inner
+                    });
+                if area.inner {
+                    let params = item_link_params(&loc, interval);
+                    if let Some(link) = item_link(&window.source_locator(), &params) {
+                        ctx.output_mut(|o| o.copied_text = link);
+                    }
+                } else {
+                    // Close on any click outside the menu
+                    let outside = ctx.input(|i| {
+                        i.pointer.any_pressed()
+                            && i.pointer
+                                .interact_pos()
+                                .is_some_and(|p| !area.response.rect.contains(p))
+                    });
+                    if !outside {
+                        window.config.item_menu = Some((loc, interval, pos));
+                    }
+                }
+            }
+
+            if let Some(last) = &window.config.last_selected {
+                if !window.config.items_selected.contains_key(&last.item_uid) {
+                    window.config.last_selected = None;
+                }
+            }
+
             if let Some((item_loc, interval)) = zoom_target {
                 let interval = match cx.item_link_mode {
                     // In Zoom mode, put the item in the center of the view
@@ -2720,17 +8025,27 @@ impl eframe::App for ProfApp {
                 window.expand_slot(&item_loc.entry_id);
                 window.config.scroll_to_item(item_loc);
             }
+            window.swap_view(cx);
         }
 
-        Self::keyboard(ctx, cx, windows);
+        Self::keyboard(ctx, cx, windows, had_focus);
+
+        #[cfg(target_arch = "wasm32")]
+        if pending_data_sources.is_empty() && !windows.is_empty() {
+            Self::update_location(ctx, cx, windows);
+        }
 
-        // Keep repainting as long as we have outstanding requests.
+        // Keep repainting as long as we have outstanding requests. (Data
+        // sources can't wake us up, so we poll.) Nothing else keeps the app
+        // repainting: animations stop on their own, and the FPS shown in
+        // debug mode is only measured when a frame is drawn anyway.
         if !pending_data_sources.is_empty()
             || windows
                 .iter()
                 .any(|w| w.config.data_source.outstanding_requests() > 0)
         {
-            ctx.request_repaint_after(Duration::from_millis(50));
+            let poll = Duration::from_millis(50).max(cx.repaint_cap.min_interval());
+            ctx.request_repaint_after(poll);
         }
     }
 }
@@ -2804,8 +8119,297 @@ fn get_locator(data_sources: &[Box<dyn DeferredDataSource>]) -> String {
     }
 }
 
+// Options for how the viewer opens
+#[derive(Debug, Clone, Default)]
+pub struct StartOptions {
+    // Initial view, overriding the remembered or fitted one
+    pub initial_interval: Option<Interval>,
+    // Item to select (in the first profile), given by its slot and UID
+    pub initial_item: Option<(EntryID, ItemUID)>,
+    // Search query for the first profile
+    pub initial_search: Option<String>,
+    // First and last node to show in the first profile
+    pub initial_nodes: Option<(u64, u64)>,
+    // Fonts (name and TrueType/OpenType data) to use for characters missing
+    // from the built-in fonts, in addition to those configured in the viewer
+    pub extra_fonts: Vec<(String, Vec<u8>)>,
+    /// Custom widgets for fields in item details
+    pub field_renderers: FieldRenderers,
+}
+
+/// Draws a field's value (with the field's color, if any) in place of the
+/// default rendering. The row is sized for the default rendering, so the
+/// widget should be about as large.
+pub type FieldRenderer = Arc<dyn Fn(&mut egui::Ui, &Field, Option<Color32>) + Send + Sync>;
+
+/// Custom renderers for item fields, keyed by field name. Fields without one
+/// are rendered as usual. For example, to show sizes in GiB:
+///
+/// ```
+/// # use legion_prof_viewer::app::FieldRenderers;
+/// # use legion_prof_viewer::data::Field;
+/// let mut renderers = FieldRenderers::default();
+/// renderers.insert("Size", |ui, field, _color| {
+///     if let Field::U64(bytes) = field {
+///         ui.label(format!("{:.1} GiB", *bytes as f64 / (1u64 << 30) as f64));
+///     }
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct FieldRenderers {
+    renderers: BTreeMap<String, FieldRenderer>,
+}
+
+impl FieldRenderers {
+    pub fn insert(
+        &mut self,
+        field_name: impl Into<String>,
+        renderer: impl Fn(&mut egui::Ui, &Field, Option<Color32>) + Send + Sync + 'static,
+    ) {
+        self.renderers.insert(field_name.into(), Arc::new(renderer));
+    }
+
+    pub fn get(&self, field_name: &str) -> Option<&FieldRenderer> {
+        self.renderers.get(field_name)
+    }
+}
+
+impl fmt::Debug for FieldRenderers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.renderers.keys()).finish()
+    }
+}
+
+// Splits the text of a RON struct, e.g. "(a:1,b:(c:\"x,y\"))", into the names
+// of its fields and the text of their values. Returns None if the text isn't
+// a struct.
+fn split_ron_struct(text: &str) -> Option<Vec<(String, String)>> {
+    // Struct names are optional in RON
+    let text = text
+        .trim()
+        .trim_start_matches(|c: char| c.is_alphanumeric() || c == '_');
+    let body = text.strip_prefix('(')?.strip_suffix(')')?;
+
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&body[start..]);
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (name, value) = part.split_once(':')?;
+            let name = name.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return None;
+            }
+            Some((name.to_owned(), value.trim().to_owned()))
+        })
+        .collect()
+}
+
+fn join_ron_struct(fields: &[(String, String)]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|(name, value)| format!("{}:{}", name, value))
+        .collect();
+    format!("({})", fields.join(","))
+}
+
+// Whether egui will be able to load the data as a font. This is the same
+// parse egui does, which panics on failure.
+fn is_font_data(data: &[u8]) -> bool {
+    ab_glyph::FontRef::try_from_slice(data).is_ok()
+}
+
+// Query parameters used for the view in links, other than the data sources
+#[cfg(target_arch = "wasm32")]
+const VIEW_LINK_KEYS: [&str; 5] = ["interval", "entry", "item", "search", "nodes"];
+
+// Query parameters that reproduce the current view (see
+// StartOptions::from_query_pairs). The selection, search and node range are
+// those of the first profile.
+#[cfg(any(target_arch = "wasm32", test))]
+fn view_link_params(cx: &Context, windows: &[Window]) -> Vec<(&'static str, String)> {
+    let view = cx.view_interval;
+    let mut params = vec![("interval", format!("{}ns..{}ns", view.start.0, view.stop.0))];
+    if let Some(window) = windows.first() {
+        let config = &window.config;
+        if let Some(loc) = &config.last_selected {
+            let slug = crate::data::EntryIDSlug(&loc.entry_id);
+            params.push(("entry", slug.to_string()));
+            params.push(("item", loc.item_uid.0.to_string()));
+        }
+        if !config.search_state.query.is_empty() {
+            params.push(("search", config.search_state.query.clone()));
+        }
+        let total = window.panel.slots.len().saturating_sub(1) as u64;
+        if config.min_node > 0 || config.max_node < total {
+            params.push(("nodes", format!("{}..{}", config.min_node, config.max_node)));
+        }
+    }
+    params
+}
+
+// Query parameters that open a profile on an item, zoomed to it
+fn item_link_params(loc: &ItemLocator, interval: Interval) -> Vec<(&'static str, String)> {
+    let view = interval.grow((interval.duration_ns() / 20).max(1));
+    vec![
+        ("interval", format!("{}ns..{}ns", view.start.0, view.stop.0)),
+        ("entry", crate::data::EntryIDSlug(&loc.entry_id).to_string()),
+        ("item", loc.item_uid.0.to_string()),
+    ]
+}
+
+// Starts the links copied in the native viewer (see StartOptions::from_link)
+pub const ITEM_LINK_SCHEME: &str = "legion-prof://";
+
+// On the web, the page URL with the view replaced by the given parameters
+#[cfg(target_arch = "wasm32")]
+fn item_link(_locator: &str, params: &[(&'static str, String)]) -> Option<String> {
+    let href = web_sys::window()?.location().href().ok()?;
+    let url = web_sys::Url::new(&href).ok()?;
+    let search = url.search_params();
+    for key in VIEW_LINK_KEYS {
+        search.delete(key);
+    }
+    for (key, value) in params {
+        search.set(key, value);
+    }
+    Some(url.href())
+}
+
+// On native, a link naming the profile for the recipient to open with the
+// same parameters (see StartOptions::from_link). The locator is escaped; the
+// parameters only use URL-safe characters, so they need no escaping.
+#[cfg(not(target_arch = "wasm32"))]
+fn item_link(locator: &str, params: &[(&'static str, String)]) -> Option<String> {
+    let query = params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+    let locator = percent_encode(locator);
+    Some(format!("{ITEM_LINK_SCHEME}{locator}?{query}"))
+}
+
+// Escapes everything but unreserved characters and path separators
 #[cfg(not(target_arch = "wasm32"))]
+fn percent_encode(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~/".contains(&b) {
+            result.push(b as char);
+        } else {
+            result.push_str(&format!("%{:02X}", b));
+        }
+    }
+    result
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut result = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            result.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            result.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(result).ok()
+}
+
+impl StartOptions {
+    // Reads the options from a link copied with "Copy Link to Item", i.e.
+    // `legion-prof://<profile>?<query>`, or any URL with a query string
+    // (see `StartOptions::from_query_pairs`). The profile is not opened;
+    // that is up to the caller (see `StartOptions::link_locator`).
+    pub fn from_link(link: &str) -> Self {
+        let query = link.split_once('?').map_or("", |(_, query)| query);
+        Self::from_query_pairs(query.split('&').filter_map(|pair| pair.split_once('=')))
+    }
+
+    // The profile named by a link copied with "Copy Link to Item", or None
+    // if it's not such a link
+    pub fn link_locator(link: &str) -> Option<String> {
+        let rest = link.strip_prefix(ITEM_LINK_SCHEME)?;
+        let locator = rest.split_once('?').map_or(rest, |(locator, _)| locator);
+        percent_decode(locator).filter(|locator| !locator.is_empty())
+    }
+
+    // Reads the options from URL query parameters, as kept up to date by the
+    // web build while the user navigates. Other parameters (e.g., the data
+    // source URLs) are ignored, as are invalid values, since links may be
+    // stale or mangled.
+    pub fn from_query_pairs<K: AsRef<str>, V: AsRef<str>>(
+        pairs: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        let mut result = Self::default();
+        let mut entry_id = None;
+        let mut item_uid = None;
+        for (key, value) in pairs {
+            let value = value.as_ref();
+            match key.as_ref() {
+                "interval" => match Interval::parse(value) {
+                    Ok(interval) => result.initial_interval = Some(interval),
+                    Err(e) => log::warn!("ignoring interval {:?}: {}", value, e),
+                },
+                "entry" => entry_id = EntryID::from_slug(value).ok(),
+                "item" => item_uid = value.parse().ok().map(ItemUID),
+                "search" => result.initial_search = Some(value.to_owned()),
+                "nodes" => {
+                    let nodes = value
+                        .split_once("..")
+                        .and_then(|(min, max)| Some((min.parse().ok()?, max.parse().ok()?)));
+                    if nodes.is_none() {
+                        log::warn!("ignoring nodes {:?}", value);
+                    }
+                    result.initial_nodes = nodes;
+                }
+                _ => {}
+            }
+        }
+        result.initial_item = entry_id.zip(item_uid);
+        result
+    }
+}
+
 pub fn start(data_sources: Vec<Box<dyn DeferredDataSource>>) {
+    start_with_options(data_sources, StartOptions::default());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn start_with_options(data_sources: Vec<Box<dyn DeferredDataSource>>, options: StartOptions) {
     env_logger::try_init().unwrap_or(()); // Log to stderr (if you run with `RUST_LOG=debug`).
 
     // IMPORTANT: This will be used as the directory name for the storage
@@ -2824,13 +8428,13 @@ pub fn start(data_sources: Vec<Box<dyn DeferredDataSource>>) {
     eframe::run_native(
         app_name,
         native_options,
-        Box::new(|cc| Box::new(ProfApp::new(cc, data_sources))),
+        Box::new(|cc| Box::new(ProfApp::new(cc, data_sources, options))),
     )
     .expect("failed to start eframe");
 }
 
 #[cfg(target_arch = "wasm32")]
-pub fn start(data_sources: Vec<Box<dyn DeferredDataSource>>) {
+pub fn start_with_options(data_sources: Vec<Box<dyn DeferredDataSource>>, options: StartOptions) {
     // Redirect `log` message to `console.log` and friends:
     eframe::WebLogger::init(log::LevelFilter::Debug).ok();
 
@@ -2841,9 +8445,831 @@ pub fn start(data_sources: Vec<Box<dyn DeferredDataSource>>) {
             .start(
                 "the_canvas_id", // hardcode it
                 web_options,
-                Box::new(|cc| Box::new(ProfApp::new(cc, data_sources))),
+                Box::new(|cc| Box::new(ProfApp::new(cc, data_sources, options))),
             )
             .await
             .expect("failed to start eframe");
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataSource, EntryInfoBuilder, Item};
+    use crate::deferred_data::DeferredDataSourceWrapper;
+    use crate::null_data::NullDataSource;
+
+    fn interval(start: i64, stop: i64) -> Interval {
+        Interval::new(Timestamp(start), Timestamp(stop))
+    }
+
+    #[test]
+    fn test_time_axis() {
+        let linear = TimeAxis::from(interval(0, 1000));
+        assert_eq!(linear.unlerp(Timestamp(250)), 0.25);
+        assert_eq!(linear.lerp(0.25), Timestamp(250));
+
+        let log = TimeAxis {
+            view: interval(0, 999_999),
+            log_origin: Some(Timestamp(0)),
+        };
+        // Each decade takes the same width
+        assert_eq!(log.unlerp(Timestamp(0)), 0.0);
+        assert!((log.unlerp(Timestamp(999)) - 0.5).abs() < 1e-6);
+        assert_eq!(log.unlerp(Timestamp(999_999)), 1.0);
+        for t in [0, 9, 999, 123_456, 999_999] {
+            assert_eq!(log.lerp(log.unlerp(Timestamp(t))), Timestamp(t));
+        }
+        // Times before the origin are pinned to it
+        assert_eq!(log.unlerp(Timestamp(-50)), 0.0);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_render_budget() {
+        let view = interval(0, 100);
+        let mut budget = RenderBudget::default();
+        budget.start_frame(vec![view]);
+        assert!(budget.draw_next());
+        // Pretend the budget ran out after the first entry
+        budget.deadline = Some(Instant::now());
+        assert!(!budget.draw_next());
+        assert!(!budget.draw_next());
+        assert!(budget.finish_frame());
+
+        // The next frame redraws what was drawn, plus at least one more
+        budget.start_frame(vec![view]);
+        assert!(budget.draw_next());
+        assert!(budget.draw_next());
+        assert!(budget.draw_next());
+        assert!(!budget.finish_frame());
+
+        // Once everything fits, there's no limit until the view changes
+        budget.start_frame(vec![view]);
+        budget.deadline = Some(Instant::now());
+        assert!((0..10).all(|_| budget.draw_next()));
+        budget.start_frame(vec![interval(0, 50)]);
+        assert!(budget.draw_next());
+        budget.deadline = Some(Instant::now());
+        assert!(!budget.draw_next());
+
+        // Likewise when a window is scrolled, from that window on
+        budget.finish_frame();
+        budget.start_frame(vec![interval(0, 50)]);
+        budget.scroll_to(0, 0.0);
+        assert!(budget.draw_next());
+        budget.scroll_to(1, 0.0);
+        assert!(budget.draw_next());
+        assert!(!budget.finish_frame());
+        budget.start_frame(vec![interval(0, 50)]);
+        budget.scroll_to(0, 0.0);
+        budget.deadline = Some(Instant::now());
+        assert!(budget.draw_next());
+        budget.scroll_to(1, 20.0);
+        assert!(!budget.draw_next());
+
+        // Likewise when a window with its own view moves
+        budget.finish_frame();
+        budget.start_frame(vec![interval(0, 50), view]);
+        assert!(budget.draw_next());
+        budget.deadline = Some(Instant::now());
+        assert!(!budget.draw_next());
+    }
+
+    #[test]
+    fn test_split_ron_struct() {
+        let fields = split_ron_struct(r#"Foo(a: 1, b: (c: "x, (y\")"), d: [2, 3],)"#).unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                ("a".to_owned(), "1".to_owned()),
+                ("b".to_owned(), r#"(c: "x, (y\")")"#.to_owned()),
+                ("d".to_owned(), "[2, 3]".to_owned()),
+            ]
+        );
+        assert_eq!(split_ron_struct("[1, 2]"), None);
+    }
+
+    #[test]
+    fn test_migrate_state() {
+        let defaults = ron::to_string(&ProfApp::default()).unwrap();
+        let mut saved = split_ron_struct(&defaults).unwrap();
+        let cx = saved.iter_mut().find(|(name, _)| name == "cx").unwrap();
+        let mut fields = split_ron_struct(&cx.1).unwrap();
+        for (name, value) in &mut fields {
+            match name.as_str() {
+                "log_time" => *value = "true".to_owned(),
+                "row_guides" => *value = "NoLongerAVariant".to_owned(),
+                _ => {}
+            }
+        }
+        fields.push(("removed_setting".to_owned(), "1".to_owned()));
+        cx.1 = join_ron_struct(&fields);
+        let saved = join_ron_struct(&saved);
+        assert!(ron::from_str::<ProfApp>(&saved).is_err());
+
+        let (state, reset) = ProfApp::migrate_state(&saved).unwrap();
+        assert!(state.cx.log_time);
+        assert_eq!(state.cx.row_guides, RowGuides::None);
+        assert_eq!(reset, vec!["row_guides".to_owned()]);
+    }
+
+    #[test]
+    fn test_is_font_data() {
+        let fonts = egui::FontDefinitions::default();
+        for data in fonts.font_data.values() {
+            assert!(is_font_data(&data.font));
+        }
+        // The right magic number isn't enough
+        assert!(!is_font_data(b"\0\x01\0\0\0\x0c"));
+        assert!(!is_font_data(b"OTTO\0\x0c"));
+        assert!(!is_font_data(b"\x89PNG\r\n"));
+        assert!(!is_font_data(b""));
+    }
+
+    #[test]
+    fn test_view_link_round_trip() {
+        let cx = Context {
+            view_interval: interval(-5, 1_234_567_891),
+            ..Default::default()
+        };
+        let params = view_link_params(&cx, &[]);
+        let options = StartOptions::from_query_pairs(params);
+        assert_eq!(options.initial_interval, Some(cx.view_interval));
+
+        let pairs = [
+            ("url", "http://example.com"),
+            ("entry", "0_1_2"),
+            ("item", "42"),
+            ("search", "task 1..2"),
+            ("nodes", "3..x"),
+        ];
+        let options = StartOptions::from_query_pairs(pairs);
+        assert_eq!(options.initial_interval, None);
+        assert_eq!(
+            options.initial_item,
+            Some((EntryID::root().child(0).child(1).child(2), ItemUID(42)))
+        );
+        assert_eq!(options.initial_search.as_deref(), Some("task 1..2"));
+        assert_eq!(options.initial_nodes, None);
+    }
+
+    #[test]
+    fn test_view_link_window() {
+        let mut builder = EntryInfoBuilder::new();
+        let mut slot = None;
+        for i in 0..3 {
+            let node = builder.add_node(format!("n{i}"), format!("Node {i}"));
+            let kind = builder.add_kind(&node, "CPU", "CPU", None);
+            slot = Some(builder.add_slot(&kind, "c0", "CPU 0", 1));
+        }
+        let slot = slot.unwrap();
+        let source = NullDataSource::new(builder.build(), interval(0, 100));
+        let info = source.fetch_info().unwrap();
+        let new_window = || {
+            let source = Box::new(DeferredDataSourceWrapper::new(NullDataSource::with_info(
+                info.clone(),
+            )));
+            Window::new(source, info.clone(), 0)
+        };
+        let cx = Context {
+            view_interval: interval(10, 20),
+            ..Default::default()
+        };
+
+        // Nothing to record for a window in its initial state
+        let window = new_window();
+        let params = view_link_params(&cx, &[window]);
+        assert_eq!(params, vec![("interval", "10ns..20ns".to_owned())]);
+
+        let mut window = new_window();
+        window.config.last_selected = Some(ItemLocator {
+            entry_id: slot.clone(),
+            irow: None,
+            row: None,
+            item_uid: ItemUID(7),
+        });
+        window.config.search_state.query = "task".to_owned();
+        window.config.min_node = 1;
+        window.config.max_node = 1;
+        let params = view_link_params(&cx, &[window]);
+        let slug = crate::data::EntryIDSlug(&slot).to_string();
+        assert_eq!(
+            params[1..],
+            [
+                ("entry", slug),
+                ("item", "7".to_owned()),
+                ("search", "task".to_owned()),
+                ("nodes", "1..1".to_owned()),
+            ]
+        );
+
+        // Opening the link restores the same state
+        let mut options = StartOptions::from_query_pairs(params);
+        let mut window = new_window();
+        window.apply_start_options(&mut options);
+        let config = &window.config;
+        assert_eq!(config.search_state.query, "task");
+        assert_eq!((config.min_node, config.max_node), (1, 1));
+        let selected = config.last_selected.as_ref().unwrap();
+        assert_eq!((&selected.entry_id, selected.item_uid), (&slot, ItemUID(7)));
+    }
+
+    #[test]
+    fn test_item_link_round_trip() {
+        let loc = ItemLocator {
+            entry_id: EntryID::root().child(1).child(0).child(3),
+            irow: None,
+            row: None,
+            item_uid: ItemUID(1234),
+        };
+        let params = item_link_params(&loc, interval(-100, 900));
+        let locator = "/path/to/my profile?x=100%";
+        let link = item_link(locator, &params).unwrap();
+        assert!(link.starts_with("legion-prof:///path/to/my%20profile%3Fx%3D100%25?"));
+        let options = StartOptions::from_link(&link);
+        assert_eq!(options.initial_interval, Some(interval(-150, 950)));
+        assert_eq!(options.initial_item, Some((loc.entry_id, loc.item_uid)));
+        assert_eq!(StartOptions::link_locator(&link).as_deref(), Some(locator));
+
+        let options = StartOptions::from_link("legion-prof://profile");
+        assert_eq!(options.initial_interval, None);
+        assert_eq!(options.initial_item, None);
+        let locator = StartOptions::link_locator("legion-prof://profile");
+        assert_eq!(locator.as_deref(), Some("profile"));
+        assert_eq!(StartOptions::link_locator("legion-prof://bad%2"), None);
+        assert_eq!(StartOptions::link_locator("legion-prof://?item=1"), None);
+        assert_eq!(StartOptions::link_locator("http://host/?item=1"), None);
+    }
+
+    #[test]
+    fn test_zoom_degenerate() {
+        let mut cx = Context {
+            total_interval: interval(0, 100),
+            ..Default::default()
+        };
+        ProfApp::zoom(&mut cx, interval(0, 100));
+
+        // Empty views are refused while the profile has a duration
+        ProfApp::zoom(&mut cx, interval(50, 50));
+        assert_eq!(cx.view_interval, interval(0, 100));
+
+        // Tiny views can always be zoomed back out
+        ProfApp::zoom(&mut cx, interval(50, 51));
+        ProfApp::zoom_out(&mut cx);
+        assert_eq!(cx.view_interval, interval(49, 52));
+
+        // Empty profiles get an empty view, and stay that way
+        let mut cx = Context {
+            total_interval: interval(10, 10),
+            ..Default::default()
+        };
+        ProfApp::zoom(&mut cx, interval(10, 10));
+        ProfApp::zoom_in(&mut cx);
+        ProfApp::zoom_out(&mut cx);
+        assert_eq!(cx.view_interval, interval(10, 10));
+        assert_eq!(item_view_span(cx.time_axis(), interval(10, 10)), None);
+    }
+
+    #[test]
+    fn test_idle_intervals() {
+        let view = interval(100, 200);
+        let items = [
+            interval(50, 110),
+            interval(120, 140),
+            interval(130, 150),
+            // Nested and negative items don't count twice
+            interval(135, 136),
+            interval(190, 160),
+            interval(180, 250),
+        ];
+        let busy = busy_intervals(view, items.into_iter());
+        assert_eq!(
+            busy,
+            [interval(100, 110), interval(120, 150), interval(180, 200)]
+        );
+        let idle = idle_intervals(view, &busy);
+        assert_eq!(idle, [interval(110, 120), interval(150, 180)]);
+        assert_eq!(idle_intervals(view, &[]), [view]);
+    }
+
+    #[test]
+    fn test_item_view_span() {
+        let view = interval(100, 200);
+        let tile = SlotTileData {
+            items: vec![vec![
+                // Zero-length item inside the view
+                Item {
+                    item_uid: ItemUID(0),
+                    interval: interval(150, 150),
+                    color: Color32::RED,
+                },
+                // Negative-length item
+                Item {
+                    item_uid: ItemUID(1),
+                    interval: interval(180, 120),
+                    color: Color32::RED,
+                },
+                // Item hanging off the end of the view
+                Item {
+                    item_uid: ItemUID(2),
+                    interval: interval(190, 250),
+                    color: Color32::RED,
+                },
+            ]],
+        };
+        let spans: Vec<_> = tile.items[0]
+            .iter()
+            .map(|item| item_view_span(view.into(), item.interval))
+            .collect();
+        assert_eq!(spans, [Some((0.5, 0.5)), None, Some((0.9, 1.0))]);
+
+        // Zero-length items on the edges of the view
+        assert_eq!(
+            item_view_span(view.into(), interval(100, 100)),
+            Some((0.0, 0.0))
+        );
+        assert_eq!(item_view_span(view.into(), interval(200, 200)), None);
+    }
+
+    #[test]
+    fn test_repaint_cap() {
+        let cap = RepaintCap::default();
+        assert_eq!(cap.min_interval(), Duration::ZERO);
+        let cap = RepaintCap {
+            enabled: true,
+            max_fps: 20.0,
+        };
+        assert_eq!(cap.min_interval(), Duration::from_millis(50));
+        // Nonsense rates don't stop repainting entirely
+        let cap = RepaintCap {
+            enabled: true,
+            max_fps: 0.0,
+        };
+        assert_eq!(cap.min_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_ordered_fields() {
+        let mut schema = FieldSchema::new();
+        let ids: Vec<_> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|name| schema.insert(name.to_owned(), false))
+            .collect();
+        let fields: Vec<_> = ids.iter().map(|id| (*id, Field::Empty, None)).collect();
+        let names = |order: &[&str]| {
+            let order: Vec<_> = order.iter().map(|x| x.to_string()).collect();
+            ordered_fields(&fields, &schema, &order)
+                .into_iter()
+                .map(|(id, _, _)| schema.get_name(*id).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&[]), ["a", "b", "c", "d"]);
+        // Listed fields come first, then the rest in source order. Names
+        // from other profiles are ignored.
+        assert_eq!(names(&["c", "x", "a"]), ["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let mut schema = FieldSchema::new();
+        let mut state = SearchState::new(schema.insert("Title".to_owned(), true), BTreeSet::new());
+        let cx = Context::default();
+        state.regex_mode = true;
+        state.query = r"task_\d+$".to_owned();
+        state.ensure_valid_cache(&cx);
+        assert!(state.regex_error.is_none());
+        assert!(state.is_string_match("task_42"));
+        assert!(!state.is_string_match("task_42b"));
+
+        // Bad patterns are reported and match nothing
+        state.query = "task_(".to_owned();
+        state.ensure_valid_cache(&cx);
+        assert!(state.regex_error.is_some());
+        assert!(!state.is_string_match("task_("));
+
+        state.regex_mode = false;
+        state.ensure_valid_cache(&cx);
+        assert!(state.regex_error.is_none());
+        assert!(state.is_string_match("task_("));
+    }
+
+    #[test]
+    fn test_search_terms() {
+        let (positive, negative) = parse_search_terms("kernel  -copy -memcpy \\-1 -");
+        assert_eq!(positive, ["kernel", "-1", "-"]);
+        assert_eq!(negative, ["copy", "memcpy"]);
+
+        let mut schema = FieldSchema::new();
+        let mut state = SearchState::new(schema.insert("Title".to_owned(), true), BTreeSet::new());
+        let cx = Context::default();
+        state.query = "kernel -copy".to_owned();
+        state.ensure_valid_cache(&cx);
+        assert!(state.is_string_match("gpu_kernel"));
+        assert!(!state.is_string_match("gpu_kernel_copy"));
+        assert!(!state.is_string_match("memcpy"));
+        // Terms may be found in different parts of a field
+        let field = Field::Vec(vec![
+            Field::String("kernel".to_owned()),
+            Field::String("copy".to_owned()),
+        ]);
+        assert!(!state.is_field_match(&field));
+
+        // Excluded words alone don't search
+        state.query = "-copy".to_owned();
+        state.ensure_valid_cache(&cx);
+        assert!(state.is_exclude_only());
+        assert!(!state.is_active());
+        assert!(!state.is_string_match("gpu_kernel"));
+        state.duration_range = Some((0, 10));
+        assert!(state.is_active());
+
+        state.duration_range = None;
+        state.query = "kernel -copy".to_owned();
+        state.whole_word = true;
+        state.ensure_valid_cache(&cx);
+        assert!(!state.is_string_match("gpu_kernel"));
+        assert!(state.is_string_match("gpu kernel"));
+        assert!(state.is_string_match("kernel copying"));
+    }
+
+    #[test]
+    fn test_search_duration() {
+        let item = |title: &str, start, stop| ItemMeta {
+            item_uid: ItemUID(0),
+            original_interval: interval(start, stop),
+            title: title.to_owned(),
+            fields: Vec::new(),
+        };
+        let short_kernel = item("kernel", 0, 5);
+        let long_kernel = item("kernel", 0, 50);
+        let short_copy = item("copy", 0, 5);
+
+        let mut schema = FieldSchema::new();
+        let mut state = SearchState::new(schema.insert("Title".to_owned(), true), BTreeSet::new());
+        let cx = Context::default();
+        state.duration_range = Some((0, 10));
+
+        // Duration alone
+        state.ensure_valid_cache(&cx);
+        assert!(state.is_match(&short_kernel));
+        assert!(state.is_match(&short_copy));
+        assert!(!state.is_match(&long_kernel));
+
+        // Duration and a word to search for
+        state.query = "kernel".to_owned();
+        state.ensure_valid_cache(&cx);
+        assert!(state.is_match(&short_kernel));
+        assert!(!state.is_match(&short_copy));
+        assert!(!state.is_match(&long_kernel));
+
+        // Duration and only an excluded word
+        state.query = "-copy".to_owned();
+        state.ensure_valid_cache(&cx);
+        assert!(state.is_match(&short_kernel));
+        assert!(!state.is_match(&short_copy));
+        assert!(!state.is_match(&long_kernel));
+    }
+
+    #[test]
+    fn test_search_range() {
+        assert_eq!(parse_search_range("10..20"), Some((Some(10), Some(20))));
+        assert_eq!(parse_search_range(" -5 .. "), Some((Some(-5), None)));
+        assert_eq!(parse_search_range(".."), Some((None, None)));
+        assert_eq!(parse_search_range("10"), None);
+        assert_eq!(
+            parse_search_range("1MB..2 gib"),
+            Some((Some(1_000_000), Some(2 << 30)))
+        );
+        assert_eq!(parse_search_range("1.5MB.."), None);
+        assert_eq!(parse_search_range("1XB.."), None);
+
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("512B"), Some(512));
+        assert_eq!(parse_size("1KB"), Some(1_000));
+        assert_eq!(parse_size("1kib"), Some(1_024));
+        assert_eq!(parse_size("3 MiB"), Some(3 << 20));
+        assert_eq!(parse_size("2GB"), Some(2_000_000_000));
+        assert_eq!(parse_size("1TiB"), Some(1 << 40));
+        assert_eq!(parse_size("-1KB"), Some(-1_000));
+        assert_eq!(parse_size("KB"), None);
+        assert_eq!(parse_size("10000000TB"), None);
+        assert_eq!(format_search_range(" 1", ""), "1..");
+        assert_eq!(format_search_range("", " "), "");
+
+        let mut schema = FieldSchema::new();
+        let title = schema.insert("Title".to_owned(), true);
+        let size = schema.insert("Size".to_owned(), false);
+        let mut state = SearchState::new(title, BTreeSet::from([size]));
+        state.search_field = size;
+        state.query = "10..20".to_owned();
+        state.ensure_valid_cache(&Context::default());
+        assert!(state.is_number_match(&Field::U64(10)));
+        assert!(state.is_number_match(&Field::I64(20)));
+        assert!(!state.is_number_match(&Field::U64(u64::MAX)));
+        assert!(!state.is_number_match(&Field::String("15".to_owned())));
+
+        state.query = "1MB..1GB".to_owned();
+        state.ensure_valid_cache(&Context::default());
+        assert!(state.is_number_match(&Field::U64(1 << 20)));
+        assert!(!state.is_number_match(&Field::U64(1 << 30)));
+
+        // Switching between text and numeric fields keeps each query
+        state.set_search_field(title);
+        assert_eq!(state.query, "");
+        state.query = "kernel".to_owned();
+        state.set_search_field(size);
+        assert_eq!(state.query, "1MB..1GB");
+        state.set_search_field(size);
+        assert_eq!(state.query, "1MB..1GB");
+        state.set_search_field(title);
+        assert_eq!(state.query, "kernel");
+    }
+
+    #[test]
+    fn test_remember_view() {
+        let view = || (interval(0, 100), IntervalState::default());
+        let mut views = BTreeMap::new();
+        let mut order = VecDeque::new();
+        // Saved by an older version, without an order
+        views.insert("old".to_owned(), view());
+        for i in 0..MAX_SAVED_VIEWS {
+            remember_view(&mut views, &mut order, i.to_string(), view());
+        }
+        assert_eq!(views.len(), MAX_SAVED_VIEWS);
+        assert!(!views.contains_key("old"));
+
+        // Saving again makes a view the most recent
+        remember_view(&mut views, &mut order, "0".to_owned(), view());
+        remember_view(&mut views, &mut order, "new".to_owned(), view());
+        assert_eq!(views.len(), MAX_SAVED_VIEWS);
+        assert!(views.contains_key("0"));
+        assert!(!views.contains_key("1"));
+        assert_eq!(order.len(), MAX_SAVED_VIEWS);
+        assert_eq!(order.back().unwrap(), "new");
+    }
+
+    #[test]
+    fn test_clamp_saved_view() {
+        let history = IntervalState {
+            levels: vec![interval(0, 100), interval(20, 60)],
+            origins: vec![IntervalOrigin::Zoom, IntervalOrigin::Zoom],
+            index: 1,
+        };
+        let saved = (interval(20, 60), history);
+
+        // Unchanged profile
+        let (view, restored) = clamp_saved_view(&saved, interval(0, 100)).unwrap();
+        assert_eq!(view, interval(20, 60));
+        assert_eq!(restored.unwrap().levels, saved.1.levels);
+
+        // Profile got shorter: everything is clamped
+        let (view, restored) = clamp_saved_view(&saved, interval(0, 50)).unwrap();
+        assert_eq!(view, interval(20, 50));
+        assert_eq!(
+            restored.unwrap().levels,
+            [interval(0, 50), interval(20, 50)]
+        );
+
+        // The view survives, but not all of the history
+        let mut partial = saved.clone();
+        partial.1.levels[0] = interval(200, 300);
+        let (view, restored) = clamp_saved_view(&partial, interval(0, 100)).unwrap();
+        assert_eq!(view, interval(20, 60));
+        assert!(restored.is_none());
+
+        assert!(clamp_saved_view(&saved, interval(60, 100)).is_none());
+    }
+
+    #[test]
+    fn test_ruler_ticks() {
+        let ticks = |start, stop, width| -> Vec<i64> {
+            ruler_ticks(TimeAxis::from(interval(start, stop)), width)
+                .into_iter()
+                .map(|t| t.0)
+                .collect()
+        };
+        assert_eq!(
+            ticks(0, 1000, 1000.0),
+            (0..=1000).step_by(100).collect::<Vec<_>>()
+        );
+        assert_eq!(ticks(-30, 70, 200.0), [0, 50]);
+        assert_eq!(ticks(1_234, 9_876, 800.0), [2_000, 4_000, 6_000, 8_000]);
+        for width in [10.0, 500.0, 2000.0] {
+            let n = ticks(17, 123_456_789, width).len();
+            assert!((1..=11).contains(&n), "{n} ticks at width {width}");
+        }
+        assert!(ticks(5, 5, 1000.0).is_empty());
+
+        let log = TimeAxis {
+            view: interval(0, 5_000),
+            log_origin: Some(Timestamp(0)),
+        };
+        let decades: Vec<_> = ruler_ticks(log, 1000.0).into_iter().map(|t| t.0).collect();
+        assert_eq!(decades, [1, 10, 100, 1000]);
+    }
+
+    #[test]
+    fn test_item_details_text() {
+        let mut schema = FieldSchema::new();
+        let count = schema.insert("Count".to_owned(), false);
+        let link = schema.insert("Link".to_owned(), false);
+        let hidden = schema.insert("Hidden".to_owned(), false);
+        let item_meta = ItemMeta {
+            item_uid: ItemUID(7),
+            original_interval: interval(0, 10),
+            title: "Task".to_owned(),
+            fields: vec![
+                (count, Field::U64(3), None),
+                (
+                    link,
+                    Field::ItemLink(ItemLink {
+                        item_uid: ItemUID(8),
+                        title: "Other".to_owned(),
+                        interval: interval(0, 5),
+                        entry_id: EntryID::root().child(0).child(1),
+                        row: None,
+                    }),
+                    None,
+                ),
+                (hidden, Field::Empty, None),
+            ],
+        };
+        let mut cx = Context::default();
+        cx.hidden_fields.insert("Hidden".to_owned());
+        assert_eq!(
+            item_details_text(&item_meta, &schema, &cx),
+            "Title: Task\nCount: 3\nLink: Other (entry 0_1)"
+        );
+        cx.debug = true;
+        assert!(
+            item_details_text(&item_meta, &schema, &cx).starts_with("Title: Task\nItem UID: 7\n")
+        );
+    }
+
+    #[test]
+    fn test_item_stats() {
+        let view = interval(0, 100);
+        let mut a = ItemStats::new(
+            view,
+            vec![interval(90, 120), interval(0, 10), interval(5, 15)],
+        );
+        a.sort();
+        // Busy time is clipped to the view, and overlaps count once
+        assert_eq!(a.busy_ns, 25);
+        assert_eq!(a.durations, [10, 10, 30]);
+        assert_eq!(a.mean_ns(), Some(16));
+        assert_eq!(a.median_ns(), Some(10));
+
+        let b = ItemStats::new(view, vec![interval(50, 52)]);
+        a.merge(b.clone());
+        a.sort();
+        assert_eq!(a.busy_ns, 27);
+        assert_eq!(a.durations, [2, 10, 10, 30]);
+        assert_eq!(a.median_ns(), Some(10));
+        assert_eq!(ItemStats::default().median_ns(), None);
+
+        let mut rows = vec![("a".to_owned(), a), ("b".to_owned(), b)];
+        sort_stats(&mut rows, StatsColumn::Busy, false);
+        assert_eq!(rows[0].0, "b");
+        sort_stats(&mut rows, StatsColumn::Name, false);
+        assert_eq!(rows[0].0, "a");
+        sort_stats(&mut rows, StatsColumn::Count, true);
+        assert_eq!(rows[0].0, "a");
+    }
+
+    #[test]
+    fn test_duration_histogram() {
+        assert_eq!(duration_bucket(0), (0, 1));
+        assert_eq!(duration_bucket(1), (1, 2));
+        assert_eq!(duration_bucket(4), (2, 5));
+        assert_eq!(duration_bucket(5), (5, 10));
+        assert_eq!(duration_bucket(1_500), (1_000, 2_000));
+        assert_eq!(duration_bucket(i64::MAX).1, i64::MAX);
+
+        assert!(duration_histogram(&[]).is_empty());
+        // Empty buckets are filled in between
+        assert_eq!(
+            duration_histogram(&[3, 7, 8, 30]),
+            [((2, 5), 1), ((5, 10), 2), ((10, 20), 0), ((20, 50), 1)]
+        );
+    }
+
+    #[test]
+    fn test_aligned_fields() {
+        let mut schema = FieldSchema::new();
+        let ids: Vec<_> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|name| schema.insert(name.to_owned(), false))
+            .collect();
+        let a = vec![(ids[0], Field::U64(1), None), (ids[1], Field::U64(2), None)];
+        let b = vec![(ids[2], Field::U64(3), None), (ids[0], Field::U64(4), None)];
+        let c = vec![(ids[3], Field::U64(5), None)];
+        let aligned = |items: &[&[(FieldID, Field, Option<Color32>)]], order: &[&str]| {
+            let order: Vec<_> = order.iter().map(|x| x.to_string()).collect();
+            aligned_fields(items, &schema, &order)
+                .into_iter()
+                .map(|(id, row)| {
+                    let present: Vec<_> = row.iter().map(|f| f.is_some()).collect();
+                    (schema.get_name(id).unwrap(), present)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            aligned(&[&a, &b], &[]),
+            [
+                ("a", vec![true, true]),
+                ("b", vec![true, false]),
+                ("c", vec![false, true])
+            ]
+        );
+        assert_eq!(
+            aligned(&[&a, &b], &["c"]),
+            [
+                ("c", vec![false, true]),
+                ("a", vec![true, true]),
+                ("b", vec![true, false])
+            ]
+        );
+        assert_eq!(
+            aligned(&[&a, &b, &c], &[]),
+            [
+                ("a", vec![true, true, false]),
+                ("b", vec![true, false, false]),
+                ("c", vec![false, true, false]),
+                ("d", vec![false, false, true])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_index_items() {
+        let tile = |start| TileID(interval(start, start + 10));
+        let mut index = ItemIndex::new();
+        let rows = vec![vec![ItemUID(1), ItemUID(2)], vec![ItemUID(3)]];
+        index_items(&mut index, tile(0), &rows, |uid| *uid);
+        assert_eq!(index.get(&ItemUID(2)), Some(&(tile(0), 0, 1)));
+        assert_eq!(index.get(&ItemUID(3)), Some(&(tile(0), 1, 0)));
+
+        // Items split across tiles keep their first location
+        let rows = vec![vec![ItemUID(2), ItemUID(4)]];
+        index_items(&mut index, tile(10), &rows, |uid| *uid);
+        assert_eq!(index.get(&ItemUID(2)), Some(&(tile(0), 0, 1)));
+        assert_eq!(index.get(&ItemUID(4)), Some(&(tile(10), 0, 1)));
+        assert_eq!(index.get(&ItemUID(5)), None);
+    }
+
+    #[test]
+    fn test_shade_below_threshold() {
+        let pos = Pos2::new;
+        // Entirely below (larger y) and entirely above
+        assert_eq!(
+            shade_below_threshold(pos(0.0, 8.0), pos(10.0, 6.0), 5.0),
+            Some(vec![
+                pos(0.0, 8.0),
+                pos(10.0, 6.0),
+                pos(10.0, 5.0),
+                pos(0.0, 5.0)
+            ])
+        );
+        assert_eq!(
+            shade_below_threshold(pos(0.0, 2.0), pos(10.0, 4.0), 5.0),
+            None
+        );
+        // Crossing halfway, in either direction
+        assert_eq!(
+            shade_below_threshold(pos(0.0, 7.0), pos(10.0, 3.0), 5.0),
+            Some(vec![pos(0.0, 7.0), pos(5.0, 5.0), pos(0.0, 5.0)])
+        );
+        assert_eq!(
+            shade_below_threshold(pos(0.0, 3.0), pos(10.0, 7.0), 5.0),
+            Some(vec![pos(5.0, 5.0), pos(10.0, 7.0), pos(10.0, 5.0)])
+        );
+        // Vertical steps have no area
+        assert_eq!(
+            shade_below_threshold(pos(5.0, 9.0), pos(5.0, 0.0), 5.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resample_utilization() {
+        let point = |time, util| UtilPoint {
+            time: Timestamp(time),
+            util,
+        };
+        // A step from 0 to 1 halfway through the first bucket
+        let points = [
+            point(0, 0.0),
+            point(50, 0.0),
+            point(50, 1.0),
+            point(200, 1.0),
+        ];
+        let result = resample_utilization(&points, interval(0, 200).into(), 2);
+        assert_eq!(result, [point(50, 0.5), point(150, 1.0)]);
+
+        // Nothing is drawn where there is no data
+        let result = resample_utilization(&points[..2], interval(0, 200).into(), 2);
+        assert_eq!(result, [point(50, 0.0)]);
+    }
+}
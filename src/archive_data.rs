@@ -4,7 +4,9 @@ use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 
-use crate::data::{DataSourceInfo, EntryID, EntryIDSlug, EntryIndex, EntryInfo, TileID, TileSet};
+use crate::data::{
+    DataSourceInfo, EntryID, EntryIDSlug, EntryIndex, EntryInfo, Result, TileID, TileSet,
+};
 use crate::deferred_data::{CountingDeferredDataSource, DeferredDataSource};
 use crate::http::schema::TileRequestRef;
 use crate::timestamp::{Interval, Timestamp};
@@ -55,6 +57,10 @@ where
     Ok(())
 }
 
+fn check_result<T>(result: Result<T>) -> io::Result<T> {
+    result.map_err(io::Error::other)
+}
+
 fn spawn_write<T>(path: PathBuf, data: T, zstd_compression: i32, scope: &rayon::Scope<'_>)
 where
     T: Serialize + Send + Sync + 'static,
@@ -110,7 +116,7 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
         }
     }
 
-    fn check_info(&mut self) -> Option<DataSourceInfo> {
+    fn check_info(&mut self) -> Option<Result<DataSourceInfo>> {
         // We requested this once, so we know we'll get zero or one result
         self.data_source.get_infos().pop()
     }
@@ -120,8 +126,9 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
         spawn_write(path, info, self.zstd_compression, scope);
     }
 
-    fn write_summary_tiles(&mut self, scope: &rayon::Scope<'_>) {
-        for tile in self.data_source.get_summary_tiles() {
+    fn write_summary_tiles(&mut self, scope: &rayon::Scope<'_>) -> io::Result<()> {
+        for (tile, _) in self.data_source.get_summary_tiles() {
+            let tile = check_result(tile)?;
             let mut path = self.path.join("summary_tile");
             let req = TileRequestRef {
                 entry_id: &tile.entry_id,
@@ -130,10 +137,12 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
             path.push(req.to_slug());
             spawn_write(path, tile, self.zstd_compression, scope);
         }
+        Ok(())
     }
 
-    fn write_slot_tiles(&mut self, scope: &rayon::Scope<'_>) {
-        for tile in self.data_source.get_slot_tiles() {
+    fn write_slot_tiles(&mut self, scope: &rayon::Scope<'_>) -> io::Result<()> {
+        for (tile, _) in self.data_source.get_slot_tiles() {
+            let tile = check_result(tile)?;
             let mut path = self.path.join("slot_tile");
             let req = TileRequestRef {
                 entry_id: &tile.entry_id,
@@ -142,10 +151,12 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
             path.push(req.to_slug());
             spawn_write(path, tile, self.zstd_compression, scope);
         }
+        Ok(())
     }
 
-    fn write_slot_meta_tiles(&mut self, scope: &rayon::Scope<'_>) {
-        for tile in self.data_source.get_slot_meta_tiles() {
+    fn write_slot_meta_tiles(&mut self, scope: &rayon::Scope<'_>) -> io::Result<()> {
+        for (tile, _) in self.data_source.get_slot_meta_tiles() {
+            let tile = check_result(tile)?;
             let mut path = self.path.join("slot_meta_tile");
             let req = TileRequestRef {
                 entry_id: &tile.entry_id,
@@ -154,6 +165,7 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
             path.push(req.to_slug());
             spawn_write(path, tile, self.zstd_compression, scope);
         }
+        Ok(())
     }
 
     pub fn write(mut self) -> io::Result<()> {
@@ -168,7 +180,7 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
         while info.is_none() {
             info = self.check_info();
         }
-        let mut info = info.unwrap();
+        let mut info = check_result(info.unwrap())?;
 
         let entry_ids = walk_entry_list(&info.entry_info);
         for entry_id in &entry_ids {
@@ -238,21 +250,23 @@ impl<T: DeferredDataSource> DataSourceArchiveWriter<T> {
                 // Bound the number of in-flight requests so we don't use too much memory.
                 rayon::in_place_scope(|s| {
                     while self.data_source.outstanding_requests() > MAX_IN_FLIGHT_REQUESTS {
-                        self.write_summary_tiles(s);
-                        self.write_slot_tiles(s);
-                        self.write_slot_meta_tiles(s);
+                        self.write_summary_tiles(s)?;
+                        self.write_slot_tiles(s)?;
+                        self.write_slot_meta_tiles(s)?;
                     }
-                });
+                    Ok::<_, io::Error>(())
+                })?;
             }
         }
 
         rayon::in_place_scope(|s| {
             while self.data_source.outstanding_requests() > 0 {
-                self.write_summary_tiles(s);
-                self.write_slot_tiles(s);
-                self.write_slot_meta_tiles(s);
+                self.write_summary_tiles(s)?;
+                self.write_slot_tiles(s)?;
+                self.write_slot_meta_tiles(s)?;
             }
-        });
+            Ok::<_, io::Error>(())
+        })?;
 
         std::fs::write(
             self.path.join("index.html"),
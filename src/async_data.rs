@@ -0,0 +1,215 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use crate::data::{
+    DataSourceDescription, DataSourceInfo, EntryID, Result, SlotMetaTile, SlotTile, SummaryTile,
+    TileID,
+};
+use crate::deferred_data::DeferredDataSource;
+use crate::http::schema::TileRequest;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+// Like DataSource, but for backends that are naturally asynchronous (e.g.,
+// object stores or databases with async drivers), so that they don't have
+// to block a thread per request.
+pub trait AsyncDataSource {
+    fn fetch_description(&self) -> DataSourceDescription;
+    fn fetch_info(&self) -> BoxFuture<'_, Result<DataSourceInfo>>;
+    fn fetch_summary_tile<'a>(
+        &'a self,
+        entry_id: &'a EntryID,
+        tile_id: TileID,
+        full: bool,
+    ) -> BoxFuture<'a, Result<SummaryTile>>;
+    fn fetch_slot_tile<'a>(
+        &'a self,
+        entry_id: &'a EntryID,
+        tile_id: TileID,
+        full: bool,
+    ) -> BoxFuture<'a, Result<SlotTile>>;
+    fn fetch_slot_meta_tile<'a>(
+        &'a self,
+        entry_id: &'a EntryID,
+        tile_id: TileID,
+        full: bool,
+    ) -> BoxFuture<'a, Result<SlotMetaTile>>;
+}
+
+// Hands a task to whatever runtime the embedder uses, e.g.
+// `|task| { tokio::spawn(task); }`
+pub type Spawner = Box<dyn Fn(BoxFuture<'static, ()>) + Send + Sync>;
+
+type TileResults<T> = Arc<Mutex<Vec<(Result<T>, TileRequest)>>>;
+
+pub struct AsyncDeferredDataSource<T: AsyncDataSource + Send + Sync + 'static> {
+    data_source: Arc<T>,
+    spawner: Spawner,
+    infos: Arc<Mutex<Vec<Result<DataSourceInfo>>>>,
+    summary_tiles: TileResults<SummaryTile>,
+    slot_tiles: TileResults<SlotTile>,
+    slot_meta_tiles: TileResults<SlotMetaTile>,
+}
+
+impl<T: AsyncDataSource + Send + Sync + 'static> AsyncDeferredDataSource<T> {
+    pub fn new(data_source: T, spawner: Spawner) -> Self {
+        Self {
+            data_source: Arc::new(data_source),
+            spawner,
+            infos: Arc::new(Mutex::new(Vec::new())),
+            summary_tiles: Arc::new(Mutex::new(Vec::new())),
+            slot_tiles: Arc::new(Mutex::new(Vec::new())),
+            slot_meta_tiles: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        (self.spawner)(Box::pin(task));
+    }
+}
+
+impl<T: AsyncDataSource + Send + Sync + 'static> DeferredDataSource for AsyncDeferredDataSource<T> {
+    fn fetch_description(&self) -> DataSourceDescription {
+        self.data_source.fetch_description()
+    }
+
+    fn fetch_info(&mut self) {
+        let data_source = self.data_source.clone();
+        let infos = self.infos.clone();
+        self.spawn(async move {
+            let result = data_source.fetch_info().await;
+            infos.lock().unwrap().push(result);
+        });
+    }
+
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
+        std::mem::take(&mut self.infos.lock().unwrap())
+    }
+
+    fn fetch_summary_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+        let entry_id = entry_id.clone();
+        let data_source = self.data_source.clone();
+        let summary_tiles = self.summary_tiles.clone();
+        self.spawn(async move {
+            let result = data_source
+                .fetch_summary_tile(&entry_id, tile_id, full)
+                .await;
+            let req = TileRequest { entry_id, tile_id };
+            summary_tiles.lock().unwrap().push((result, req));
+        });
+    }
+
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
+        std::mem::take(&mut self.summary_tiles.lock().unwrap())
+    }
+
+    fn fetch_slot_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+        let entry_id = entry_id.clone();
+        let data_source = self.data_source.clone();
+        let slot_tiles = self.slot_tiles.clone();
+        self.spawn(async move {
+            let result = data_source.fetch_slot_tile(&entry_id, tile_id, full).await;
+            let req = TileRequest { entry_id, tile_id };
+            slot_tiles.lock().unwrap().push((result, req));
+        });
+    }
+
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
+        std::mem::take(&mut self.slot_tiles.lock().unwrap())
+    }
+
+    fn fetch_slot_meta_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+        let entry_id = entry_id.clone();
+        let data_source = self.data_source.clone();
+        let slot_meta_tiles = self.slot_meta_tiles.clone();
+        self.spawn(async move {
+            let result = data_source
+                .fetch_slot_meta_tile(&entry_id, tile_id, full)
+                .await;
+            let req = TileRequest { entry_id, tile_id };
+            slot_meta_tiles.lock().unwrap().push((result, req));
+        });
+    }
+
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
+        std::mem::take(&mut self.slot_meta_tiles.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use super::*;
+    use crate::data::{DataSourceError, EntryInfoBuilder};
+    use crate::timestamp::{Interval, Timestamp};
+
+    struct Ready;
+
+    impl AsyncDataSource for Ready {
+        fn fetch_description(&self) -> DataSourceDescription {
+            DataSourceDescription {
+                source_locator: vec!["ready".to_owned()],
+            }
+        }
+        fn fetch_info(&self) -> BoxFuture<'_, Result<DataSourceInfo>> {
+            Box::pin(async {
+                Ok(DataSourceInfo::new(
+                    EntryInfoBuilder::new().build(),
+                    Interval::new(Timestamp(0), Timestamp(10)),
+                ))
+            })
+        }
+        fn fetch_summary_tile<'a>(
+            &'a self,
+            entry_id: &'a EntryID,
+            tile_id: TileID,
+            full: bool,
+        ) -> BoxFuture<'a, Result<SummaryTile>> {
+            let message = format!("no tile {:?} {:?} {}", entry_id, tile_id, full);
+            Box::pin(async { Err(DataSourceError::NotFound(message)) })
+        }
+        fn fetch_slot_tile<'a>(
+            &'a self,
+            entry_id: &'a EntryID,
+            tile_id: TileID,
+            full: bool,
+        ) -> BoxFuture<'a, Result<SlotTile>> {
+            let message = format!("no tile {:?} {:?} {}", entry_id, tile_id, full);
+            Box::pin(async { Err(DataSourceError::NotFound(message)) })
+        }
+        fn fetch_slot_meta_tile<'a>(
+            &'a self,
+            entry_id: &'a EntryID,
+            tile_id: TileID,
+            full: bool,
+        ) -> BoxFuture<'a, Result<SlotMetaTile>> {
+            let message = format!("no tile {:?} {:?} {}", entry_id, tile_id, full);
+            Box::pin(async { Err(DataSourceError::NotFound(message)) })
+        }
+    }
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn test_fetch_info() {
+        // Futures that are immediately ready only need to be polled once
+        let spawner: Spawner = Box::new(|mut task| {
+            let waker = Waker::from(Arc::new(NoopWake));
+            let mut cx = Context::from_waker(&waker);
+            assert!(matches!(task.as_mut().poll(&mut cx), Poll::Ready(())));
+        });
+        let mut data_source = AsyncDeferredDataSource::new(Ready, spawner);
+        assert!(data_source.get_infos().is_empty());
+        data_source.fetch_info();
+        let infos = data_source.get_infos();
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].as_ref().unwrap().interval.stop, Timestamp(10));
+    }
+}
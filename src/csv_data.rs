@@ -0,0 +1,492 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+
+use crate::data::{
+    pack_rows, Color32, DataSource, DataSourceDescription, DataSourceError, DataSourceInfo,
+    EntryID, EntryInfo, Field, FieldID, FieldSchema, Item, ItemMeta, ItemUID, Result, SlotMetaTile,
+    SlotMetaTileData, SlotTile, SlotTileData, SummaryTile, TileID,
+};
+use crate::timestamp::{Interval, Timestamp};
+
+// Serves a generic (non-Legion) trace stored as CSV. The file must have a
+// header row with (at least) the columns "processor", "start", "stop" and
+// "name". Optional "node" and "kind" columns are used to group processors;
+// all other columns are shown as fields on each item.
+//
+// Times are integers in nanoseconds, or may include a unit (e.g. "1.5 us").
+// Quoted values may contain commas, but not line breaks.
+pub struct CsvDataSource {
+    source_locator: String,
+    info: DataSourceInfo,
+    slots: BTreeMap<EntryID, Vec<Vec<(Item, ItemMeta)>>>, // row -> [item]
+}
+
+// Groups children by name, in order of first appearance
+struct Group<T> {
+    names: Vec<String>,
+    index: BTreeMap<String, usize>,
+    children: Vec<T>,
+}
+
+impl<T> Default for Group<T> {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            index: BTreeMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<T: Default> Group<T> {
+    fn get_or_insert(&mut self, name: &str) -> (usize, &mut T) {
+        let index = *self.index.entry(name.to_owned()).or_insert_with(|| {
+            self.names.push(name.to_owned());
+            self.children.push(T::default());
+            self.children.len() - 1
+        });
+        (index, &mut self.children[index])
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, &String, &T)> {
+        self.names
+            .iter()
+            .zip(self.children.iter())
+            .enumerate()
+            .map(|(i, (name, child))| (i, name, child))
+    }
+}
+
+type Row = (Interval, String, Vec<String>);
+
+fn split_line(line: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                value.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => result.push(std::mem::take(&mut value)),
+            c => value.push(c),
+        }
+    }
+    result.push(value);
+    result
+}
+
+fn parse_time(value: &str) -> Option<Timestamp> {
+    let value = value.trim();
+    value
+        .parse::<i64>()
+        .ok()
+        .map(Timestamp)
+        .or_else(|| Timestamp::parse(value).ok())
+}
+
+fn parse_field(value: &str) -> Field {
+    match value.parse::<i64>() {
+        Ok(value) => Field::I64(value),
+        Err(_) if value.is_empty() => Field::Empty,
+        Err(_) => Field::String(value.to_owned()),
+    }
+}
+
+fn color_for_name(name: &str) -> Color32 {
+    const PALETTE: [Color32; 8] = [
+        Color32::BLUE,
+        Color32::GREEN,
+        Color32::RED,
+        Color32::YELLOW,
+        Color32::KHAKI,
+        Color32::DARK_GREEN,
+        Color32::DARK_BLUE,
+        Color32::GOLD,
+    ];
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    PALETTE[(hasher.finish() % PALETTE.len() as u64) as usize]
+}
+
+fn invalid_data(line: usize, message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {line}: {message}"),
+    )
+}
+
+impl CsvDataSource {
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text, path.to_string_lossy().into())
+    }
+
+    fn parse(text: &str, source_locator: String) -> io::Result<Self> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| invalid_data(1, "missing header row"))?;
+        let header: Vec<_> = split_line(header)
+            .into_iter()
+            .map(|name| name.trim().to_owned())
+            .collect();
+        let find_column = |name: &str| {
+            header
+                .iter()
+                .position(|column| column.eq_ignore_ascii_case(name))
+        };
+        let required_column = |name: &str| {
+            find_column(name).ok_or_else(|| invalid_data(1, format!("missing column {name:?}")))
+        };
+        let processor_column = required_column("processor")?;
+        let start_column = required_column("start")?;
+        let stop_column = required_column("stop")?;
+        let name_column = required_column("name")?;
+        let node_column = find_column("node");
+        let kind_column = find_column("kind");
+
+        let mut field_schema = FieldSchema::new();
+        let interval_field = field_schema.insert("Interval".to_owned(), false);
+        let extra_columns: Vec<(usize, FieldID)> = header
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                ![
+                    Some(processor_column),
+                    Some(start_column),
+                    Some(stop_column),
+                    Some(name_column),
+                    node_column,
+                    kind_column,
+                ]
+                .contains(&Some(*i))
+            })
+            .map(|(i, name)| (i, field_schema.insert(name.clone(), true)))
+            .collect();
+
+        // Extra columns are numeric if every value in them is
+        let mut numeric = vec![true; extra_columns.len()];
+
+        // node -> kind -> processor -> [row]
+        let mut nodes: Group<Group<Group<Vec<Row>>>> = Group::default();
+        let mut interval: Option<Interval> = None;
+        for (line, text) in lines {
+            let values = split_line(text);
+            if values.len() != header.len() {
+                return Err(invalid_data(
+                    line,
+                    format!("expected {} columns, got {}", header.len(), values.len()),
+                ));
+            }
+
+            let parse = |column: usize| {
+                parse_time(&values[column])
+                    .ok_or_else(|| invalid_data(line, format!("invalid time {:?}", values[column])))
+            };
+            let start = parse(start_column)?;
+            let stop = parse(stop_column)?;
+            if stop < start {
+                return Err(invalid_data(line, "stop before start"));
+            }
+            let item_interval = Interval::new(start, stop);
+            interval = Some(interval.map_or(item_interval, |i| i.union(item_interval)));
+
+            let group = |column: Option<usize>| column.map_or("", |c| values[c].trim());
+            let (_, kinds) = nodes.get_or_insert(group(node_column));
+            let (_, processors) = kinds.get_or_insert(group(kind_column));
+            let (_, rows) = processors.get_or_insert(values[processor_column].trim());
+            let extra: Vec<_> = extra_columns
+                .iter()
+                .map(|(i, _)| values[*i].trim().to_owned())
+                .collect();
+            for (is_numeric, value) in numeric.iter_mut().zip(&extra) {
+                *is_numeric &= matches!(parse_field(value), Field::I64(_) | Field::Empty);
+            }
+            rows.push((item_interval, values[name_column].trim().to_owned(), extra));
+        }
+        let interval = interval.ok_or_else(|| invalid_data(1, "no data rows"))?;
+        for ((_, field_id), is_numeric) in extra_columns.iter().zip(numeric) {
+            if is_numeric {
+                field_schema.set_numeric(*field_id);
+            }
+        }
+
+        let mut next_uid = 0;
+        let mut slots = BTreeMap::new();
+        let mut node_slots = Vec::new();
+        for (node_index, node_name, kinds) in nodes.iter() {
+            let node_id = EntryID::root().child(node_index as u64);
+            let mut kind_slots = Vec::new();
+            for (kind_index, kind_name, processors) in kinds.iter() {
+                let kind_id = node_id.child(kind_index as u64);
+                let mut proc_slots = Vec::new();
+                for (proc_index, proc_name, rows) in processors.iter() {
+                    let proc_id = kind_id.child(proc_index as u64);
+
+                    let items = rows
+                        .iter()
+                        .map(|(interval, name, extra)| (*interval, (name, extra)))
+                        .collect();
+                    let packed: Vec<Vec<_>> = pack_rows(items)
+                        .into_iter()
+                        .map(|row| {
+                            row.into_iter()
+                                .map(|(interval, (name, extra))| {
+                                    let item_uid = ItemUID(next_uid);
+                                    next_uid += 1;
+                                    let mut fields =
+                                        vec![(interval_field, Field::Interval(interval), None)];
+                                    for ((_, field_id), value) in extra_columns.iter().zip(extra) {
+                                        fields.push((*field_id, parse_field(value), None));
+                                    }
+                                    let item = Item {
+                                        item_uid,
+                                        interval,
+                                        color: color_for_name(name),
+                                    };
+                                    let meta = ItemMeta {
+                                        item_uid,
+                                        original_interval: interval,
+                                        title: name.clone(),
+                                        fields,
+                                    };
+                                    (item, meta)
+                                })
+                                .collect()
+                        })
+                        .collect();
+
+                    proc_slots.push(EntryInfo::Slot {
+                        short_name: proc_name.clone(),
+                        long_name: proc_name.clone(),
+                        max_rows: packed.len() as u64,
+                    });
+                    slots.insert(proc_id, packed);
+                }
+                kind_slots.push(EntryInfo::Panel {
+                    short_name: kind_name.clone(),
+                    long_name: kind_name.clone(),
+                    summary: None,
+                    slots: proc_slots,
+                });
+            }
+            node_slots.push(EntryInfo::Panel {
+                short_name: node_name.clone(),
+                long_name: node_name.clone(),
+                summary: None,
+                slots: kind_slots,
+            });
+        }
+
+        let entry_info = EntryInfo::Panel {
+            short_name: "root".to_owned(),
+            long_name: "root".to_owned(),
+            summary: None,
+            slots: node_slots,
+        };
+        let info = DataSourceInfo {
+            field_schema,
+            ..DataSourceInfo::new(entry_info, interval)
+        };
+
+        Ok(Self {
+            source_locator,
+            info,
+            slots,
+        })
+    }
+
+    fn find_slot(&self, entry_id: &EntryID) -> Result<&Vec<Vec<(Item, ItemMeta)>>> {
+        self.slots
+            .get(entry_id)
+            .ok_or_else(|| DataSourceError::NotFound(format!("no slot with ID {:?}", entry_id)))
+    }
+}
+
+impl DataSource for CsvDataSource {
+    fn fetch_description(&self) -> DataSourceDescription {
+        DataSourceDescription {
+            source_locator: vec![self.source_locator.clone()],
+        }
+    }
+
+    fn fetch_info(&self) -> Result<DataSourceInfo> {
+        Ok(self.info.clone())
+    }
+
+    fn fetch_summary_tile(
+        &self,
+        entry_id: &EntryID,
+        _tile_id: TileID,
+        _full: bool,
+    ) -> Result<SummaryTile> {
+        Err(DataSourceError::NotFound(format!(
+            "no summary with ID {:?}",
+            entry_id
+        )))
+    }
+
+    fn fetch_slot_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotTile> {
+        let rows = self.find_slot(entry_id)?;
+        let items = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .filter(|(item, _)| tile_id.0.overlaps(item.interval))
+                    .map(|(item, _)| {
+                        // When the item straddles a tile boundary, it has to
+                        // be sliced to fit
+                        let mut item = item.clone();
+                        item.interval = item.interval.intersection(tile_id.0);
+                        item
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(SlotTile {
+            entry_id: entry_id.clone(),
+            tile_id,
+            data: SlotTileData { items },
+        })
+    }
+
+    fn fetch_slot_meta_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotMetaTile> {
+        let rows = self.find_slot(entry_id)?;
+        let items = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .filter(|(item, _)| tile_id.0.overlaps(item.interval))
+                    .map(|(_, meta)| meta.clone())
+                    .collect()
+            })
+            .collect();
+        Ok(SlotMetaTile {
+            entry_id: entry_id.clone(),
+            tile_id,
+            data: SlotMetaTileData { items },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(start: i64, stop: i64) -> Interval {
+        Interval::new(Timestamp(start), Timestamp(stop))
+    }
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(split_line("a,b,,c"), vec!["a", "b", "", "c"]);
+        assert_eq!(
+            split_line(r#""a, b","say ""hi""",c"#),
+            vec!["a, b", r#"say "hi""#, "c"]
+        );
+    }
+
+    #[test]
+    fn test_pack_rows() {
+        let rows = pack_rows(vec![
+            (interval(0, 10), 'a'),
+            (interval(5, 15), 'b'),
+            (interval(10, 20), 'c'),
+            (interval(12, 14), 'd'),
+        ]);
+        let rows: Vec<Vec<_>> = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(|(_, x)| x).collect())
+            .collect();
+        assert_eq!(rows, vec![vec!['a', 'c'], vec!['b'], vec!['d']]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "\
+processor,start,stop,name,node,size
+cpu0,0,100,task A,n0,4
+cpu0,50,150,task B,n0,
+cpu1,1 us,2 us,\"task, C\",n1,hello
+";
+        let source = CsvDataSource::parse(text, "test.csv".to_owned()).unwrap();
+        let info = source.fetch_info().unwrap();
+        assert_eq!(info.interval, interval(0, 2000));
+
+        let EntryInfo::Panel { slots: nodes, .. } = &info.entry_info else {
+            panic!("expected root panel");
+        };
+        assert_eq!(nodes.len(), 2);
+
+        let cpu0 = EntryID::root().child(0).child(0).child(0);
+        let EntryInfo::Slot { max_rows, .. } = info.entry_info.get(&cpu0).unwrap() else {
+            panic!("expected slot");
+        };
+        assert_eq!(*max_rows, 2);
+
+        // The tile only covers task A, which gets sliced to fit
+        let tile = source
+            .fetch_slot_tile(&cpu0, TileID(interval(0, 40)), false)
+            .unwrap();
+        assert_eq!(tile.data.items[0].len(), 1);
+        assert_eq!(tile.data.items[0][0].interval, interval(0, 40));
+        assert!(tile.data.items[1].is_empty());
+
+        let cpu1 = EntryID::root().child(1).child(0).child(0);
+        let meta = source
+            .fetch_slot_meta_tile(&cpu1, TileID(info.interval), false)
+            .unwrap();
+        let item = &meta.data.items[0][0];
+        assert_eq!(item.title, "task, C");
+        assert_eq!(item.original_interval, interval(1000, 2000));
+        let size = info.field_schema.get_id("size").unwrap();
+        assert!(!info.field_schema.numeric().contains(&size));
+        assert!(
+            item.fields
+                .iter()
+                .any(|(id, field, _)| *id == size
+                    && matches!(field, Field::String(s) if s == "hello"))
+        );
+
+        let text = "processor,start,stop,name,bytes\np,0,1,x,10\np,1,2,y,\n";
+        let info = CsvDataSource::parse(text, "".to_owned())
+            .unwrap()
+            .fetch_info()
+            .unwrap();
+        let bytes = info.field_schema.get_id("bytes").unwrap();
+        assert!(info.field_schema.numeric().contains(&bytes));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CsvDataSource::parse("", "".to_owned()).is_err());
+        assert!(CsvDataSource::parse("processor,start,stop\n", "".to_owned()).is_err());
+        assert!(
+            CsvDataSource::parse("processor,start,stop,name\np,10,5,x\n", "".to_owned()).is_err()
+        );
+        assert!(CsvDataSource::parse("processor,start,stop,name\np,1,2\n", "".to_owned()).is_err());
+    }
+}
@@ -10,7 +10,7 @@ use crate::timestamp::{Interval, Timestamp};
 // We encode EntryID as i64 because it allows us to pack Summary into the
 // value -1. Users shouldn't need to know about this and interact through the
 // methods below, or via EntryIndex.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
 pub struct EntryID(Vec<i64>);
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
@@ -26,6 +26,25 @@ pub struct DataSourceInfo {
     pub tile_set: TileSet,
     pub field_schema: FieldSchema,
     pub warning_message: Option<String>,
+    // Wall-clock time (in ns since 1970-01-01 UTC) at which the profile
+    // starts, if known
+    #[serde(default)]
+    pub epoch: Option<Timestamp>,
+}
+
+impl DataSourceInfo {
+    // The given entries over the interval, with no fields and the default
+    // tile set. Set the other fields as needed.
+    pub fn new(entry_info: EntryInfo, interval: Interval) -> Self {
+        Self {
+            entry_info,
+            interval,
+            tile_set: TileSet::default(),
+            field_schema: FieldSchema::new(),
+            warning_message: None,
+            epoch: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -52,6 +71,22 @@ pub struct UtilPoint {
     pub util: f32,
 }
 
+impl UtilPoint {
+    // Utilization at the given time, interpolating linearly between points
+    // (as the utilization plot in the viewer does). Points must be sorted by
+    // time.
+    pub fn interpolate(points: &[UtilPoint], time: Timestamp) -> Option<f32> {
+        let i = points.partition_point(|p| p.time < time);
+        let next = points.get(i)?;
+        if next.time == time || i == 0 {
+            return (next.time == time).then_some(next.util);
+        }
+        let last = &points[i - 1];
+        let ratio = Interval::new(last.time, next.time).unlerp(time);
+        Some(last.util + (next.util - last.util) * ratio)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ItemLink {
     pub item_uid: ItemUID,
@@ -62,6 +97,13 @@ pub struct ItemLink {
     // Required to enable zoom/scroll-to-item
     pub interval: Interval,
     pub entry_id: EntryID,
+
+    // Optional: the row of the linked item within its slot (as in
+    // SlotTileData::items). If provided, scroll-to-item can go directly to the
+    // item instead of waiting for its tiles to load. Data sources that place
+    // items with pack_rows can compute this up front.
+    #[serde(default)]
+    pub row: Option<u64>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
@@ -74,6 +116,10 @@ pub struct FieldSchema {
     field_ids: BTreeMap<String, FieldID>,
     field_names: BTreeMap<FieldID, String>,
     searchable: BTreeSet<FieldID>,
+    // Fields whose values are all Field::I64 or Field::U64 (or empty), which
+    // can be searched by range
+    #[serde(default)]
+    numeric: BTreeSet<FieldID>,
 }
 
 impl FieldSchema {
@@ -82,6 +128,7 @@ impl FieldSchema {
             field_ids: BTreeMap::new(),
             field_names: BTreeMap::new(),
             searchable: BTreeSet::new(),
+            numeric: BTreeSet::new(),
         }
     }
 
@@ -123,6 +170,21 @@ impl FieldSchema {
     pub fn searchable(&self) -> &BTreeSet<FieldID> {
         &self.searchable
     }
+
+    pub fn set_numeric(&mut self, field_id: FieldID) {
+        assert!(self.contains_id(field_id));
+        self.numeric.insert(field_id);
+    }
+
+    pub fn numeric(&self) -> &BTreeSet<FieldID> {
+        &self.numeric
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (FieldID, &str)> {
+        self.field_names
+            .iter()
+            .map(|(field_id, name)| (*field_id, name.as_str()))
+    }
 }
 
 impl Default for FieldSchema {
@@ -171,9 +233,70 @@ pub struct TileSet {
     pub tiles: Vec<Vec<TileID>>,
 }
 
+impl TileSet {
+    // Returns the tiles to request in order to cover the given interval.
+    pub fn covering_tiles(&self, request_interval: Interval) -> Vec<TileID> {
+        self.covering_tiles_at_level(request_interval, None)
+    }
+
+    // Like covering_tiles, but with the level (an index into tiles) chosen by
+    // the caller. Levels past the last are clamped to the finest level.
+    // Dynamic profiles have no levels, so this has no effect on them.
+    pub fn covering_tiles_at_level(
+        &self,
+        request_interval: Interval,
+        level: Option<usize>,
+    ) -> Vec<TileID> {
+        if self.tiles.is_empty() {
+            // For dynamic profiles, just return the request as one tile.
+            return vec![TileID(request_interval)];
+        }
+
+        let chosen_level = if let Some(level) = level {
+            &self.tiles[level.min(self.tiles.len() - 1)]
+        } else {
+            // We're in a static profile. Estimate the best zoom level, where
+            // "best" minimizes the ratio of the tile size to request size.
+            // (Clamped, since empty requests or tiles would divide by zero.)
+            let request_duration = request_interval.duration_ns().max(1);
+            self.tiles
+                .iter()
+                .min_by_key(|level| {
+                    let d = level.first().unwrap().0.duration_ns().max(1);
+                    if d < request_duration {
+                        request_duration / d
+                    } else {
+                        d / request_duration
+                    }
+                })
+                .unwrap()
+        };
+
+        // Now filter to just tiles overlapping the requested interval.
+        chosen_level
+            .iter()
+            .filter(|tile| request_interval.overlaps(tile.0))
+            .copied()
+            .collect()
+    }
+}
+
+// Spread of utilization across the units that were averaged (e.g., the
+// processors of a kind) at a point in time
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default, Deserialize, Serialize)]
+pub struct UtilRange {
+    pub time: Timestamp,
+    pub min: f32,
+    pub max: f32,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SummaryTileData {
     pub utilization: Vec<UtilPoint>,
+    // Optional, drawn as a band behind the average. Older data sources don't
+    // provide this.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub utilization_range: Vec<UtilRange>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -188,6 +311,27 @@ pub struct SlotTileData {
     pub items: Vec<Vec<Item>>, // row -> [item]
 }
 
+// Assigns each item to the first row that is free at the item's start time
+// (returning rows in the order of SlotTileData::items). This is not optimal,
+// but keeps the number of rows reasonable. Data sources can use this to lay
+// out items, and to know the row of an item when creating an ItemLink to it.
+pub fn pack_rows<T>(mut items: Vec<(Interval, T)>) -> Vec<Vec<(Interval, T)>> {
+    items.sort_by_key(|(interval, _)| (interval.start, interval.stop));
+
+    let mut rows: Vec<Vec<(Interval, T)>> = Vec::new();
+    for (interval, item) in items {
+        let row = rows.iter_mut().find(|row| {
+            row.last()
+                .map_or(true, |(last, _)| last.stop <= interval.start)
+        });
+        match row {
+            Some(row) => row.push((interval, item)),
+            None => rows.push(vec![(interval, item)]),
+        }
+    }
+    rows
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SlotTile {
     pub entry_id: EntryID,
@@ -212,13 +356,62 @@ pub struct DataSourceDescription {
     pub source_locator: Vec<String>,
 }
 
+// Errors are kept as messages (rather than the underlying error values) so
+// that results can be cloned and sent between threads freely. The variant
+// says what kind of failure occurred, so callers can decide how to react.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DataSourceError {
+    // The requested profile or tile does not exist
+    NotFound(String),
+    // Reading local data failed
+    Io(String),
+    // A remote request failed (connection error, timeout, server error)
+    Network(String),
+    // The data was retrieved but could not be decompressed or decoded
+    Decode(String),
+    // The data was decoded but is not self-consistent
+    Invalid(String),
+}
+
+impl DataSourceError {
+    // Network failures are often transient, so it may be worth asking again
+    pub fn is_transient(&self) -> bool {
+        matches!(self, DataSourceError::Network(..))
+    }
+}
+
+impl fmt::Display for DataSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSourceError::NotFound(message)
+            | DataSourceError::Io(message)
+            | DataSourceError::Network(message)
+            | DataSourceError::Decode(message)
+            | DataSourceError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DataSourceError {}
+
+pub type Result<T, E = DataSourceError> = std::result::Result<T, E>;
+
 pub trait DataSource {
     fn fetch_description(&self) -> DataSourceDescription;
-    fn fetch_info(&self) -> DataSourceInfo;
-    fn fetch_summary_tile(&self, entry_id: &EntryID, tile_id: TileID, full: bool) -> SummaryTile;
-    fn fetch_slot_tile(&self, entry_id: &EntryID, tile_id: TileID, full: bool) -> SlotTile;
-    fn fetch_slot_meta_tile(&self, entry_id: &EntryID, tile_id: TileID, full: bool)
-        -> SlotMetaTile;
+    fn fetch_info(&self) -> Result<DataSourceInfo>;
+    fn fetch_summary_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        full: bool,
+    ) -> Result<SummaryTile>;
+    fn fetch_slot_tile(&self, entry_id: &EntryID, tile_id: TileID, full: bool) -> Result<SlotTile>;
+    fn fetch_slot_meta_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        full: bool,
+    ) -> Result<SlotMetaTile>;
 }
 
 impl EntryID {
@@ -284,7 +477,11 @@ impl EntryID {
         true
     }
 
+    // Inverse of EntryIDSlug. The root's slug is empty.
     pub fn from_slug(s: &str) -> Result<Self, std::num::ParseIntError> {
+        if s.is_empty() {
+            return Ok(Self::root());
+        }
         let elts: Result<Vec<_>, _> = s.split('_').map(|x| x.parse::<i64>()).collect();
         Ok(Self(elts?))
     }
@@ -307,6 +504,62 @@ impl EntryInfo {
         Some(result)
     }
 
+    // The viewer expects a root panel of nodes, each a panel of kinds, each a
+    // panel of slots. Summaries may only appear on panels.
+    pub fn validate(&self) -> Result<()> {
+        const SLOT_LEVEL: u64 = 3;
+
+        fn check(info: &EntryInfo, entry_id: EntryID) -> Result<()> {
+            let invalid = |message: &str| {
+                Err(DataSourceError::Invalid(format!(
+                    "malformed entry tree at {:?}: {}",
+                    entry_id, message
+                )))
+            };
+            let level = entry_id.level();
+            match info {
+                EntryInfo::Panel { summary, slots, .. } => {
+                    if level >= SLOT_LEVEL {
+                        return invalid("expected a slot, found a panel");
+                    }
+                    if let Some(summary) = summary {
+                        if !matches!(**summary, EntryInfo::Summary { .. }) {
+                            return invalid("summary is not a Summary entry");
+                        }
+                    }
+                    for (i, slot) in slots.iter().enumerate() {
+                        check(slot, entry_id.child(i as u64))?;
+                    }
+                    Ok(())
+                }
+                EntryInfo::Slot { .. } if level != SLOT_LEVEL => {
+                    invalid("expected a panel, found a slot")
+                }
+                EntryInfo::Slot { .. } => Ok(()),
+                EntryInfo::Summary { .. } => invalid("summary found in place of a panel or slot"),
+            }
+        }
+        check(self, EntryID::root())
+    }
+
+    // IDs of all the slots in the tree, in order
+    pub fn slot_ids(&self) -> Vec<EntryID> {
+        fn walk(info: &EntryInfo, entry_id: EntryID, result: &mut Vec<EntryID>) {
+            match info {
+                EntryInfo::Panel { slots, .. } => {
+                    for (i, slot) in slots.iter().enumerate() {
+                        walk(slot, entry_id.child(i as u64), result);
+                    }
+                }
+                EntryInfo::Slot { .. } => result.push(entry_id),
+                EntryInfo::Summary { .. } => {}
+            }
+        }
+        let mut result = Vec::new();
+        walk(self, EntryID::root(), &mut result);
+        result
+    }
+
     pub fn nodes(&self) -> u64 {
         if let EntryInfo::Panel { slots, .. } = self {
             slots.len() as u64
@@ -340,6 +593,114 @@ impl EntryInfo {
     }
 }
 
+// Builds the node -> kind -> slot tree that the viewer expects, keeping
+// track of the EntryID of each entry so that data sources don't have to.
+//
+// Entries are numbered in the order they're added. Passing an EntryID that
+// didn't come from the same builder (or from the wrong method) panics.
+#[derive(Debug, Clone)]
+pub struct EntryInfoBuilder {
+    root: EntryInfo,
+}
+
+impl Default for EntryInfoBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntryInfoBuilder {
+    pub fn new() -> Self {
+        Self {
+            root: EntryInfo::Panel {
+                short_name: "root".to_owned(),
+                long_name: "root".to_owned(),
+                summary: None,
+                slots: Vec::new(),
+            },
+        }
+    }
+
+    fn add_panel(
+        &mut self,
+        parent: &EntryID,
+        short_name: String,
+        long_name: String,
+        summary: Option<Color32>,
+    ) -> EntryID {
+        self.add_entry(
+            parent,
+            EntryInfo::Panel {
+                short_name,
+                long_name,
+                summary: summary.map(|color| Box::new(EntryInfo::Summary { color })),
+                slots: Vec::new(),
+            },
+        )
+    }
+
+    fn add_entry(&mut self, parent: &EntryID, entry: EntryInfo) -> EntryID {
+        let mut panel = &mut self.root;
+        for level in 0..parent.level() {
+            let Some(EntryIndex::Slot(i)) = parent.index(level) else {
+                panic!("parent must be a panel");
+            };
+            let EntryInfo::Panel { slots, .. } = panel else {
+                unreachable!()
+            };
+            panel = slots.get_mut(i as usize).expect("parent does not exist");
+        }
+        let EntryInfo::Panel { slots, .. } = panel else {
+            panic!("parent must be a panel");
+        };
+        slots.push(entry);
+        parent.child(slots.len() as u64 - 1)
+    }
+
+    pub fn add_node(
+        &mut self,
+        short_name: impl Into<String>,
+        long_name: impl Into<String>,
+    ) -> EntryID {
+        self.add_panel(&EntryID::root(), short_name.into(), long_name.into(), None)
+    }
+
+    // Kinds (e.g., CPU, GPU) may have a utilization summary, drawn in the
+    // given color
+    pub fn add_kind(
+        &mut self,
+        node: &EntryID,
+        short_name: impl Into<String>,
+        long_name: impl Into<String>,
+        summary: Option<Color32>,
+    ) -> EntryID {
+        assert_eq!(node.level(), 1, "expected a node");
+        self.add_panel(node, short_name.into(), long_name.into(), summary)
+    }
+
+    pub fn add_slot(
+        &mut self,
+        kind: &EntryID,
+        short_name: impl Into<String>,
+        long_name: impl Into<String>,
+        max_rows: u64,
+    ) -> EntryID {
+        assert_eq!(kind.level(), 2, "expected a kind");
+        self.add_entry(
+            kind,
+            EntryInfo::Slot {
+                short_name: short_name.into(),
+                long_name: long_name.into(),
+                max_rows,
+            },
+        )
+    }
+
+    pub fn build(self) -> EntryInfo {
+        self.root
+    }
+}
+
 #[derive(Debug)]
 pub enum SlugParseError {
     ParseInt(std::num::ParseIntError),
@@ -411,6 +772,14 @@ impl fmt::Display for TileIDSlug {
 
 // Private helpers for EntryID
 impl EntryID {
+    // Moves an entry from under one prefix to another
+    pub(crate) fn replace_prefix(&self, prefix: &EntryID, replacement: &EntryID) -> EntryID {
+        assert!(self.has_prefix(prefix));
+        let mut result = replacement.clone();
+        result.0.extend_from_slice(&self.0[prefix.0.len()..]);
+        result
+    }
+
     pub(crate) fn shift_level0(&self, level0_offset: i64) -> EntryID {
         assert!(!self.0.is_empty());
         assert_ne!(self.0[0], -1);
@@ -420,3 +789,111 @@ impl EntryID {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_covering_tiles_at_level() {
+        let tile = |start, stop| TileID(Interval::new(Timestamp(start), Timestamp(stop)));
+        let tile_set = TileSet {
+            tiles: vec![
+                vec![tile(0, 40)],
+                vec![tile(0, 10), tile(10, 20), tile(20, 30), tile(30, 40)],
+            ],
+        };
+        let request = Interval::new(Timestamp(5), Timestamp(15));
+        assert_eq!(
+            tile_set.covering_tiles(request),
+            [tile(0, 10), tile(10, 20)]
+        );
+        assert_eq!(
+            tile_set.covering_tiles_at_level(request, Some(0)),
+            [tile(0, 40)]
+        );
+        assert_eq!(
+            tile_set.covering_tiles_at_level(request, Some(5)),
+            [tile(0, 10), tile(10, 20)]
+        );
+
+        // Empty requests pick a level without dividing by zero
+        let empty = Interval::new(Timestamp(5), Timestamp(5));
+        assert_eq!(tile_set.covering_tiles(empty), [tile(0, 10)]);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let point = |time, util| UtilPoint {
+            time: Timestamp(time),
+            util,
+        };
+        let points = [point(10, 0.0), point(20, 1.0), point(30, 0.5)];
+        let util_at = |time| UtilPoint::interpolate(&points, Timestamp(time));
+        assert_eq!(util_at(5), None);
+        assert_eq!(util_at(10), Some(0.0));
+        assert_eq!(util_at(15), Some(0.5));
+        assert_eq!(util_at(30), Some(0.5));
+        assert_eq!(util_at(35), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut builder = EntryInfoBuilder::new();
+        let node = builder.add_node("n0", "Node 0");
+        builder.add_kind(&node, "cpu", "Node 0 CPU", None);
+        assert!(builder.build().validate().is_ok());
+
+        // Slot directly under a node
+        let info = EntryInfo::Panel {
+            short_name: "root".to_owned(),
+            long_name: "root".to_owned(),
+            summary: None,
+            slots: vec![EntryInfo::Slot {
+                short_name: "c0".to_owned(),
+                long_name: "CPU 0".to_owned(),
+                max_rows: 1,
+            }],
+        };
+        assert!(matches!(info.validate(), Err(DataSourceError::Invalid(_))));
+
+        // Root must be a panel
+        let info = EntryInfo::Summary {
+            color: Color32::RED,
+        };
+        assert!(matches!(info.validate(), Err(DataSourceError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_data_source_error() {
+        let error = DataSourceError::Network("request failed: timed out".to_owned());
+        assert_eq!(error.to_string(), "request failed: timed out");
+        assert!(error.is_transient());
+        assert!(!DataSourceError::NotFound("missing".to_owned()).is_transient());
+    }
+
+    #[test]
+    fn test_entry_info_builder() {
+        let mut builder = EntryInfoBuilder::new();
+        let n0 = builder.add_node("n0", "Node 0");
+        let n1 = builder.add_node("n1", "Node 1");
+        let cpu = builder.add_kind(&n1, "cpu", "Node 1 CPU", Some(Color32::BLUE));
+        let p0 = builder.add_slot(&cpu, "c0", "Node 1 CPU 0", 3);
+        let p1 = builder.add_slot(&cpu, "c1", "Node 1 CPU 1", 5);
+        let info = builder.build();
+
+        assert_eq!(n0, EntryID::root().child(0));
+        assert_eq!(p1, EntryID::root().child(1).child(0).child(1));
+        assert_eq!(info.nodes(), 2);
+        assert_eq!(info.kinds(), ["cpu"]);
+        assert_eq!(info.slot_ids(), [p0.clone(), p1.clone()]);
+        assert!(matches!(
+            info.get(&p0),
+            Some(EntryInfo::Slot { max_rows: 3, .. })
+        ));
+        assert!(matches!(
+            info.get(&cpu.summary()),
+            Some(EntryInfo::Summary { .. })
+        ));
+    }
+}
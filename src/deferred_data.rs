@@ -1,26 +1,54 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+
 use crate::data::{
-    DataSource, DataSourceDescription, DataSourceInfo, EntryID, SlotMetaTile, SlotTile,
-    SummaryTile, TileID,
+    DataSource, DataSourceDescription, DataSourceInfo, EntryID, ItemMeta, ItemUID, Result,
+    SlotMetaTile, SlotTile, SummaryTile, TileID,
 };
+use crate::http::schema::TileRequest;
+use crate::timestamp::Interval;
 
 pub trait DeferredDataSource {
     fn fetch_description(&self) -> DataSourceDescription;
     fn fetch_info(&mut self);
-    fn get_infos(&mut self) -> Vec<DataSourceInfo>;
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>>;
     fn fetch_summary_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool);
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile>;
+    // Each result is paired with the request that produced it, so that
+    // callers can tell which tile failed
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)>;
     fn fetch_slot_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool);
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile>;
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)>;
     fn fetch_slot_meta_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool);
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile>;
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)>;
+
+    // Batched versions of the fetch methods. Results are returned one tile at
+    // a time, exactly as if each tile had been requested separately. Data
+    // sources where each request is expensive (e.g., over the network) can
+    // override these to fetch many tiles at once.
+    fn fetch_summary_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        for req in requests {
+            self.fetch_summary_tile(&req.entry_id, req.tile_id, full);
+        }
+    }
+    fn fetch_slot_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        for req in requests {
+            self.fetch_slot_tile(&req.entry_id, req.tile_id, full);
+        }
+    }
+    fn fetch_slot_meta_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        for req in requests {
+            self.fetch_slot_meta_tile(&req.entry_id, req.tile_id, full);
+        }
+    }
 }
 
 pub struct DeferredDataSourceWrapper<T: DataSource> {
     data_source: T,
-    infos: Vec<DataSourceInfo>,
-    summary_tiles: Vec<SummaryTile>,
-    slot_tiles: Vec<SlotTile>,
-    slot_meta_tiles: Vec<SlotMetaTile>,
+    infos: Vec<Result<DataSourceInfo>>,
+    summary_tiles: Vec<(Result<SummaryTile>, TileRequest)>,
+    slot_tiles: Vec<(Result<SlotTile>, TileRequest)>,
+    slot_meta_tiles: Vec<(Result<SlotMetaTile>, TileRequest)>,
 }
 
 impl<T: DataSource> DeferredDataSourceWrapper<T> {
@@ -44,65 +72,191 @@ impl<T: DataSource> DeferredDataSource for DeferredDataSourceWrapper<T> {
         self.infos.push(self.data_source.fetch_info());
     }
 
-    fn get_infos(&mut self) -> Vec<DataSourceInfo> {
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
         std::mem::take(&mut self.infos)
     }
 
     fn fetch_summary_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
-        self.summary_tiles
-            .push(self.data_source.fetch_summary_tile(entry_id, tile_id, full));
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        self.summary_tiles.push((
+            self.data_source.fetch_summary_tile(entry_id, tile_id, full),
+            req,
+        ));
     }
 
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile> {
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
         std::mem::take(&mut self.summary_tiles)
     }
 
     fn fetch_slot_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
-        self.slot_tiles
-            .push(self.data_source.fetch_slot_tile(entry_id, tile_id, full));
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        self.slot_tiles.push((
+            self.data_source.fetch_slot_tile(entry_id, tile_id, full),
+            req,
+        ));
     }
 
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile> {
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
         std::mem::take(&mut self.slot_tiles)
     }
 
     fn fetch_slot_meta_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
-        self.slot_meta_tiles.push(
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        self.slot_meta_tiles.push((
             self.data_source
                 .fetch_slot_meta_tile(entry_id, tile_id, full),
-        );
+            req,
+        ));
     }
 
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile> {
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
         std::mem::take(&mut self.slot_meta_tiles)
     }
 }
 
+// Counters for diagnosing slow loads
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    pub total_requests: u64,
+    pub outstanding_requests: u64,
+    pub peak_outstanding_requests: u64,
+}
+
 pub struct CountingDeferredDataSource<T: DeferredDataSource> {
     data_source: T,
-    outstanding_requests: u64,
+    stats: RequestStats,
 }
 
 impl<T: DeferredDataSource> CountingDeferredDataSource<T> {
     pub fn new(data_source: T) -> Self {
         Self {
             data_source,
-            outstanding_requests: 0,
+            stats: RequestStats::default(),
         }
     }
 
     pub fn outstanding_requests(&self) -> u64 {
-        self.outstanding_requests
+        self.stats.outstanding_requests
+    }
+
+    pub fn stats(&self) -> RequestStats {
+        self.stats
+    }
+
+    pub fn data_source(&self) -> &T {
+        &self.data_source
     }
 
     fn start_request(&mut self) {
-        self.outstanding_requests += 1;
+        self.start_requests(1);
+    }
+
+    fn start_requests(&mut self, count: usize) {
+        let stats = &mut self.stats;
+        stats.total_requests += count as u64;
+        stats.outstanding_requests += count as u64;
+        stats.peak_outstanding_requests = stats
+            .peak_outstanding_requests
+            .max(stats.outstanding_requests);
+    }
+
+    // Blocks until the info arrives
+    pub fn fetch_info_blocking(&mut self) -> Result<DataSourceInfo> {
+        self.fetch_info();
+        loop {
+            // We requested this once, so we know we'll get zero or one result
+            if let Some(info) = self.get_infos().pop() {
+                return info;
+            }
+        }
+    }
+
+    // Fetches the slot meta tiles of every slot over the entire profile, and
+    // returns them (in no particular order) as they arrive. Uses the finest
+    // level of detail available, which covers the entire profile. At most
+    // max_in_flight requests are outstanding at once, to bound memory use.
+    pub fn drain_all_slot_meta_tiles(
+        &mut self,
+        info: &DataSourceInfo,
+        full: bool,
+        max_in_flight: u64,
+    ) -> SlotMetaTileDrain<'_, T> {
+        SlotMetaTileDrain {
+            data_source: self,
+            pending: all_slot_meta_tile_requests(info),
+            ready: VecDeque::new(),
+            full,
+            max_in_flight: max_in_flight.max(1),
+        }
+    }
+
+    // Blocks until the slot meta tiles of entry_id covering interval arrive,
+    // and returns the items that overlap the interval, ordered by start time.
+    // Items sliced across tiles are returned once. Any other slot meta tiles
+    // that arrive in the meantime are dropped.
+    pub fn query_items_blocking(
+        &mut self,
+        info: &DataSourceInfo,
+        entry_id: &EntryID,
+        interval: Interval,
+    ) -> Result<Vec<ItemMeta>> {
+        let requests: Vec<_> = info
+            .tile_set
+            .covering_tiles(interval)
+            .into_iter()
+            .map(|tile_id| TileRequest {
+                entry_id: entry_id.clone(),
+                tile_id,
+            })
+            .collect();
+        self.fetch_slot_meta_tiles(&requests, true);
+
+        let mut items: BTreeMap<ItemUID, ItemMeta> = BTreeMap::new();
+        let mut error = None;
+        let mut remaining = requests.len();
+        while remaining > 0 {
+            for (tile, req) in self.get_slot_meta_tiles() {
+                if !requests.contains(&req) {
+                    continue;
+                }
+                remaining -= 1;
+                match tile {
+                    Ok(tile) => {
+                        for item in tile.data.items.into_iter().flatten() {
+                            if item.original_interval.overlaps(interval) {
+                                items.entry(item.item_uid).or_insert(item);
+                            }
+                        }
+                    }
+                    // Keep going, so that no responses are left behind
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+        if let Some(e) = error {
+            return Err(e);
+        }
+
+        let mut items: Vec<_> = items.into_values().collect();
+        items.sort_by_key(|item| (item.original_interval.start, item.item_uid));
+        Ok(items)
     }
 
     fn finish_request<E>(&mut self, result: Vec<E>) -> Vec<E> {
         let count = result.len() as u64;
-        assert!(self.outstanding_requests >= count);
-        self.outstanding_requests -= count;
+        assert!(self.stats.outstanding_requests >= count);
+        self.stats.outstanding_requests -= count;
         result
     }
 }
@@ -117,7 +271,7 @@ impl<T: DeferredDataSource> DeferredDataSource for CountingDeferredDataSource<T>
         self.data_source.fetch_info()
     }
 
-    fn get_infos(&mut self) -> Vec<DataSourceInfo> {
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
         let result = self.data_source.get_infos();
         self.finish_request(result)
     }
@@ -127,7 +281,7 @@ impl<T: DeferredDataSource> DeferredDataSource for CountingDeferredDataSource<T>
         self.data_source.fetch_summary_tile(entry_id, tile_id, full)
     }
 
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile> {
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
         let result = self.data_source.get_summary_tiles();
         self.finish_request(result)
     }
@@ -137,7 +291,7 @@ impl<T: DeferredDataSource> DeferredDataSource for CountingDeferredDataSource<T>
         self.data_source.fetch_slot_tile(entry_id, tile_id, full)
     }
 
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile> {
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
         let result = self.data_source.get_slot_tiles();
         self.finish_request(result)
     }
@@ -148,10 +302,387 @@ impl<T: DeferredDataSource> DeferredDataSource for CountingDeferredDataSource<T>
             .fetch_slot_meta_tile(entry_id, tile_id, full)
     }
 
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile> {
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
         let result = self.data_source.get_slot_meta_tiles();
         self.finish_request(result)
     }
+
+    fn fetch_summary_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        self.start_requests(requests.len());
+        self.data_source.fetch_summary_tiles(requests, full)
+    }
+
+    fn fetch_slot_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        self.start_requests(requests.len());
+        self.data_source.fetch_slot_tiles(requests, full)
+    }
+
+    fn fetch_slot_meta_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        self.start_requests(requests.len());
+        self.data_source.fetch_slot_meta_tiles(requests, full)
+    }
+}
+
+// The requests for the slot meta tiles of every slot over the entire profile,
+// at the finest level of detail available
+pub fn all_slot_meta_tile_requests(info: &DataSourceInfo) -> VecDeque<TileRequest> {
+    // Dynamic data sources can produce the entire profile in one tile
+    let tile_ids = match info.tile_set.tiles.last() {
+        Some(tiles) => tiles.clone(),
+        None => vec![TileID(info.interval)],
+    };
+    info.entry_info
+        .slot_ids()
+        .into_iter()
+        .flat_map(|entry_id| {
+            tile_ids.iter().map(move |tile_id| TileRequest {
+                entry_id: entry_id.clone(),
+                tile_id: *tile_id,
+            })
+        })
+        .collect()
+}
+
+pub struct SlotMetaTileDrain<'a, T: DeferredDataSource> {
+    data_source: &'a mut CountingDeferredDataSource<T>,
+    pending: VecDeque<TileRequest>,
+    ready: VecDeque<Result<SlotMetaTile>>,
+    full: bool,
+    max_in_flight: u64,
+}
+
+impl<'a, T: DeferredDataSource> Iterator for SlotMetaTileDrain<'a, T> {
+    type Item = Result<SlotMetaTile>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(tile) = self.ready.pop_front() {
+                return Some(tile);
+            }
+
+            while self.data_source.outstanding_requests() < self.max_in_flight {
+                let Some(req) = self.pending.pop_front() else {
+                    break;
+                };
+                self.data_source
+                    .fetch_slot_meta_tile(&req.entry_id, req.tile_id, self.full);
+            }
+
+            if self.data_source.outstanding_requests() == 0 {
+                return None;
+            }
+            let tiles = self.data_source.get_slot_meta_tiles();
+            self.ready.extend(tiles.into_iter().map(|(tile, _)| tile));
+        }
+    }
+}
+
+// Routes each tile back to the handle that requested it.
+//
+// A tile comes back with its request, but not whether the request was for
+// the full tile, so the two can't be told apart once sent. Therefore only one
+// kind (full or not) of request per tile is sent at a time, and requests of
+// the other kind are held back until those are answered. Replies to requests
+// of the same kind are interchangeable, so the data source may answer them in
+// any order.
+struct TileDispatch<T> {
+    requests: BTreeMap<TileRequest, PendingTile>,
+    ready: BTreeMap<u64, Vec<(Result<T>, TileRequest)>>,
+}
+
+struct PendingTile {
+    // Whether the requests sent for this tile are for the full tile
+    full: bool,
+    // Handles waiting on the requests sent, oldest first
+    sent: VecDeque<u64>,
+    // Handles waiting to send a request (with its full flag), oldest first
+    held: VecDeque<(u64, bool)>,
+}
+
+impl<T> Default for TileDispatch<T> {
+    fn default() -> Self {
+        Self {
+            requests: BTreeMap::new(),
+            ready: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> TileDispatch<T> {
+    // Returns whether to send the request to the data source now. If not,
+    // dispatch returns it once it can be sent.
+    fn request(&mut self, handle: u64, entry_id: &EntryID, tile_id: TileID, full: bool) -> bool {
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        let pending = self.requests.entry(req).or_insert_with(|| PendingTile {
+            full,
+            sent: VecDeque::new(),
+            held: VecDeque::new(),
+        });
+        if pending.sent.is_empty() || (pending.full == full && pending.held.is_empty()) {
+            pending.full = full;
+            pending.sent.push_back(handle);
+            true
+        } else {
+            pending.held.push_back((handle, full));
+            false
+        }
+    }
+
+    // Returns the held requests that can be sent now, with their full flags
+    fn dispatch(&mut self, tiles: Vec<(Result<T>, TileRequest)>) -> Vec<(TileRequest, bool)> {
+        let mut send = Vec::new();
+        for (tile, req) in tiles {
+            let Some(pending) = self.requests.get_mut(&req) else {
+                continue;
+            };
+            let Some(handle) = pending.sent.pop_front() else {
+                continue;
+            };
+            if pending.sent.is_empty() {
+                // Send the next run of held requests of the same kind
+                if let Some(&(_, full)) = pending.held.front() {
+                    pending.full = full;
+                    while let Some(&(next, _)) = pending.held.front().filter(|(_, f)| *f == full) {
+                        pending.held.pop_front();
+                        pending.sent.push_back(next);
+                        send.push((req.clone(), full));
+                    }
+                } else {
+                    self.requests.remove(&req);
+                }
+            }
+            // The handle may have been dropped since
+            if let Some(ready) = self.ready.get_mut(&handle) {
+                ready.push((tile, req));
+            }
+        }
+        send
+    }
+
+    fn take(&mut self, handle: u64) -> Vec<(Result<T>, TileRequest)> {
+        self.ready
+            .get_mut(&handle)
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
+
+struct SharedState<T: DeferredDataSource> {
+    data_source: T,
+    next_handle: u64,
+    info_requests: VecDeque<u64>,
+    infos: BTreeMap<u64, Vec<Result<DataSourceInfo>>>,
+    summary_tiles: TileDispatch<SummaryTile>,
+    slot_tiles: TileDispatch<SlotTile>,
+    slot_meta_tiles: TileDispatch<SlotMetaTile>,
+}
+
+impl<T: DeferredDataSource> SharedState<T> {
+    fn add_handle(&mut self) -> u64 {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.infos.insert(handle, Vec::new());
+        self.summary_tiles.ready.insert(handle, Vec::new());
+        self.slot_tiles.ready.insert(handle, Vec::new());
+        self.slot_meta_tiles.ready.insert(handle, Vec::new());
+        handle
+    }
+
+    fn remove_handle(&mut self, handle: u64) {
+        self.infos.remove(&handle);
+        self.summary_tiles.ready.remove(&handle);
+        self.slot_tiles.ready.remove(&handle);
+        self.slot_meta_tiles.ready.remove(&handle);
+    }
+}
+
+// Lets several views share one data source. Each handle only sees the
+// results of its own requests.
+pub struct SharedDeferredDataSource<T: DeferredDataSource> {
+    state: Rc<RefCell<SharedState<T>>>,
+    handle: u64,
+}
+
+impl<T: DeferredDataSource> SharedDeferredDataSource<T> {
+    pub fn new(data_source: T) -> Self {
+        let mut state = SharedState {
+            data_source,
+            next_handle: 0,
+            info_requests: VecDeque::new(),
+            infos: BTreeMap::new(),
+            summary_tiles: TileDispatch::default(),
+            slot_tiles: TileDispatch::default(),
+            slot_meta_tiles: TileDispatch::default(),
+        };
+        let handle = state.add_handle();
+        Self {
+            state: Rc::new(RefCell::new(state)),
+            handle,
+        }
+    }
+
+    // Makes a new handle onto the same underlying data source
+    pub fn handle(&self) -> Self {
+        let handle = self.state.borrow_mut().add_handle();
+        Self {
+            state: self.state.clone(),
+            handle,
+        }
+    }
+}
+
+impl<T: DeferredDataSource> Drop for SharedDeferredDataSource<T> {
+    fn drop(&mut self) {
+        self.state.borrow_mut().remove_handle(self.handle);
+    }
+}
+
+impl<T: DeferredDataSource> DeferredDataSource for SharedDeferredDataSource<T> {
+    fn fetch_description(&self) -> DataSourceDescription {
+        self.state.borrow().data_source.fetch_description()
+    }
+
+    fn fetch_info(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.info_requests.push_back(self.handle);
+        state.data_source.fetch_info()
+    }
+
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
+        let mut state = self.state.borrow_mut();
+        // Infos carry no request, so assume they arrive in the order requested
+        for info in state.data_source.get_infos() {
+            let Some(handle) = state.info_requests.pop_front() else {
+                break;
+            };
+            if let Some(infos) = state.infos.get_mut(&handle) {
+                infos.push(info);
+            }
+        }
+        state
+            .infos
+            .get_mut(&self.handle)
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn fetch_summary_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+        let mut state = self.state.borrow_mut();
+        if state
+            .summary_tiles
+            .request(self.handle, entry_id, tile_id, full)
+        {
+            state
+                .data_source
+                .fetch_summary_tile(entry_id, tile_id, full)
+        }
+    }
+
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
+        let state = &mut *self.state.borrow_mut();
+        let tiles = state.data_source.get_summary_tiles();
+        for (req, full) in state.summary_tiles.dispatch(tiles) {
+            state
+                .data_source
+                .fetch_summary_tile(&req.entry_id, req.tile_id, full);
+        }
+        state.summary_tiles.take(self.handle)
+    }
+
+    fn fetch_slot_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+        let mut state = self.state.borrow_mut();
+        if state
+            .slot_tiles
+            .request(self.handle, entry_id, tile_id, full)
+        {
+            state.data_source.fetch_slot_tile(entry_id, tile_id, full)
+        }
+    }
+
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
+        let state = &mut *self.state.borrow_mut();
+        let tiles = state.data_source.get_slot_tiles();
+        for (req, full) in state.slot_tiles.dispatch(tiles) {
+            state
+                .data_source
+                .fetch_slot_tile(&req.entry_id, req.tile_id, full);
+        }
+        state.slot_tiles.take(self.handle)
+    }
+
+    fn fetch_slot_meta_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+        let mut state = self.state.borrow_mut();
+        if state
+            .slot_meta_tiles
+            .request(self.handle, entry_id, tile_id, full)
+        {
+            state
+                .data_source
+                .fetch_slot_meta_tile(entry_id, tile_id, full)
+        }
+    }
+
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
+        let state = &mut *self.state.borrow_mut();
+        let tiles = state.data_source.get_slot_meta_tiles();
+        for (req, full) in state.slot_meta_tiles.dispatch(tiles) {
+            state
+                .data_source
+                .fetch_slot_meta_tile(&req.entry_id, req.tile_id, full);
+        }
+        state.slot_meta_tiles.take(self.handle)
+    }
+
+    fn fetch_summary_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        let state = &mut *self.state.borrow_mut();
+        let requests: Vec<_> = requests
+            .iter()
+            .filter(|req| {
+                state
+                    .summary_tiles
+                    .request(self.handle, &req.entry_id, req.tile_id, full)
+            })
+            .cloned()
+            .collect();
+        if !requests.is_empty() {
+            state.data_source.fetch_summary_tiles(&requests, full)
+        }
+    }
+
+    fn fetch_slot_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        let state = &mut *self.state.borrow_mut();
+        let requests: Vec<_> = requests
+            .iter()
+            .filter(|req| {
+                state
+                    .slot_tiles
+                    .request(self.handle, &req.entry_id, req.tile_id, full)
+            })
+            .cloned()
+            .collect();
+        if !requests.is_empty() {
+            state.data_source.fetch_slot_tiles(&requests, full)
+        }
+    }
+
+    fn fetch_slot_meta_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        let state = &mut *self.state.borrow_mut();
+        let requests: Vec<_> = requests
+            .iter()
+            .filter(|req| {
+                state
+                    .slot_meta_tiles
+                    .request(self.handle, &req.entry_id, req.tile_id, full)
+            })
+            .cloned()
+            .collect();
+        if !requests.is_empty() {
+            state.data_source.fetch_slot_meta_tiles(&requests, full)
+        }
+    }
 }
 
 impl DeferredDataSource for Box<dyn DeferredDataSource> {
@@ -163,7 +694,7 @@ impl DeferredDataSource for Box<dyn DeferredDataSource> {
         self.as_mut().fetch_info()
     }
 
-    fn get_infos(&mut self) -> Vec<DataSourceInfo> {
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
         self.as_mut().get_infos()
     }
 
@@ -171,7 +702,7 @@ impl DeferredDataSource for Box<dyn DeferredDataSource> {
         self.as_mut().fetch_summary_tile(entry_id, tile_id, full)
     }
 
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile> {
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
         self.as_mut().get_summary_tiles()
     }
 
@@ -179,7 +710,7 @@ impl DeferredDataSource for Box<dyn DeferredDataSource> {
         self.as_mut().fetch_slot_tile(entry_id, tile_id, full)
     }
 
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile> {
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
         self.as_mut().get_slot_tiles()
     }
 
@@ -187,7 +718,299 @@ impl DeferredDataSource for Box<dyn DeferredDataSource> {
         self.as_mut().fetch_slot_meta_tile(entry_id, tile_id, full)
     }
 
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile> {
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
         self.as_mut().get_slot_meta_tiles()
     }
+
+    fn fetch_summary_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        self.as_mut().fetch_summary_tiles(requests, full)
+    }
+
+    fn fetch_slot_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        self.as_mut().fetch_slot_tiles(requests, full)
+    }
+
+    fn fetch_slot_meta_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        self.as_mut().fetch_slot_meta_tiles(requests, full)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::data::{DataSourceError, EntryInfoBuilder, SlotMetaTileData, TileSet};
+    use crate::timestamp::{Interval, Timestamp};
+
+    struct Empty(DataSourceInfo);
+
+    impl DataSource for Empty {
+        fn fetch_description(&self) -> DataSourceDescription {
+            DataSourceDescription {
+                source_locator: Vec::new(),
+            }
+        }
+        fn fetch_info(&self) -> Result<DataSourceInfo> {
+            Ok(self.0.clone())
+        }
+        fn fetch_summary_tile(&self, _: &EntryID, _: TileID, _: bool) -> Result<SummaryTile> {
+            Err(DataSourceError::NotFound("no summary tiles".to_owned()))
+        }
+        fn fetch_slot_tile(&self, _: &EntryID, _: TileID, _: bool) -> Result<SlotTile> {
+            Err(DataSourceError::NotFound("no slot tiles".to_owned()))
+        }
+        fn fetch_slot_meta_tile(
+            &self,
+            entry_id: &EntryID,
+            tile_id: TileID,
+            _full: bool,
+        ) -> Result<SlotMetaTile> {
+            Ok(SlotMetaTile {
+                entry_id: entry_id.clone(),
+                tile_id,
+                data: SlotMetaTileData { items: Vec::new() },
+            })
+        }
+    }
+
+    #[test]
+    fn test_drain_all_slot_meta_tiles() {
+        let mut builder = EntryInfoBuilder::new();
+        let node = builder.add_node("n0", "Node 0");
+        let kind = builder.add_kind(&node, "cpu", "Node 0 CPU", None);
+        builder.add_slot(&kind, "c0", "Node 0 CPU 0", 1);
+        builder.add_slot(&kind, "c1", "Node 0 CPU 1", 1);
+
+        let interval = |start, stop| TileID(Interval::new(Timestamp(start), Timestamp(stop)));
+        let info = DataSourceInfo {
+            tile_set: TileSet {
+                tiles: vec![
+                    vec![interval(0, 30)],
+                    vec![interval(0, 10), interval(10, 20), interval(20, 30)],
+                ],
+            },
+            ..DataSourceInfo::new(builder.build(), Interval::new(Timestamp(0), Timestamp(30)))
+        };
+
+        let data_source = DeferredDataSourceWrapper::new(Empty(info));
+        let mut data_source = CountingDeferredDataSource::new(data_source);
+        let info = data_source.fetch_info_blocking().unwrap();
+        let tiles: BTreeSet<_> = data_source
+            .drain_all_slot_meta_tiles(&info, true, 4)
+            .map(|tile| {
+                let tile = tile.unwrap();
+                (tile.entry_id, tile.tile_id)
+            })
+            .collect();
+        // Every slot at the finest level of detail
+        assert_eq!(tiles.len(), 6);
+        assert!(tiles
+            .iter()
+            .all(|(_, tile_id)| tile_id.0.duration_ns() == 10));
+        assert_eq!(data_source.outstanding_requests(), 0);
+        assert!(data_source.stats().peak_outstanding_requests <= 4);
+    }
+
+    // One item spanning the whole profile, plus one per tile
+    struct Sliced(DataSourceInfo);
+
+    impl DataSource for Sliced {
+        fn fetch_description(&self) -> DataSourceDescription {
+            DataSourceDescription {
+                source_locator: Vec::new(),
+            }
+        }
+        fn fetch_info(&self) -> Result<DataSourceInfo> {
+            Ok(self.0.clone())
+        }
+        fn fetch_summary_tile(&self, _: &EntryID, _: TileID, _: bool) -> Result<SummaryTile> {
+            Err(DataSourceError::NotFound("no summary tiles".to_owned()))
+        }
+        fn fetch_slot_tile(&self, _: &EntryID, _: TileID, _: bool) -> Result<SlotTile> {
+            Err(DataSourceError::NotFound("no slot tiles".to_owned()))
+        }
+        fn fetch_slot_meta_tile(
+            &self,
+            entry_id: &EntryID,
+            tile_id: TileID,
+            _full: bool,
+        ) -> Result<SlotMetaTile> {
+            let item = |uid, interval| ItemMeta {
+                item_uid: ItemUID(uid),
+                original_interval: interval,
+                title: format!("item {}", uid),
+                fields: Vec::new(),
+            };
+            let whole = item(1, self.0.interval);
+            let local = item(100 + tile_id.0.start.0 as u64, tile_id.0);
+            Ok(SlotMetaTile {
+                entry_id: entry_id.clone(),
+                tile_id,
+                data: SlotMetaTileData {
+                    items: vec![vec![whole], vec![local]],
+                },
+            })
+        }
+    }
+
+    #[test]
+    fn test_query_items_blocking() {
+        let interval = |start, stop| Interval::new(Timestamp(start), Timestamp(stop));
+        let info = DataSourceInfo {
+            tile_set: TileSet {
+                tiles: vec![
+                    vec![TileID(interval(0, 30))],
+                    vec![
+                        TileID(interval(0, 10)),
+                        TileID(interval(10, 20)),
+                        TileID(interval(20, 30)),
+                    ],
+                ],
+            },
+            ..DataSourceInfo::new(EntryInfoBuilder::new().build(), interval(0, 30))
+        };
+        let data_source = DeferredDataSourceWrapper::new(Sliced(info));
+        let mut data_source = CountingDeferredDataSource::new(data_source);
+        let info = data_source.fetch_info_blocking().unwrap();
+
+        let entry_id = EntryID::root().child(0);
+        let items = data_source
+            .query_items_blocking(&info, &entry_id, interval(5, 15))
+            .unwrap();
+        let uids: Vec<_> = items.iter().map(|item| item.item_uid.0).collect();
+        assert_eq!(uids, vec![1, 100, 110]);
+        assert_eq!(data_source.outstanding_requests(), 0);
+    }
+
+    #[test]
+    fn test_shared_handles() {
+        let info = DataSourceInfo::new(
+            EntryInfoBuilder::new().build(),
+            Interval::new(Timestamp(0), Timestamp(20)),
+        );
+        let mut a = SharedDeferredDataSource::new(DeferredDataSourceWrapper::new(Empty(info)));
+        let mut b = a.handle();
+        let mut c = a.handle();
+
+        let entry_id = EntryID::root().child(0);
+        let first = TileID(Interval::new(Timestamp(0), Timestamp(10)));
+        let second = TileID(Interval::new(Timestamp(10), Timestamp(20)));
+        a.fetch_slot_meta_tile(&entry_id, first, false);
+        b.fetch_slot_meta_tile(&entry_id, first, false);
+        b.fetch_slot_meta_tile(&entry_id, second, false);
+        c.fetch_slot_meta_tile(&entry_id, second, false);
+        b.fetch_info();
+        drop(c);
+
+        // Each handle gets exactly the tiles it asked for
+        let tiles = b.get_slot_meta_tiles();
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[1].1.tile_id, second);
+        let tiles = a.get_slot_meta_tiles();
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].1.tile_id, first);
+        assert!(a.get_slot_meta_tiles().is_empty());
+
+        assert!(a.get_infos().is_empty());
+        assert_eq!(b.get_infos().len(), 1);
+    }
+
+    // Answers the slot meta tile requests in reverse order. Full tiles have
+    // one row, others none.
+    #[derive(Default)]
+    struct Reversed {
+        requests: Vec<(TileRequest, bool)>,
+    }
+
+    impl DeferredDataSource for Reversed {
+        fn fetch_description(&self) -> DataSourceDescription {
+            DataSourceDescription {
+                source_locator: Vec::new(),
+            }
+        }
+        fn fetch_info(&mut self) {}
+        fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
+            Vec::new()
+        }
+        fn fetch_summary_tile(&mut self, _: &EntryID, _: TileID, _: bool) {}
+        fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
+            Vec::new()
+        }
+        fn fetch_slot_tile(&mut self, _: &EntryID, _: TileID, _: bool) {}
+        fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
+            Vec::new()
+        }
+        fn fetch_slot_meta_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
+            let req = TileRequest {
+                entry_id: entry_id.clone(),
+                tile_id,
+            };
+            self.requests.push((req, full));
+        }
+        fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
+            std::mem::take(&mut self.requests)
+                .into_iter()
+                .rev()
+                .map(|(req, full)| {
+                    let tile = SlotMetaTile {
+                        entry_id: req.entry_id.clone(),
+                        tile_id: req.tile_id,
+                        data: SlotMetaTileData {
+                            items: vec![Vec::new(); full as usize],
+                        },
+                    };
+                    (Ok(tile), req)
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_shared_handles_out_of_order() {
+        let mut a = SharedDeferredDataSource::new(Reversed::default());
+        let mut b = a.handle();
+
+        let entry_id = EntryID::root().child(0);
+        let first = TileID(Interval::new(Timestamp(0), Timestamp(10)));
+        let second = TileID(Interval::new(Timestamp(10), Timestamp(20)));
+        a.fetch_slot_meta_tile(&entry_id, first, false);
+        b.fetch_slot_meta_tile(&entry_id, second, false);
+        b.fetch_slot_meta_tile(&entry_id, first, false);
+
+        // Requests for the same tile are interchangeable, so it doesn't
+        // matter which one was answered first
+        let tiles = a.get_slot_meta_tiles();
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].1.tile_id, first);
+        let tiles = b.get_slot_meta_tiles();
+        assert_eq!(tiles.len(), 2);
+        assert!(a.get_slot_meta_tiles().is_empty());
+    }
+
+    #[test]
+    fn test_shared_handles_full_and_partial() {
+        // E.g., a window and an export of the same profile
+        let mut a = SharedDeferredDataSource::new(Reversed::default());
+        let mut b = a.handle();
+        let mut c = a.handle();
+
+        let entry_id = EntryID::root().child(0);
+        let tile_id = TileID(Interval::new(Timestamp(0), Timestamp(10)));
+        a.fetch_slot_meta_tile(&entry_id, tile_id, false);
+        b.fetch_slot_meta_tile(&entry_id, tile_id, true);
+        c.fetch_slot_meta_tile(&entry_id, tile_id, true);
+
+        // The full requests wait for the partial one to be answered
+        let rows = |tiles: Vec<(Result<SlotMetaTile>, TileRequest)>| -> Vec<usize> {
+            tiles
+                .into_iter()
+                .map(|(tile, _)| tile.unwrap().data.items.len())
+                .collect()
+        };
+        assert!(rows(b.get_slot_meta_tiles()).is_empty());
+        assert_eq!(rows(b.get_slot_meta_tiles()), [1]);
+        assert_eq!(rows(c.get_slot_meta_tiles()), [1]);
+        assert_eq!(rows(a.get_slot_meta_tiles()), [0]);
+    }
 }
@@ -0,0 +1,483 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::data::{
+    DataSourceInfo, EntryID, EntryInfo, Field, FieldSchema, ItemLink, ItemUID, SlotMetaTile,
+};
+use crate::deferred_data::{
+    all_slot_meta_tile_requests, CountingDeferredDataSource, DeferredDataSource,
+};
+use crate::http::schema::TileRequest;
+use crate::timestamp::Timestamp;
+
+// Writes a profile in the Chrome Trace Event format, which can be loaded
+// into Perfetto (https://ui.perfetto.dev) or chrome://tracing. Each node
+// becomes a process and each slot (processor, channel, memory) a thread.
+//
+// Either write the whole profile at once with write, or call poll repeatedly
+// (e.g., once per frame) until it's done.
+pub struct ChromeTraceWriter<T: DeferredDataSource> {
+    data_source: CountingDeferredDataSource<T>,
+    path: PathBuf,
+    force: bool,
+    // Names of fields to leave out of each event's args
+    hidden_fields: BTreeSet<String>,
+    info_requested: bool,
+    // Set once the info arrives
+    export: Option<Export>,
+}
+
+// An export in progress, after the file has been created
+struct Export {
+    info: DataSourceInfo,
+    // Taken when the file is finished
+    writer: Option<EventWriter<BufWriter<File>>>,
+    threads: BTreeMap<EntryID, Thread>,
+    pending: VecDeque<TileRequest>,
+    written: BTreeMap<EntryID, BTreeSet<ItemUID>>,
+    tiles_written: u64,
+    tiles_total: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceEvent<'a> {
+    name: &'a str,
+    ph: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+    pid: u64,
+    tid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bp: Option<&'static str>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    args: BTreeMap<&'a str, String>,
+}
+
+impl<'a> TraceEvent<'a> {
+    fn new(name: &'a str, ph: &'static str, pid: u64, tid: u64) -> Self {
+        Self {
+            name,
+            ph,
+            ts: None,
+            dur: None,
+            pid,
+            tid,
+            id: None,
+            bp: None,
+            args: BTreeMap::new(),
+        }
+    }
+}
+
+// Trace event timestamps are in microseconds
+fn to_us(ns: i64) -> f64 {
+    ns as f64 / 1e3
+}
+
+struct Thread {
+    pid: u64,
+    tid: u64,
+}
+
+// Each slot is assigned a unique thread ID, grouped into processes by the
+// first level of the hierarchy (i.e., the node).
+fn walk_entry_list(info: &EntryInfo) -> Vec<(EntryID, String, String)> {
+    let mut result = Vec::new();
+    fn walk(
+        info: &EntryInfo,
+        entry_id: EntryID,
+        process_name: &str,
+        result: &mut Vec<(EntryID, String, String)>,
+    ) {
+        match info {
+            EntryInfo::Panel {
+                slots, long_name, ..
+            } => {
+                let process_name = if entry_id.level() == 1 {
+                    long_name
+                } else {
+                    process_name
+                };
+                for (i, slot) in slots.iter().enumerate() {
+                    walk(slot, entry_id.child(i as u64), process_name, result)
+                }
+            }
+            EntryInfo::Slot { long_name, .. } => {
+                result.push((entry_id, process_name.to_owned(), long_name.clone()));
+            }
+            EntryInfo::Summary { .. } => {}
+        }
+    }
+    walk(info, EntryID::root(), "", &mut result);
+    result
+}
+
+struct EventWriter<W: Write> {
+    writer: W,
+    first: bool,
+    next_flow_id: u64,
+}
+
+impl<W: Write> EventWriter<W> {
+    fn new(mut writer: W) -> io::Result<Self> {
+        write!(writer, "{{\"displayTimeUnit\":\"ns\",\"traceEvents\":[")?;
+        Ok(Self {
+            writer,
+            first: true,
+            next_flow_id: 0,
+        })
+    }
+
+    fn write_event(&mut self, event: &TraceEvent<'_>) -> io::Result<()> {
+        if !self.first {
+            writeln!(self.writer, ",")?;
+        }
+        self.first = false;
+        serde_json::to_writer(&mut self.writer, event)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        writeln!(self.writer, "]}}")?;
+        self.writer.flush()
+    }
+
+    fn write_metadata(&mut self, name: &str, value: &str, pid: u64, tid: u64) -> io::Result<()> {
+        let mut event = TraceEvent::new(name, "M", pid, tid);
+        event.args.insert("name", value.to_owned());
+        self.write_event(&event)
+    }
+
+    fn write_tile(
+        &mut self,
+        tile: &SlotMetaTile,
+        threads: &BTreeMap<EntryID, Thread>,
+        field_schema: &FieldSchema,
+        hidden_fields: &BTreeSet<String>,
+        written: &mut BTreeSet<ItemUID>,
+    ) -> io::Result<()> {
+        let thread = &threads[&tile.entry_id];
+        for row in &tile.data.items {
+            for item in row {
+                // Items that span tiles show up once per tile, but
+                // original_interval always covers the entire item
+                if !written.insert(item.item_uid) {
+                    continue;
+                }
+
+                let interval = item.original_interval;
+                let mut event = TraceEvent::new(&item.title, "X", thread.pid, thread.tid);
+                event.ts = Some(to_us(interval.start.0));
+                event.dur = Some(to_us(interval.duration_ns()));
+                let mut links = Vec::new();
+                for (field_id, field, _) in &item.fields {
+                    if let Some(name) = field_schema.get_name(*field_id) {
+                        if !hidden_fields.contains(name) {
+                            event.args.insert(name, field.to_string());
+                        }
+                    }
+                    match field {
+                        Field::ItemLink(link) => links.push(link),
+                        Field::Vec(fields) => links.extend(fields.iter().filter_map(|f| match f {
+                            Field::ItemLink(link) => Some(link),
+                            _ => None,
+                        })),
+                        _ => {}
+                    }
+                }
+                self.write_event(&event)?;
+
+                for link in links {
+                    self.write_flow(link, interval.start, thread, threads)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Item links become flow events from the start of the linking item to
+    // the start of the linked item
+    fn write_flow(
+        &mut self,
+        link: &ItemLink,
+        start: Timestamp,
+        thread: &Thread,
+        threads: &BTreeMap<EntryID, Thread>,
+    ) -> io::Result<()> {
+        let Some(target) = threads.get(&link.entry_id) else {
+            return Ok(());
+        };
+
+        let id = self.next_flow_id;
+        self.next_flow_id += 1;
+
+        let mut begin = TraceEvent::new(&link.title, "s", thread.pid, thread.tid);
+        begin.ts = Some(to_us(start.0));
+        begin.id = Some(id);
+        self.write_event(&begin)?;
+
+        let mut end = TraceEvent::new(&link.title, "f", target.pid, target.tid);
+        end.ts = Some(to_us(link.interval.start.0));
+        end.id = Some(id);
+        end.bp = Some("e");
+        self.write_event(&end)
+    }
+}
+
+impl<T: DeferredDataSource> ChromeTraceWriter<T> {
+    pub fn new(data_source: T, path: impl AsRef<Path>, force: bool) -> Self {
+        Self {
+            data_source: CountingDeferredDataSource::new(data_source),
+            path: path.as_ref().to_owned(),
+            force,
+            hidden_fields: BTreeSet::new(),
+            info_requested: false,
+            export: None,
+        }
+    }
+
+    // Leaves the named fields out of the exported args (e.g., the ones
+    // hidden in the viewer). Item links are still exported as flows.
+    pub fn with_hidden_fields(mut self, hidden_fields: BTreeSet<String>) -> Self {
+        self.hidden_fields = hidden_fields;
+        self
+    }
+
+    fn create_file(&self) -> io::Result<File> {
+        if self.force {
+            File::create(&self.path)
+        } else {
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&self.path)
+        }
+    }
+
+    // Writes the process and thread names, and lists the tiles to fetch
+    fn start(&self, info: DataSourceInfo) -> io::Result<Export> {
+        let mut writer = EventWriter::new(BufWriter::new(self.create_file()?))?;
+
+        let entries = walk_entry_list(&info.entry_info);
+        let mut threads = BTreeMap::new();
+        for (tid, (entry_id, process_name, thread_name)) in entries.iter().enumerate() {
+            let pid = entry_id.slot_index(0).unwrap_or(0);
+            let tid = tid as u64;
+            if !threads.values().any(|t: &Thread| t.pid == pid) {
+                writer.write_metadata("process_name", process_name, pid, tid)?;
+            }
+            writer.write_metadata("thread_name", thread_name, pid, tid)?;
+            threads.insert(entry_id.clone(), Thread { pid, tid });
+        }
+
+        let pending = all_slot_meta_tile_requests(&info);
+        let tiles_total = pending.len() as u64;
+        Ok(Export {
+            info,
+            writer: Some(writer),
+            threads,
+            pending,
+            written: BTreeMap::new(),
+            tiles_written: 0,
+            tiles_total,
+        })
+    }
+
+    // Writes whatever tiles have arrived, and requests more, without
+    // blocking. Returns true once the file is complete.
+    pub fn poll(&mut self) -> io::Result<bool> {
+        if self.export.is_none() {
+            if !self.info_requested {
+                self.data_source.fetch_info();
+                self.info_requested = true;
+            }
+            // We requested this once, so we know we'll get zero or one result
+            let Some(info) = self.data_source.get_infos().pop() else {
+                return Ok(false);
+            };
+            let info = info.map_err(io::Error::other)?;
+            self.export = Some(self.start(info)?);
+        }
+        let export = self.export.as_mut().unwrap();
+        let Some(writer) = export.writer.as_mut() else {
+            return Ok(true);
+        };
+
+        // Bound the number of in-flight requests so we don't use too much memory.
+        const MAX_IN_FLIGHT_REQUESTS: u64 = 100;
+
+        while self.data_source.outstanding_requests() < MAX_IN_FLIGHT_REQUESTS {
+            let Some(req) = export.pending.pop_front() else {
+                break;
+            };
+            let full = true;
+            self.data_source
+                .fetch_slot_meta_tile(&req.entry_id, req.tile_id, full);
+        }
+
+        for (tile, _) in self.data_source.get_slot_meta_tiles() {
+            let tile = tile.map_err(io::Error::other)?;
+            let written = export.written.entry(tile.entry_id.clone()).or_default();
+            writer.write_tile(
+                &tile,
+                &export.threads,
+                &export.info.field_schema,
+                &self.hidden_fields,
+                written,
+            )?;
+            export.tiles_written += 1;
+        }
+
+        if !export.pending.is_empty() || self.data_source.outstanding_requests() > 0 {
+            return Ok(false);
+        }
+        export.writer.take().unwrap().finish()?;
+        Ok(true)
+    }
+
+    // The number of tiles written so far, out of the total. Both are zero
+    // until the info arrives.
+    pub fn progress(&self) -> (u64, u64) {
+        self.export
+            .as_ref()
+            .map_or((0, 0), |export| (export.tiles_written, export.tiles_total))
+    }
+
+    // Blocks until the entire profile has been written
+    pub fn write(mut self) -> io::Result<()> {
+        while !self.poll()? {}
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{EntryInfoBuilder, ItemMeta, SlotMetaTileData, TileID};
+    use crate::deferred_data::DeferredDataSourceWrapper;
+    use crate::null_data::NullDataSource;
+    use crate::timestamp::Interval;
+
+    #[test]
+    fn test_walk_entry_list() {
+        let mut builder = EntryInfoBuilder::new();
+        let n0 = builder.add_node("n0", "Node 0");
+        let cpu = builder.add_kind(&n0, "cpu", "Node 0 CPU", None);
+        let c0 = builder.add_slot(&cpu, "c0", "CPU 0", 1);
+        let c1 = builder.add_slot(&cpu, "c1", "CPU 1", 1);
+
+        let entries = walk_entry_list(&builder.build());
+        assert_eq!(
+            entries,
+            [
+                (c0, "Node 0".to_owned(), "CPU 0".to_owned()),
+                (c1, "Node 0".to_owned(), "CPU 1".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_poll() {
+        let mut builder = EntryInfoBuilder::new();
+        let n0 = builder.add_node("n0", "Node 0");
+        let cpu = builder.add_kind(&n0, "cpu", "Node 0 CPU", None);
+        builder.add_slot(&cpu, "c0", "CPU 0", 1);
+        builder.add_slot(&cpu, "c1", "CPU 1", 1);
+        let interval = Interval::new(Timestamp(0), Timestamp(100));
+        let data_source = NullDataSource::new(builder.build(), interval);
+
+        let path = std::env::temp_dir().join(format!("prof_viewer_trace_{}", std::process::id()));
+        let mut writer =
+            ChromeTraceWriter::new(DeferredDataSourceWrapper::new(data_source), &path, true);
+        assert_eq!(writer.progress(), (0, 0));
+        let mut polls = 0;
+        while !writer.poll().unwrap() {
+            polls += 1;
+        }
+        // Everything arrives right away from a synchronous data source
+        assert_eq!(polls, 0);
+        assert_eq!(writer.progress(), (2, 2));
+        assert!(writer.poll().unwrap());
+
+        let output = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let events = output["traceEvents"].as_array().unwrap();
+        // One process name and two thread names
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|event| event["ph"] == "M"));
+    }
+
+    #[test]
+    fn test_write_tile() {
+        let interval = |start, stop| Interval::new(Timestamp(start), Timestamp(stop));
+        let c0 = EntryID::root().child(0).child(0).child(0);
+        let c1 = EntryID::root().child(1).child(0).child(0);
+        let mut threads = BTreeMap::new();
+        threads.insert(c0.clone(), Thread { pid: 0, tid: 0 });
+        threads.insert(c1.clone(), Thread { pid: 1, tid: 1 });
+
+        let mut field_schema = FieldSchema::new();
+        let size = field_schema.insert("Size".to_owned(), false);
+        let secret = field_schema.insert("Secret".to_owned(), false);
+        let waits_on = field_schema.insert("Waits On".to_owned(), false);
+        let link = ItemLink {
+            item_uid: ItemUID(2),
+            title: "target".to_owned(),
+            interval: interval(500, 600),
+            entry_id: c1,
+            row: None,
+        };
+        let item = ItemMeta {
+            item_uid: ItemUID(1),
+            original_interval: interval(1000, 3000),
+            title: "task".to_owned(),
+            fields: vec![
+                (size, Field::U64(7), None),
+                (secret, Field::U64(8), None),
+                (waits_on, Field::ItemLink(link), None),
+            ],
+        };
+        // The item spans both tiles, but is only exported once
+        let tile = |start, stop| SlotMetaTile {
+            entry_id: c0.clone(),
+            tile_id: TileID(interval(start, stop)),
+            data: SlotMetaTileData {
+                items: vec![vec![item.clone()]],
+            },
+        };
+        let hidden_fields = ["Secret".to_owned()].into();
+
+        let mut output = Vec::new();
+        let mut writer = EventWriter::new(&mut output).unwrap();
+        let mut written = BTreeSet::new();
+        for tile in [tile(0, 2000), tile(2000, 4000)] {
+            writer
+                .write_tile(&tile, &threads, &field_schema, &hidden_fields, &mut written)
+                .unwrap();
+        }
+        writer.finish().unwrap();
+
+        let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let events = output["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["ph"], "X");
+        assert_eq!(events[0]["ts"], 1.0);
+        assert_eq!(events[0]["dur"], 2.0);
+        assert_eq!(events[0]["args"]["Size"], "7");
+        assert!(events[0]["args"].get("Secret").is_none());
+        assert_eq!(events[1]["ph"], "s");
+        assert_eq!(events[2]["ph"], "f");
+        assert_eq!(events[2]["pid"], 1);
+        assert_eq!(events[2]["ts"], 0.5);
+        assert_eq!(events[1]["id"], events[2]["id"]);
+    }
+}
@@ -0,0 +1 @@
+pub mod chrome_trace;
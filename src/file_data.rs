@@ -1,14 +1,21 @@
+use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
 
+use memmap2::Mmap;
+
 use serde::Deserialize;
 
 use crate::data::{
-    DataSource, DataSourceDescription, DataSourceInfo, EntryID, SlotMetaTile, SlotTile,
-    SummaryTile, TileID,
+    DataSource, DataSourceDescription, DataSourceError, DataSourceInfo, EntryID, Result,
+    SlotMetaTile, SlotTile, SummaryTile, TileID,
 };
 use crate::http::schema::TileRequestRef;
 
+// Every zstd frame starts with these bytes (0xFD2FB528, little endian)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
 pub struct FileDataSource {
     pub basedir: PathBuf,
 }
@@ -20,13 +27,72 @@ impl FileDataSource {
         }
     }
 
-    fn read_file<T>(&self, path: impl AsRef<Path>) -> T
+    // Finds the profiles (i.e., directories with an info file, or archives
+    // of them) in a directory of them, e.g., one per run of a sweep, sorted
+    // by name. If the path is a profile itself, that's the only one.
+    pub fn discover(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        if dir.join("info").is_file() || TarFileDataSource::is_archive(dir) {
+            return Ok(vec![dir.to_owned()]);
+        }
+        let mut result = Vec::new();
+        for entry in dir.read_dir()? {
+            let path = entry?.path();
+            if path.join("info").is_file() || TarFileDataSource::is_archive(&path) {
+                result.push(path);
+            }
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    fn read_file<T>(&self, path: impl AsRef<Path>) -> Result<T>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        let path = path.as_ref();
+        let f = File::open(path).map_err(|e| {
+            let message = format!("opening {:?} failed: {}", path, e);
+            if e.kind() == io::ErrorKind::NotFound {
+                DataSourceError::NotFound(message)
+            } else {
+                DataSourceError::Io(message)
+            }
+        })?;
+
+        // Decoding straight from a memory map avoids a system call every time
+        // the decoder needs more input. If the file can't be mapped (e.g., on
+        // a file system that doesn't support it), read it normally instead.
+        //
+        // Safety: the map is only invalid if the file is truncated while we
+        // read it, which profile files (written once) are not.
+        match unsafe { Mmap::map(&f) } {
+            Ok(map) => Self::decode(&map[..], path),
+            Err(_) => Self::decode(BufReader::new(f), path),
+        }
+    }
+
+    pub(crate) fn decode<T>(mut f: impl BufRead, path: &Path) -> Result<T>
     where
         T: for<'a> Deserialize<'a>,
     {
-        let f = File::open(path).expect("opening file failed");
-        let f = zstd::Decoder::new(f).expect("zstd decompression failed");
-        ciborium::from_reader(f).expect("cbor decoding failed")
+        let io_error = |e| DataSourceError::Io(format!("reading {:?} failed: {}", path, e));
+        let cbor_error = |e| match e {
+            ciborium::de::Error::Io(e) => io_error(e),
+            e => DataSourceError::Decode(format!("cbor decoding failed: {}", e)),
+        };
+
+        // Tiles are normally compressed, but accept uncompressed tiles too
+        // (convenient when producing them by hand)
+        let compressed = f.fill_buf().map_err(io_error)?.starts_with(&ZSTD_MAGIC);
+        if compressed {
+            let f = zstd::Decoder::with_buffer(f).map_err(|e| {
+                DataSourceError::Decode(format!("zstd decompression failed: {}", e))
+            })?;
+            ciborium::from_reader(f).map_err(cbor_error)
+        } else {
+            ciborium::from_reader(f).map_err(cbor_error)
+        }
     }
 }
 
@@ -36,19 +102,29 @@ impl DataSource for FileDataSource {
             source_locator: vec![String::from(self.basedir.to_string_lossy())],
         }
     }
-    fn fetch_info(&self) -> DataSourceInfo {
+    fn fetch_info(&self) -> Result<DataSourceInfo> {
         let path = self.basedir.join("info");
         self.read_file::<DataSourceInfo>(&path)
     }
 
-    fn fetch_summary_tile(&self, entry_id: &EntryID, tile_id: TileID, _full: bool) -> SummaryTile {
+    fn fetch_summary_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SummaryTile> {
         let req = TileRequestRef { entry_id, tile_id };
         let mut path = self.basedir.join("summary_tile");
         path.push(&req.to_slug());
         self.read_file::<SummaryTile>(&path)
     }
 
-    fn fetch_slot_tile(&self, entry_id: &EntryID, tile_id: TileID, _full: bool) -> SlotTile {
+    fn fetch_slot_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotTile> {
         let req = TileRequestRef { entry_id, tile_id };
         let mut path = self.basedir.join("slot_tile");
         path.push(&req.to_slug());
@@ -60,10 +136,375 @@ impl DataSource for FileDataSource {
         entry_id: &EntryID,
         tile_id: TileID,
         _full: bool,
-    ) -> SlotMetaTile {
+    ) -> Result<SlotMetaTile> {
         let req = TileRequestRef { entry_id, tile_id };
         let mut path = self.basedir.join("slot_meta_tile");
         path.push(&req.to_slug());
         self.read_file::<SlotMetaTile>(&path)
     }
 }
+
+enum Contents {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+impl Contents {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Contents::Mapped(map) => &map[..],
+            Contents::Read(data) => data,
+        }
+    }
+}
+
+// Reads a profile directory (as written by DataSourceArchiveWriter) that has
+// been packed into a tar file, e.g., with `tar cf profile.tar legion_prof`.
+// Uncompressed archives are read in place. Archives compressed with zstd
+// (`.tar.zst`) have to be decompressed into memory first, and since the
+// tiles are already compressed, they're barely smaller.
+pub struct TarFileDataSource {
+    path: PathBuf,
+    contents: Contents,
+    // Path (within the profile directory) -> offset and size in contents
+    members: BTreeMap<String, (usize, usize)>,
+}
+
+impl TarFileDataSource {
+    // Whether the path names an archive this can open, by its extension
+    pub fn is_archive(path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map_or("".into(), |name| name.to_string_lossy());
+        path.is_file() && (name.ends_with(".tar") || name.ends_with(".tar.zst"))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let mut f = File::open(&path)?;
+        // Safety: see FileDataSource::read_file
+        let mut contents = match unsafe { Mmap::map(&f) } {
+            Ok(map) => Contents::Mapped(map),
+            Err(_) => {
+                let mut data = Vec::new();
+                f.read_to_end(&mut data)?;
+                Contents::Read(data)
+            }
+        };
+        if contents.bytes().starts_with(&ZSTD_MAGIC) {
+            contents = Contents::Read(zstd::decode_all(contents.bytes())?);
+        }
+        let members = index_tar(contents.bytes())?;
+
+        // The profile directory may be at the top of the archive, or nested
+        // in a directory of its own
+        let base = members
+            .keys()
+            .filter_map(|name| name.strip_suffix("info"))
+            .filter(|base| base.is_empty() || base.ends_with('/'))
+            .min_by_key(|base| base.len())
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "no profile info in archive")
+            })?
+            .to_owned();
+        let members = members
+            .into_iter()
+            .filter_map(|(name, span)| Some((name.strip_prefix(&base)?.to_owned(), span)))
+            .collect();
+
+        Ok(Self {
+            path,
+            contents,
+            members,
+        })
+    }
+
+    fn read_member<T>(&self, name: &str) -> Result<T>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        let Some(&(offset, size)) = self.members.get(name) else {
+            return Err(DataSourceError::NotFound(format!(
+                "{:?} not found in {:?}",
+                name, self.path
+            )));
+        };
+        let data = &self.contents.bytes()[offset..offset + size];
+        FileDataSource::decode(data, &self.path.join(name))
+    }
+
+    fn read_tile<T>(&self, kind: &str, entry_id: &EntryID, tile_id: TileID) -> Result<T>
+    where
+        T: for<'a> Deserialize<'a>,
+    {
+        let req = TileRequestRef { entry_id, tile_id };
+        self.read_member(&format!("{}/{}", kind, req.to_slug()))
+    }
+}
+
+impl DataSource for TarFileDataSource {
+    fn fetch_description(&self) -> DataSourceDescription {
+        DataSourceDescription {
+            source_locator: vec![String::from(self.path.to_string_lossy())],
+        }
+    }
+    fn fetch_info(&self) -> Result<DataSourceInfo> {
+        self.read_member("info")
+    }
+
+    fn fetch_summary_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SummaryTile> {
+        self.read_tile("summary_tile", entry_id, tile_id)
+    }
+
+    fn fetch_slot_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotTile> {
+        self.read_tile("slot_tile", entry_id, tile_id)
+    }
+
+    fn fetch_slot_meta_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotMetaTile> {
+        self.read_tile("slot_meta_tile", entry_id, tile_id)
+    }
+}
+
+const TAR_BLOCK: usize = 512;
+
+// Parses a numeric header field: octal text, or (for large values) base-256
+// with the high bit set, as written by GNU tar
+fn parse_tar_number(field: &[u8]) -> Option<usize> {
+    if field.first().is_some_and(|b| b & 0x80 != 0) {
+        return field[1..]
+            .iter()
+            .try_fold(0usize, |n, b| n.checked_mul(256)?.checked_add(*b as usize));
+    }
+    let text = std::str::from_utf8(field).ok()?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Some(0);
+    }
+    usize::from_str_radix(text, 8).ok()
+}
+
+fn parse_tar_string(field: &[u8]) -> String {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+// Finds the regular files in a tar archive, returning each one's offset and
+// size. Understands ustar prefixes, and the long names of GNU and pax
+// archives.
+fn index_tar(data: &[u8]) -> io::Result<BTreeMap<String, (usize, usize)>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_owned());
+
+    let mut members = BTreeMap::new();
+    let mut long_name = None;
+    let mut offset = 0;
+    while offset + TAR_BLOCK <= data.len() {
+        let header = &data[offset..offset + TAR_BLOCK];
+        // The archive ends with zero blocks
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let size = parse_tar_number(&header[124..136]).ok_or_else(|| invalid("bad size"))?;
+        let start = offset + TAR_BLOCK;
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= data.len())
+            .ok_or_else(|| invalid("truncated archive"))?;
+        let contents = &data[start..end];
+        offset = start + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+
+        let mut name = parse_tar_string(&header[0..100]);
+        if &header[257..262] == b"ustar" {
+            let prefix = parse_tar_string(&header[345..500]);
+            if !prefix.is_empty() {
+                name = format!("{}/{}", prefix, name);
+            }
+        }
+        match header[156] {
+            // Regular file
+            b'0' | 0 => {
+                let name = long_name.take().unwrap_or(name);
+                let name = name.trim_start_matches("./").to_owned();
+                members.insert(name, (start, size));
+            }
+            // GNU long name for the next member
+            b'L' => long_name = Some(parse_tar_string(contents)),
+            // pax extended header for the next member: records of the form
+            // "<length> <key>=<value>\n"
+            b'x' => {
+                let records = String::from_utf8_lossy(contents);
+                for record in records.lines() {
+                    if let Some((_, path)) = record.split_once(" path=") {
+                        long_name = Some(path.to_owned());
+                    }
+                }
+            }
+            _ => long_name = None,
+        }
+    }
+    Ok(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let dir = std::env::temp_dir().join(format!("prof_viewer_discover_{}", std::process::id()));
+        for name in ["run_2", "run_1", "not_a_profile"] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        std::fs::write(dir.join("run_1").join("info"), b"").unwrap();
+        std::fs::write(dir.join("run_2").join("info"), b"").unwrap();
+        std::fs::write(dir.join("stray_file"), b"").unwrap();
+
+        let found = FileDataSource::discover(&dir);
+        let single = FileDataSource::discover(dir.join("run_2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found.unwrap(), [dir.join("run_1"), dir.join("run_2")]);
+        assert_eq!(single.unwrap(), [dir.join("run_2")]);
+        assert!(FileDataSource::discover(dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_read_file_errors() {
+        let dir = std::env::temp_dir().join(format!("prof_viewer_errors_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("garbage"), b"\xff\xff\xff").unwrap();
+
+        let source = FileDataSource::new(&dir);
+        let missing = source.read_file::<u64>(dir.join("missing"));
+        let garbage = source.read_file::<u64>(dir.join("garbage"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(missing, Err(DataSourceError::NotFound(_))));
+        assert!(matches!(garbage, Err(DataSourceError::Decode(_))));
+        assert!(!garbage.unwrap_err().is_transient());
+    }
+
+    fn encode(value: &[u64], compressed: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        ciborium::into_writer(value, &mut data).unwrap();
+        if compressed {
+            data = zstd::encode_all(&data[..], 1).unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn test_read_file_mapped() {
+        let dir = std::env::temp_dir().join(format!("prof_viewer_mapped_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("tile"), encode(&[1, 2, 3], true)).unwrap();
+
+        let source = FileDataSource::new(&dir);
+        let result = source.read_file::<Vec<u64>>(dir.join("tile"));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_decode_uncompressed() {
+        let path = Path::new("tile");
+        for compressed in [true, false] {
+            let data = encode(&[4, 5], compressed);
+            // Both from a slice (as when mapped) and from a reader (the fallback)
+            let mapped = FileDataSource::decode::<Vec<u64>>(&data[..], path);
+            let read = FileDataSource::decode::<Vec<u64>>(BufReader::new(&data[..]), path);
+            assert_eq!(mapped.unwrap(), [4, 5]);
+            assert_eq!(read.unwrap(), [4, 5]);
+        }
+    }
+
+    fn tar_member(name: &str, typeflag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut header = vec![0; TAR_BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        let size = format!("{:011o}", contents.len());
+        header[124..135].copy_from_slice(size.as_bytes());
+        header[156] = typeflag;
+        header[257..262].copy_from_slice(b"ustar");
+
+        let mut result = header;
+        result.extend_from_slice(contents);
+        result.resize(result.len().div_ceil(TAR_BLOCK) * TAR_BLOCK, 0);
+        result
+    }
+
+    #[test]
+    fn test_index_tar() {
+        let long_name = format!("legion_prof/slot_tile/{}", "1".repeat(120));
+        let mut data = Vec::new();
+        data.extend(tar_member("legion_prof/", b'5', b""));
+        data.extend(tar_member("legion_prof/info", b'0', b"info data"));
+        data.extend(tar_member("././@LongLink", b'L', long_name.as_bytes()));
+        data.extend(tar_member("legion_prof/slot_tile/111", b'0', &[7; 600]));
+        data.extend(vec![0; 2 * TAR_BLOCK]);
+
+        let members = index_tar(&data).unwrap();
+        assert_eq!(members.len(), 2);
+        let (offset, size) = members["legion_prof/info"];
+        assert_eq!(&data[offset..offset + size], b"info data");
+        let (offset, size) = members[&long_name];
+        assert_eq!(size, 600);
+        assert!(data[offset..offset + size].iter().all(|b| *b == 7));
+
+        data.truncate(6 * TAR_BLOCK);
+        assert!(index_tar(&data).is_err());
+    }
+
+    #[test]
+    fn test_tar_archives() {
+        let dir = std::env::temp_dir().join(format!("prof_viewer_tar_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut data = Vec::new();
+        data.extend(tar_member("legion_prof/info", b'0', &encode(&[1], true)));
+        data.extend(tar_member(
+            "legion_prof/slot_tile/0_1",
+            b'0',
+            &encode(&[2], false),
+        ));
+        data.extend(vec![0; 2 * TAR_BLOCK]);
+        std::fs::write(dir.join("a.tar"), &data).unwrap();
+        std::fs::write(
+            dir.join("b.tar.zst"),
+            zstd::encode_all(&data[..], 1).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("c.txt"), &data).unwrap();
+
+        let paths = FileDataSource::discover(&dir).unwrap();
+        let sources: Vec<_> = paths
+            .iter()
+            .map(|path| TarFileDataSource::open(path).unwrap())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(paths, [dir.join("a.tar"), dir.join("b.tar.zst")]);
+        for source in sources {
+            assert_eq!(source.read_member::<Vec<u64>>("info").unwrap(), [1]);
+            assert_eq!(
+                source.read_member::<Vec<u64>>("slot_tile/0_1").unwrap(),
+                [2]
+            );
+        }
+    }
+}
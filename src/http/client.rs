@@ -1,47 +1,130 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
 
-use bytes::Buf;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::ThreadPool;
 
 use log::info;
 
 #[cfg(not(target_arch = "wasm32"))]
-use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder};
 #[cfg(target_arch = "wasm32")]
-use reqwest::{Client, ClientBuilder};
+use reqwest::{Client, ClientBuilder, RequestBuilder};
 
 use serde::Deserialize;
 
 use url::Url;
 
 use crate::data::{
-    DataSourceDescription, DataSourceInfo, EntryID, SlotMetaTile, SlotTile, SummaryTile, TileID,
+    DataSourceDescription, DataSourceError, DataSourceInfo, EntryID, Result, SlotMetaTile,
+    SlotTile, SummaryTile, TileID,
 };
 use crate::deferred_data::DeferredDataSource;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::http::fetch::fetch_on;
 use crate::http::fetch::{fetch, DataSourceResponse};
-use crate::http::schema::TileRequestRef;
+use crate::http::schema::{BatchTileRequest, TileRequest, TileRequestRef};
+
+type TileResults<T> = Arc<Mutex<Vec<(Result<T>, TileRequest)>>>;
+type TileRetries = Arc<Mutex<Vec<(TileRequest, bool)>>>;
+
+// Connection settings for the HTTP client. Fields left as None keep reqwest's
+// defaults. Raising the number of idle connections helps when fetching many
+// tiles at once, since otherwise connections are torn down and reopened.
+// (Not available on the web, where the browser manages connections.)
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Default)]
+pub struct HTTPClientConfig {
+    // Maximum number of idle connections kept open to the server
+    pub pool_max_idle_per_host: Option<usize>,
+    // How long an idle connection is kept open before closing it
+    pub pool_idle_timeout: Option<Duration>,
+    // Interval between TCP keep-alive probes on open connections
+    pub tcp_keepalive: Option<Duration>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl HTTPClientConfig {
+    fn build(&self) -> Client {
+        let mut builder = ClientBuilder::new();
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        builder.build().unwrap()
+    }
+}
 
 pub struct HTTPClientDataSource {
     pub baseurl: Url,
     pub client: Client,
-    infos: Arc<Mutex<Vec<DataSourceInfo>>>,
-    summary_tiles: Arc<Mutex<Vec<SummaryTile>>>,
-    slot_tiles: Arc<Mutex<Vec<SlotTile>>>,
-    slot_meta_tiles: Arc<Mutex<Vec<SlotMetaTile>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    thread_pool: Option<Arc<ThreadPool>>,
+    infos: Arc<Mutex<Vec<Result<DataSourceInfo>>>>,
+    summary_tiles: TileResults<SummaryTile>,
+    slot_tiles: TileResults<SlotTile>,
+    slot_meta_tiles: TileResults<SlotMetaTile>,
+    // Set once the server turns out not to have the batch endpoints (e.g.,
+    // an older server), after which tiles are requested one at a time
+    batch_unsupported: Arc<AtomicBool>,
+    // Tiles (and full flag) from batch requests that failed for that reason,
+    // to be requested again one at a time
+    summary_retries: TileRetries,
+    slot_retries: TileRetries,
+    slot_meta_retries: TileRetries,
 }
 
 impl HTTPClientDataSource {
     pub fn new(baseurl: Url) -> Self {
+        Self::with_client(baseurl, ClientBuilder::new().build().unwrap())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_config(baseurl: Url, config: &HTTPClientConfig) -> Self {
+        Self::with_client(baseurl, config.build())
+    }
+
+    fn with_client(baseurl: Url, client: Client) -> Self {
         Self {
             baseurl,
-            client: ClientBuilder::new().build().unwrap(),
+            client,
+            #[cfg(not(target_arch = "wasm32"))]
+            thread_pool: None,
             infos: Arc::new(Mutex::new(Vec::new())),
             summary_tiles: Arc::new(Mutex::new(Vec::new())),
             slot_tiles: Arc::new(Mutex::new(Vec::new())),
             slot_meta_tiles: Arc::new(Mutex::new(Vec::new())),
+            batch_unsupported: Arc::new(AtomicBool::new(false)),
+            summary_retries: Arc::new(Mutex::new(Vec::new())),
+            slot_retries: Arc::new(Mutex::new(Vec::new())),
+            slot_meta_retries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    // Requests (and tile decoding) run on the given thread pool, rather than
+    // the global one
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_thread_pool(baseurl: Url, thread_pool: Arc<ThreadPool>) -> Self {
+        Self {
+            thread_pool: Some(thread_pool),
+            ..Self::new(baseurl)
         }
     }
 
-    fn request<T>(&mut self, url: Url, container: Arc<Mutex<Vec<T>>>)
+    // Uses the given thread pool for requests (see with_thread_pool)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_thread_pool(&mut self, thread_pool: Arc<ThreadPool>) {
+        self.thread_pool = Some(thread_pool);
+    }
+
+    fn request<T>(&mut self, url: Url, on_done: impl 'static + Send + FnOnce(Result<T>))
     where
         T: 'static + Sync + Send + for<'a> Deserialize<'a>,
     {
@@ -51,15 +134,101 @@ impl HTTPClientDataSource {
             .get(url)
             .header("Accept", "*/*")
             .header("Content-Type", "application/octet-stream;");
-        fetch(
-            request,
-            move |response: Result<DataSourceResponse, String>| {
-                let f = response.unwrap().body.reader();
-                let f = zstd::Decoder::new(f).expect("zstd decompression failed");
-                let result = ciborium::from_reader(f).expect("cbor decoding failed");
-                container.lock().unwrap().push(result);
-            },
-        );
+        self.send(request, on_done);
+    }
+
+    // Requests all the tiles in one round trip, and splits the response back
+    // into individual results. If the server doesn't have the endpoint, the
+    // tiles go to retries instead, to be requested one at a time.
+    fn request_batch<T>(
+        &mut self,
+        endpoint: &str,
+        requests: &[TileRequest],
+        full: bool,
+        results: TileResults<T>,
+        retries: TileRetries,
+    ) where
+        T: 'static + Sync + Send + for<'a> Deserialize<'a>,
+    {
+        if requests.is_empty() {
+            return;
+        }
+
+        let url = self.baseurl.join(endpoint).expect("invalid baseurl");
+        info!("fetch: {} ({} tiles)", url, requests.len());
+        let batch = BatchTileRequest {
+            tiles: requests.to_vec(),
+            full,
+        };
+        let mut body = Vec::new();
+        ciborium::into_writer(&batch, &mut body).expect("ciborium encoding failed");
+        let request = self
+            .client
+            .post(url)
+            .header("Accept", "*/*")
+            .header("Content-Type", "application/cbor")
+            .body(body);
+
+        let tiles = batch.tiles;
+        let batch_unsupported = self.batch_unsupported.clone();
+        self.send::<Vec<Result<T>>>(request, move |result| {
+            // Missing tiles are reported individually in the response, so
+            // the batch as a whole is only not found if the endpoint is
+            if let Err(DataSourceError::NotFound(_)) = result {
+                batch_unsupported.store(true, Ordering::Relaxed);
+                let mut retries = retries.lock().unwrap();
+                retries.extend(tiles.into_iter().map(|req| (req, full)));
+                return;
+            }
+
+            let mut results = results.lock().unwrap();
+            match result {
+                Ok(values) if values.len() == tiles.len() => {
+                    results.extend(values.into_iter().zip(tiles));
+                }
+                Ok(values) => {
+                    let e = DataSourceError::Decode(format!(
+                        "expected {} tiles in response, got {}",
+                        tiles.len(),
+                        values.len()
+                    ));
+                    results.extend(tiles.into_iter().map(|req| (Err(e.clone()), req)));
+                }
+                Err(e) => {
+                    results.extend(tiles.into_iter().map(|req| (Err(e.clone()), req)));
+                }
+            }
+        });
+    }
+
+    fn send<T>(&mut self, request: RequestBuilder, on_done: impl 'static + Send + FnOnce(Result<T>))
+    where
+        T: 'static + Sync + Send + for<'a> Deserialize<'a>,
+    {
+        let on_done = move |response: Result<DataSourceResponse>| {
+            let result = response.and_then(|response| {
+                // Decode as the body streams in, rather than buffering it
+                let f = zstd::Decoder::new(response.body).map_err(|e| {
+                    DataSourceError::Decode(format!("zstd decompression failed: {}", e))
+                })?;
+                ciborium::from_reader(f).map_err(|e| match e {
+                    // Read errors come from the underlying connection
+                    ciborium::de::Error::Io(e) => {
+                        DataSourceError::Network(format!("reading response failed: {}", e))
+                    }
+                    e => DataSourceError::Decode(format!("cbor decoding failed: {}", e)),
+                })
+            });
+            on_done(result);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(thread_pool) = &self.thread_pool {
+            fetch_on(thread_pool, request, on_done);
+            return;
+        }
+
+        fetch(request, on_done);
     }
 }
 
@@ -72,10 +241,11 @@ impl DeferredDataSource for HTTPClientDataSource {
 
     fn fetch_info(&mut self) {
         let url = self.baseurl.join("info").expect("invalid baseurl");
-        self.request::<DataSourceInfo>(url, self.infos.clone());
+        let infos = self.infos.clone();
+        self.request::<DataSourceInfo>(url, move |result| infos.lock().unwrap().push(result));
     }
 
-    fn get_infos(&mut self) -> Vec<DataSourceInfo> {
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
         std::mem::take(&mut self.infos.lock().unwrap())
     }
 
@@ -87,10 +257,21 @@ impl DeferredDataSource for HTTPClientDataSource {
             .and_then(|u| u.join(&req.to_slug()))
             .expect("invalid baseurl");
         url.set_query(Some(&format!("full={}", full)));
-        self.request::<SummaryTile>(url, self.summary_tiles.clone());
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        let summary_tiles = self.summary_tiles.clone();
+        self.request::<SummaryTile>(url, move |result| {
+            summary_tiles.lock().unwrap().push((result, req));
+        });
     }
 
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile> {
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
+        let retries = std::mem::take(&mut *self.summary_retries.lock().unwrap());
+        for (req, full) in retries {
+            self.fetch_summary_tile(&req.entry_id, req.tile_id, full);
+        }
         std::mem::take(&mut self.summary_tiles.lock().unwrap())
     }
 
@@ -102,10 +283,21 @@ impl DeferredDataSource for HTTPClientDataSource {
             .and_then(|u| u.join(&req.to_slug()))
             .expect("invalid baseurl");
         url.set_query(Some(&format!("full={}", full)));
-        self.request::<SlotTile>(url, self.slot_tiles.clone());
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        let slot_tiles = self.slot_tiles.clone();
+        self.request::<SlotTile>(url, move |result| {
+            slot_tiles.lock().unwrap().push((result, req));
+        });
     }
 
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile> {
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
+        let retries = std::mem::take(&mut *self.slot_retries.lock().unwrap());
+        for (req, full) in retries {
+            self.fetch_slot_tile(&req.entry_id, req.tile_id, full);
+        }
         std::mem::take(&mut self.slot_tiles.lock().unwrap())
     }
 
@@ -117,10 +309,166 @@ impl DeferredDataSource for HTTPClientDataSource {
             .and_then(|u| u.join(&req.to_slug()))
             .expect("invalid baseurl");
         url.set_query(Some(&format!("full={}", full)));
-        self.request::<SlotMetaTile>(url, self.slot_meta_tiles.clone());
+        let req = TileRequest {
+            entry_id: entry_id.clone(),
+            tile_id,
+        };
+        let slot_meta_tiles = self.slot_meta_tiles.clone();
+        self.request::<SlotMetaTile>(url, move |result| {
+            slot_meta_tiles.lock().unwrap().push((result, req));
+        });
     }
 
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile> {
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
+        let retries = std::mem::take(&mut *self.slot_meta_retries.lock().unwrap());
+        for (req, full) in retries {
+            self.fetch_slot_meta_tile(&req.entry_id, req.tile_id, full);
+        }
         std::mem::take(&mut self.slot_meta_tiles.lock().unwrap())
     }
+
+    fn fetch_summary_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        if self.batch_unsupported.load(Ordering::Relaxed) {
+            for req in requests {
+                self.fetch_summary_tile(&req.entry_id, req.tile_id, full);
+            }
+            return;
+        }
+        let summary_tiles = self.summary_tiles.clone();
+        let retries = self.summary_retries.clone();
+        self.request_batch("summary_tiles", requests, full, summary_tiles, retries);
+    }
+
+    fn fetch_slot_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        if self.batch_unsupported.load(Ordering::Relaxed) {
+            for req in requests {
+                self.fetch_slot_tile(&req.entry_id, req.tile_id, full);
+            }
+            return;
+        }
+        let slot_tiles = self.slot_tiles.clone();
+        let retries = self.slot_retries.clone();
+        self.request_batch("slot_tiles", requests, full, slot_tiles, retries);
+    }
+
+    fn fetch_slot_meta_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        if self.batch_unsupported.load(Ordering::Relaxed) {
+            for req in requests {
+                self.fetch_slot_meta_tile(&req.entry_id, req.tile_id, full);
+            }
+            return;
+        }
+        let slot_meta_tiles = self.slot_meta_tiles.clone();
+        let retries = self.slot_meta_retries.clone();
+        self.request_batch("slot_meta_tiles", requests, full, slot_meta_tiles, retries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::data::SlotTileData;
+    use crate::timestamp::{Interval, Timestamp};
+
+    // Serves slot tiles one at a time, like a server from before the batch
+    // endpoints existed. Returns the URL and the number of POSTs received.
+    fn start_server_without_batch() -> (Url, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let posts = Arc::new(AtomicUsize::new(0));
+        let server_posts = posts.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let (status, body) = if request_line.starts_with("GET /slot_tile/") {
+                    let tile = SlotTile {
+                        entry_id: EntryID::root(),
+                        tile_id: TileID(Interval::new(Timestamp(0), Timestamp(10))),
+                        data: SlotTileData { items: Vec::new() },
+                    };
+                    let mut data = Vec::new();
+                    ciborium::into_writer(&tile, &mut data).unwrap();
+                    ("200 OK", zstd::encode_all(&data[..], 1).unwrap())
+                } else {
+                    if request_line.starts_with("POST ") {
+                        server_posts.fetch_add(1, Ordering::SeqCst);
+                    }
+                    ("404 Not Found", Vec::new())
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        (url, posts)
+    }
+
+    fn wait_for_slot_tiles(
+        data_source: &mut HTTPClientDataSource,
+        count: usize,
+    ) -> Vec<(Result<SlotTile>, TileRequest)> {
+        let start = Instant::now();
+        let mut result = Vec::new();
+        while result.len() < count {
+            assert!(start.elapsed() < Duration::from_secs(30), "timed out");
+            result.extend(data_source.get_slot_tiles());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        result
+    }
+
+    #[test]
+    fn test_batch_fallback() {
+        let (url, posts) = start_server_without_batch();
+        let mut data_source = HTTPClientDataSource::new(url);
+        let requests: Vec<_> = (0..3)
+            .map(|i| TileRequest {
+                entry_id: EntryID::root().child(i),
+                tile_id: TileID(Interval::new(Timestamp(0), Timestamp(10))),
+            })
+            .collect();
+
+        data_source.fetch_slot_tiles(&requests, false);
+        let tiles = wait_for_slot_tiles(&mut data_source, requests.len());
+        assert!(tiles.iter().all(|(tile, _)| tile.is_ok()));
+        let mut received: Vec<_> = tiles.into_iter().map(|(_, req)| req).collect();
+        received.sort_by_key(|req| req.entry_id.clone());
+        assert_eq!(received, requests);
+        assert_eq!(posts.load(Ordering::SeqCst), 1);
+
+        // Once the batch endpoint is known to be missing, it isn't tried again
+        data_source.fetch_slot_tiles(&requests, false);
+        let tiles = wait_for_slot_tiles(&mut data_source, requests.len());
+        assert!(tiles.iter().all(|(tile, _)| tile.is_ok()));
+        assert_eq!(posts.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,4 +1,6 @@
-use bytes::Bytes;
+use std::io::Read;
+
+use crate::data::{DataSourceError, Result};
 
 #[cfg(not(target_arch = "wasm32"))]
 use reqwest::blocking::RequestBuilder;
@@ -6,16 +8,42 @@ use reqwest::blocking::RequestBuilder;
 use reqwest::RequestBuilder;
 
 pub struct DataSourceResponse {
-    pub body: Bytes,
+    // On native platforms this reads directly from the network, so that
+    // large tiles can be decoded without first buffering the whole response
+    pub body: Box<dyn Read + Send>,
 }
 
 pub fn fetch(
     request: RequestBuilder,
-    on_done: impl 'static + Send + FnOnce(Result<DataSourceResponse, String>),
+    on_done: impl 'static + Send + FnOnce(Result<DataSourceResponse>),
 ) {
     #[cfg(not(target_arch = "wasm32"))]
-    crate::http::fetch_native::fetch(request, Box::new(on_done));
+    crate::http::fetch_native::fetch(request, None, Box::new(on_done));
 
     #[cfg(target_arch = "wasm32")]
     crate::http::fetch_web::fetch(request, Box::new(on_done));
 }
+
+// Like fetch, but runs the request (and any processing in on_done) on the
+// given thread pool rather than the global one
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fetch_on(
+    thread_pool: &rayon::ThreadPool,
+    request: RequestBuilder,
+    on_done: impl 'static + Send + FnOnce(Result<DataSourceResponse>),
+) {
+    crate::http::fetch_native::fetch(request, Some(thread_pool), Box::new(on_done));
+}
+
+pub(crate) fn request_error(e: reqwest::Error) -> DataSourceError {
+    let message = format!("request failed: {}", e);
+    // Servers (or proxies) that don't have an endpoint may also answer that
+    // the method isn't allowed
+    if e.status() == Some(reqwest::StatusCode::NOT_FOUND)
+        || e.status() == Some(reqwest::StatusCode::METHOD_NOT_ALLOWED)
+    {
+        DataSourceError::NotFound(message)
+    } else {
+        DataSourceError::Network(message)
+    }
+}
@@ -1,18 +1,27 @@
+use rayon::ThreadPool;
 use reqwest::blocking::RequestBuilder;
 
-use crate::http::fetch::DataSourceResponse;
+use crate::data::Result;
+use crate::http::fetch::{request_error, DataSourceResponse};
 
 pub fn fetch(
     request: RequestBuilder,
-    on_done: Box<dyn FnOnce(Result<DataSourceResponse, String>) + Send>,
+    thread_pool: Option<&ThreadPool>,
+    on_done: Box<dyn FnOnce(Result<DataSourceResponse>) + Send>,
 ) {
-    rayon::spawn(move || {
+    let task = move || {
         let result = request
             .send()
-            .expect("request failed")
-            .bytes()
-            .expect("unable to get bytes");
+            .and_then(|response| response.error_for_status())
+            .map(|response| DataSourceResponse {
+                body: Box::new(response),
+            })
+            .map_err(request_error);
 
-        on_done(Ok(DataSourceResponse { body: result }))
-    });
+        on_done(result)
+    };
+    match thread_pool {
+        Some(thread_pool) => thread_pool.spawn(task),
+        None => rayon::spawn(task),
+    }
 }
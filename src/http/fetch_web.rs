@@ -1,6 +1,9 @@
+use bytes::Buf;
+
 use reqwest::RequestBuilder;
 
-use crate::http::fetch::DataSourceResponse;
+use crate::data::Result;
+use crate::http::fetch::{request_error, DataSourceResponse};
 
 /// Spawn an async task.
 ///
@@ -13,20 +16,21 @@ where
     wasm_bindgen_futures::spawn_local(future);
 }
 
-pub fn fetch(
-    request: RequestBuilder,
-    on_done: Box<dyn FnOnce(Result<DataSourceResponse, String>) + Send>,
-) {
+pub fn fetch(request: RequestBuilder, on_done: Box<dyn FnOnce(Result<DataSourceResponse>) + Send>) {
     spawn_future(async move {
-        let result = request
-            .send()
-            .await
-            .expect("request failed")
-            .bytes()
-            .await
-            .expect("unable to get bytes");
+        let result = match request.send().await {
+            Ok(response) => match response.error_for_status() {
+                Ok(response) => response.bytes().await,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
 
-        let res = Ok(DataSourceResponse { body: result });
+        let res = result
+            .map(|body| DataSourceResponse {
+                body: Box::new(body.reader()),
+            })
+            .map_err(request_error);
 
         on_done(res)
     });
@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::data::{EntryID, EntryIDSlug, SlugParseError, TileID, TileIDSlug};
 
@@ -8,7 +8,7 @@ pub struct TileRequestPath {
     pub tile_id: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
 pub struct TileRequest {
     pub entry_id: EntryID,
     pub tile_id: TileID,
@@ -25,6 +25,14 @@ pub struct TileQuery {
     pub full: bool,
 }
 
+// Body of a request for many tiles at once. The response contains one result
+// per tile, in the same order as the requests.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BatchTileRequest {
+    pub tiles: Vec<TileRequest>,
+    pub full: bool,
+}
+
 impl TileRequestPath {
     pub fn parse(&self) -> Result<TileRequest, SlugParseError> {
         Ok(TileRequest {
@@ -34,7 +42,26 @@ impl TileRequestPath {
     }
 }
 
+impl TileRequest {
+    // Inverse of TileRequestRef::to_slug
+    pub fn from_slug(s: &str) -> Result<Self, SlugParseError> {
+        let Some((entry_id, tile_id)) = s.split_once('/') else {
+            return Err(SlugParseError::TooFewValues);
+        };
+        TileRequestPath {
+            entry_id: entry_id.to_owned(),
+            tile_id: tile_id.to_owned(),
+        }
+        .parse()
+    }
+}
+
 impl<'a> TileRequestRef<'a> {
+    // Names the tile in file paths (see FileDataSource) and URLs (see
+    // HTTPClientDataSource), as "<entry>/<start>_<stop>" where the entry is
+    // its indices joined by '_' (e.g., "0_2_-1" for a summary). Only digits,
+    // '-', '_' and the one '/' are used, so it needs no escaping in either.
+    // Changing this breaks existing profiles on disk.
     pub fn to_slug(&self) -> String {
         format!(
             "{}/{}",
@@ -43,3 +70,70 @@ impl<'a> TileRequestRef<'a> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::timestamp::{Interval, Timestamp};
+
+    fn random_timestamp(rng: &mut StdRng) -> Timestamp {
+        Timestamp(match rng.gen_range(0..4) {
+            0 => rng.gen(),
+            1 => rng.gen_range(-1000..1000),
+            2 => i64::MIN,
+            _ => i64::MAX,
+        })
+    }
+
+    #[test]
+    fn test_slug_round_trip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let mut entry_id = EntryID::root();
+            for _ in 0..rng.gen_range(1..8) {
+                let index = match rng.gen_range(0..3) {
+                    0 => rng.gen_range(0..10),
+                    1 => rng.gen_range(0..i64::MAX as u64),
+                    _ => i64::MAX as u64,
+                };
+                entry_id = entry_id.child(index);
+            }
+            if rng.gen() {
+                entry_id = entry_id.summary();
+            }
+            let tile_id = TileID(Interval::new(
+                random_timestamp(&mut rng),
+                random_timestamp(&mut rng),
+            ));
+
+            let slug = TileRequestRef {
+                entry_id: &entry_id,
+                tile_id,
+            }
+            .to_slug();
+            assert!(
+                slug.chars()
+                    .all(|c| c.is_ascii_digit() || c == '-' || c == '_' || c == '/'),
+                "{slug}"
+            );
+            assert_eq!(slug.matches('/').count(), 1, "{slug}");
+
+            let req = TileRequest::from_slug(&slug).unwrap();
+            assert_eq!(req.entry_id, entry_id);
+            assert_eq!(req.tile_id, tile_id);
+        }
+    }
+
+    #[test]
+    fn test_slug_errors() {
+        assert_eq!(EntryID::from_slug(""), Ok(EntryID::root()));
+        assert!(TileRequest::from_slug("0_1").is_err());
+        assert!(TileRequest::from_slug("0_1/5").is_err());
+        assert!(TileRequest::from_slug("0_1/5_6_7").is_err());
+        assert!(TileRequest::from_slug("0_x/5_6").is_err());
+        assert!(TileRequest::from_slug("0_1/5_6/7").is_err());
+    }
+}
@@ -2,15 +2,15 @@ use std::sync::Arc;
 
 use actix_cors::Cors;
 use actix_web::{
-    error, get, http, middleware,
+    error, get, http, middleware, post,
     web::{self, Data},
     App, HttpServer, Responder, Result,
 };
 
 use serde::Serialize;
 
-use crate::data::DataSource;
-use crate::http::schema::{TileQuery, TileRequestPath};
+use crate::data::{DataSource, DataSourceError};
+use crate::http::schema::{BatchTileRequest, TileQuery, TileRequestPath};
 
 struct AppState {
     data_source: Box<dyn DataSource + Send + Sync + 'static>,
@@ -32,9 +32,16 @@ where
     Ok(f)
 }
 
+fn data_source_error(e: DataSourceError) -> error::Error {
+    match e {
+        DataSourceError::NotFound(..) => error::ErrorNotFound(e),
+        _ => error::ErrorInternalServerError(e),
+    }
+}
+
 #[get("/info")]
 async fn fetch_info(state: web::Data<AppState>) -> Result<impl Responder> {
-    let result = state.data_source.fetch_info();
+    let result = state.data_source.fetch_info().map_err(data_source_error)?;
     encode(result)
 }
 
@@ -49,7 +56,8 @@ async fn fetch_summary_tile(
         .map_err(|e| error::ErrorBadRequest(format!("bad request: {}", e)))?;
     let result = state
         .data_source
-        .fetch_summary_tile(&path.entry_id, path.tile_id, query.full);
+        .fetch_summary_tile(&path.entry_id, path.tile_id, query.full)
+        .map_err(data_source_error)?;
     encode(result)
 }
 
@@ -64,7 +72,8 @@ async fn fetch_slot_tile(
         .map_err(|e| error::ErrorBadRequest(format!("bad request: {}", e)))?;
     let result = state
         .data_source
-        .fetch_slot_tile(&path.entry_id, path.tile_id, query.full);
+        .fetch_slot_tile(&path.entry_id, path.tile_id, query.full)
+        .map_err(data_source_error)?;
     encode(result)
 }
 
@@ -79,7 +88,66 @@ async fn fetch_slot_meta_tile(
         .map_err(|e| error::ErrorBadRequest(format!("bad request: {}", e)))?;
     let result = state
         .data_source
-        .fetch_slot_meta_tile(&path.entry_id, path.tile_id, query.full);
+        .fetch_slot_meta_tile(&path.entry_id, path.tile_id, query.full)
+        .map_err(data_source_error)?;
+    encode(result)
+}
+
+fn decode_batch(body: &[u8]) -> Result<BatchTileRequest> {
+    ciborium::from_reader(body).map_err(|e| error::ErrorBadRequest(format!("bad request: {}", e)))
+}
+
+// The batch endpoints return a result per tile (rather than failing the
+// entire request), so that one missing tile doesn't affect the others
+
+#[post("/summary_tiles")]
+async fn fetch_summary_tiles(
+    body: web::Bytes,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let batch = decode_batch(&body)?;
+    let result: Vec<_> = batch
+        .tiles
+        .iter()
+        .map(|req| {
+            state
+                .data_source
+                .fetch_summary_tile(&req.entry_id, req.tile_id, batch.full)
+        })
+        .collect();
+    encode(result)
+}
+
+#[post("/slot_tiles")]
+async fn fetch_slot_tiles(body: web::Bytes, state: web::Data<AppState>) -> Result<impl Responder> {
+    let batch = decode_batch(&body)?;
+    let result: Vec<_> = batch
+        .tiles
+        .iter()
+        .map(|req| {
+            state
+                .data_source
+                .fetch_slot_tile(&req.entry_id, req.tile_id, batch.full)
+        })
+        .collect();
+    encode(result)
+}
+
+#[post("/slot_meta_tiles")]
+async fn fetch_slot_meta_tiles(
+    body: web::Bytes,
+    state: web::Data<AppState>,
+) -> Result<impl Responder> {
+    let batch = decode_batch(&body)?;
+    let result: Vec<_> = batch
+        .tiles
+        .iter()
+        .map(|req| {
+            state
+                .data_source
+                .fetch_slot_meta_tile(&req.entry_id, req.tile_id, batch.full)
+        })
+        .collect();
     encode(result)
 }
 
@@ -115,6 +183,9 @@ impl DataSourceHTTPServer {
                 .service(fetch_summary_tile)
                 .service(fetch_slot_tile)
                 .service(fetch_slot_meta_tile)
+                .service(fetch_summary_tiles)
+                .service(fetch_slot_tiles)
+                .service(fetch_slot_meta_tiles)
         })
         .bind((self.host.as_str(), self.port))?
         .run()
@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use egui::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+// Things the user can do from the keyboard
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum KeyAction {
+    PanLeft,
+    PanRight,
+    FinePanLeft,
+    FinePanRight,
+    ScrollUp,
+    ScrollDown,
+    FineScrollUp,
+    FineScrollDown,
+    PageUp,
+    PageDown,
+    ZoomIn,
+    ZoomOut,
+    UndoZoom,
+    RedoZoom,
+    ResetZoom,
+    ExpandVertical,
+    ShrinkVertical,
+    ResetVertical,
+    ResetView,
+    NextItem,
+    PreviousItem,
+    AddMarker,
+    ToggleControls,
+    ResetUI,
+    ClearFilters,
+}
+
+impl KeyAction {
+    // In the order they're listed in the controls window
+    pub const ALL: [KeyAction; 25] = [
+        KeyAction::PanLeft,
+        KeyAction::PanRight,
+        KeyAction::FinePanLeft,
+        KeyAction::FinePanRight,
+        KeyAction::ScrollUp,
+        KeyAction::ScrollDown,
+        KeyAction::FineScrollUp,
+        KeyAction::FineScrollDown,
+        KeyAction::PageUp,
+        KeyAction::PageDown,
+        KeyAction::ZoomIn,
+        KeyAction::ZoomOut,
+        KeyAction::UndoZoom,
+        KeyAction::RedoZoom,
+        KeyAction::ResetZoom,
+        KeyAction::ExpandVertical,
+        KeyAction::ShrinkVertical,
+        KeyAction::ResetVertical,
+        KeyAction::ResetView,
+        KeyAction::NextItem,
+        KeyAction::PreviousItem,
+        KeyAction::AddMarker,
+        KeyAction::ToggleControls,
+        KeyAction::ResetUI,
+        KeyAction::ClearFilters,
+    ];
+
+    pub fn label_text(&self) -> &'static str {
+        match *self {
+            KeyAction::PanLeft => "Pan Left",
+            KeyAction::PanRight => "Pan Right",
+            KeyAction::FinePanLeft => "Fine Pan Left",
+            KeyAction::FinePanRight => "Fine Pan Right",
+            KeyAction::ScrollUp => "Vertical Scroll Up",
+            KeyAction::ScrollDown => "Vertical Scroll Down",
+            KeyAction::FineScrollUp => "Fine Vertical Scroll Up",
+            KeyAction::FineScrollDown => "Fine Vertical Scroll Down",
+            KeyAction::PageUp => "Vertical Scroll Up by Page",
+            KeyAction::PageDown => "Vertical Scroll Down by Page",
+            KeyAction::ZoomIn => "Zoom In",
+            KeyAction::ZoomOut => "Zoom Out",
+            KeyAction::UndoZoom => "Undo Pan/Zoom",
+            KeyAction::RedoZoom => "Redo Pan/Zoom",
+            KeyAction::ResetZoom => "Reset Pan/Zoom",
+            KeyAction::ExpandVertical => "Expand Vertical Spacing",
+            KeyAction::ShrinkVertical => "Shrink Vertical Spacing",
+            KeyAction::ResetVertical => "Reset Vertical Spacing",
+            KeyAction::ResetView => "Reset All View Settings",
+            KeyAction::NextItem => "Next Item in Row",
+            KeyAction::PreviousItem => "Previous Item in Row",
+            KeyAction::AddMarker => "Add Marker at Cursor",
+            KeyAction::ToggleControls => "Toggle This Window",
+            KeyAction::ResetUI => "Clear Selection and Hide This Window",
+            KeyAction::ClearFilters => "Clear All Filters",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct KeyBinding {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl KeyBinding {
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    pub const fn ctrl(self) -> Self {
+        Self { ctrl: true, ..self }
+    }
+
+    pub const fn alt(self) -> Self {
+        Self { alt: true, ..self }
+    }
+
+    pub const fn shift(self) -> Self {
+        Self {
+            shift: true,
+            ..self
+        }
+    }
+
+    pub fn from_press(key: Key, modifiers: Modifiers) -> Self {
+        Self {
+            key,
+            ctrl: modifiers.ctrl,
+            alt: modifiers.alt,
+            shift: modifiers.shift,
+        }
+    }
+
+    // Ctrl and Alt must match exactly. Shift is only checked if the binding
+    // asks for it, because some keys (e.g., Plus) need Shift to type.
+    fn matches(&self, key: Key, modifiers: Modifiers) -> bool {
+        self.key == key
+            && self.ctrl == modifiers.ctrl
+            && self.alt == modifiers.alt
+            && (!self.shift || modifiers.shift)
+    }
+
+    fn modifier_count(&self) -> usize {
+        [self.ctrl, self.alt, self.shift]
+            .into_iter()
+            .filter(|x| *x)
+            .count()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl + ")?;
+        }
+        if self.alt {
+            write!(f, "Alt + ")?;
+        }
+        if self.shift {
+            write!(f, "Shift + ")?;
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    bindings: BTreeMap<KeyAction, Vec<KeyBinding>>,
+
+    // Percent of the view to pan by
+    pub pan_step: u8,
+    pub fine_pan_step: u8,
+
+    // Rows to scroll by
+    pub scroll_step: i32,
+    pub fine_scroll_step: i32,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use KeyAction::*;
+        let bindings = [
+            (PanLeft, vec![KeyBinding::new(Key::ArrowLeft)]),
+            (PanRight, vec![KeyBinding::new(Key::ArrowRight)]),
+            (FinePanLeft, vec![KeyBinding::new(Key::ArrowLeft).shift()]),
+            (FinePanRight, vec![KeyBinding::new(Key::ArrowRight).shift()]),
+            (ScrollUp, vec![KeyBinding::new(Key::ArrowUp)]),
+            (ScrollDown, vec![KeyBinding::new(Key::ArrowDown)]),
+            (FineScrollUp, vec![KeyBinding::new(Key::ArrowUp).shift()]),
+            (
+                FineScrollDown,
+                vec![KeyBinding::new(Key::ArrowDown).shift()],
+            ),
+            (PageUp, vec![KeyBinding::new(Key::PageUp)]),
+            (PageDown, vec![KeyBinding::new(Key::PageDown)]),
+            (
+                ZoomIn,
+                vec![
+                    KeyBinding::new(Key::Plus).ctrl(),
+                    KeyBinding::new(Key::Equals).ctrl(),
+                ],
+            ),
+            (ZoomOut, vec![KeyBinding::new(Key::Minus).ctrl()]),
+            (UndoZoom, vec![KeyBinding::new(Key::ArrowLeft).ctrl()]),
+            (RedoZoom, vec![KeyBinding::new(Key::ArrowRight).ctrl()]),
+            (ResetZoom, vec![KeyBinding::new(Key::Num0).ctrl()]),
+            (
+                ExpandVertical,
+                vec![
+                    KeyBinding::new(Key::Plus).ctrl().alt(),
+                    KeyBinding::new(Key::Equals).ctrl().alt(),
+                ],
+            ),
+            (
+                ShrinkVertical,
+                vec![KeyBinding::new(Key::Minus).ctrl().alt()],
+            ),
+            (ResetVertical, vec![KeyBinding::new(Key::Num0).ctrl().alt()]),
+            (ResetView, vec![KeyBinding::new(Key::Num0).ctrl().shift()]),
+            (NextItem, vec![KeyBinding::new(Key::Tab)]),
+            (PreviousItem, vec![KeyBinding::new(Key::Tab).shift()]),
+            (AddMarker, vec![KeyBinding::new(Key::M)]),
+            (ToggleControls, vec![KeyBinding::new(Key::H)]),
+            (ResetUI, vec![KeyBinding::new(Key::Escape)]),
+            (ClearFilters, vec![KeyBinding::new(Key::Escape).shift()]),
+        ];
+        Self {
+            bindings: bindings.into_iter().collect(),
+            pan_step: 5,
+            fine_pan_step: 1,
+            scroll_step: 5,
+            fine_scroll_step: 1,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: KeyAction) -> &[KeyBinding] {
+        self.bindings.get(&action).map_or(&[], |b| b.as_slice())
+    }
+
+    pub fn set(&mut self, action: KeyAction, binding: KeyBinding) {
+        self.bindings.insert(action, vec![binding]);
+    }
+
+    // Finds the action for a key press. If several bindings match, the one
+    // with the most modifiers wins (so Shift + Left beats Left).
+    pub fn action(&self, key: Key, modifiers: Modifiers) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .flat_map(|(action, bindings)| bindings.iter().map(move |b| (*action, b)))
+            .filter(|(_, binding)| binding.matches(key, modifiers))
+            .max_by_key(|(_, binding)| binding.modifier_count())
+            .map(|(action, _)| action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifiers(ctrl: bool, alt: bool, shift: bool) -> Modifiers {
+        Modifiers {
+            ctrl,
+            alt,
+            shift,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_defaults() {
+        let bindings = KeyBindings::default();
+        let action = |key, ctrl, alt, shift| bindings.action(key, modifiers(ctrl, alt, shift));
+        assert_eq!(
+            action(Key::ArrowLeft, false, false, false),
+            Some(KeyAction::PanLeft)
+        );
+        assert_eq!(
+            action(Key::ArrowLeft, false, false, true),
+            Some(KeyAction::FinePanLeft)
+        );
+        assert_eq!(
+            action(Key::ArrowLeft, true, false, false),
+            Some(KeyAction::UndoZoom)
+        );
+        assert_eq!(
+            action(Key::Plus, true, false, true),
+            Some(KeyAction::ZoomIn)
+        );
+        assert_eq!(
+            action(Key::Num0, true, false, false),
+            Some(KeyAction::ResetZoom)
+        );
+        assert_eq!(
+            action(Key::Num0, true, false, true),
+            Some(KeyAction::ResetView)
+        );
+        assert_eq!(
+            action(Key::Num0, true, true, false),
+            Some(KeyAction::ResetVertical)
+        );
+        assert_eq!(
+            action(Key::Escape, false, false, true),
+            Some(KeyAction::ClearFilters)
+        );
+        assert_eq!(action(Key::H, true, false, false), None);
+    }
+
+    #[test]
+    fn test_rebind() {
+        let mut bindings = KeyBindings::default();
+        bindings.set(KeyAction::PanLeft, KeyBinding::new(Key::A));
+        let none = Modifiers::default();
+        assert_eq!(bindings.action(Key::A, none), Some(KeyAction::PanLeft));
+        assert_eq!(bindings.action(Key::ArrowLeft, none), None);
+    }
+}
@@ -3,14 +3,25 @@
 pub mod app;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod archive_data;
+pub mod async_data;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod csv_data;
 pub mod data;
 pub mod deferred_data;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod export;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod file_data;
 pub mod http;
+pub mod key_bindings;
 pub mod merge_data;
+pub mod null_data;
 #[cfg(feature = "nvtxw")]
 pub mod nvtxw;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod parallel_data;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod snapshot;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod svg;
 pub mod timestamp;
@@ -6,14 +6,17 @@ use rand::Rng;
 use std::collections::BTreeMap;
 use std::sync::Mutex;
 
+use legion_prof_viewer::app::StartOptions;
 use legion_prof_viewer::data::{
-    DataSource, DataSourceDescription, DataSourceInfo, EntryID, EntryInfo, Field, FieldID,
-    FieldSchema, Item, ItemMeta, ItemUID, SlotMetaTile, SlotMetaTileData, SlotTile, SlotTileData,
-    SummaryTile, SummaryTileData, TileID, TileSet, UtilPoint,
+    DataSource, DataSourceDescription, DataSourceInfo, EntryID, EntryInfo, EntryInfoBuilder, Field,
+    FieldID, FieldSchema, Item, ItemMeta, ItemUID, Result, SlotMetaTile, SlotMetaTileData,
+    SlotTile, SlotTileData, SummaryTile, SummaryTileData, TileID, TileSet, UtilPoint, UtilRange,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-use legion_prof_viewer::deferred_data::DeferredDataSourceWrapper;
+use legion_prof_viewer::deferred_data::{DeferredDataSource, DeferredDataSourceWrapper};
+#[cfg(not(target_arch = "wasm32"))]
+use legion_prof_viewer::file_data::{FileDataSource, TarFileDataSource};
 use legion_prof_viewer::timestamp::{Interval, Timestamp};
 
 #[cfg(target_arch = "wasm32")]
@@ -24,11 +27,74 @@ use url::Url;
 #[cfg(target_arch = "wasm32")]
 const DEFAULT_URL: &str = "http://127.0.0.1:8080";
 
+// Usage: legion_prof_viewer [--interval <start>..<stop> | --link <link> | --profiles <dir>]
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
-    legion_prof_viewer::app::start(vec![Box::new(DeferredDataSourceWrapper::new(
-        RandomDataSource::new(),
-    ))]);
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    // Opens each profile (directory or .tar/.tar.zst archive) found in a
+    // directory, e.g., one per run of a sweep
+    if let [flag, dir] = &args[..] {
+        if flag == "--profiles" {
+            let paths = FileDataSource::discover(dir).expect("Unable to read directory");
+            if paths.is_empty() {
+                eprintln!("no profiles found in {}", dir);
+                std::process::exit(1);
+            }
+            let data_sources = paths.iter().map(|path| open_profile(path)).collect();
+            legion_prof_viewer::app::start(data_sources);
+            return;
+        }
+    }
+    // e.g. a link copied with "Copy Link to Item", which opens the profile it
+    // was copied from
+    if let [flag, link] = &args[..] {
+        if flag == "--link" {
+            let Some(locator) = StartOptions::link_locator(link) else {
+                eprintln!("not a link to a profile: {}", link);
+                std::process::exit(1);
+            };
+            legion_prof_viewer::app::start_with_options(
+                vec![open_profile(std::path::Path::new(&locator))],
+                StartOptions::from_link(link),
+            );
+            return;
+        }
+    }
+    let options = match &args[..] {
+        [] => StartOptions::default(),
+        [flag, interval] if flag == "--interval" => StartOptions {
+            initial_interval: Some(Interval::parse(interval).expect("Unable to parse interval")),
+            ..Default::default()
+        },
+        _ => {
+            eprintln!(
+                "usage: legion_prof_viewer [--interval <start>..<stop> | --link <link> | --profiles <dir>]"
+            );
+            std::process::exit(1);
+        }
+    };
+
+    legion_prof_viewer::app::start_with_options(
+        vec![Box::new(DeferredDataSourceWrapper::new(
+            RandomDataSource::new(),
+        ))],
+        options,
+    );
+}
+
+// A profile directory, or an archive of one
+#[cfg(not(target_arch = "wasm32"))]
+fn open_profile(path: &std::path::Path) -> Box<dyn DeferredDataSource> {
+    if !TarFileDataSource::is_archive(path) {
+        return Box::new(DeferredDataSourceWrapper::new(FileDataSource::new(path)));
+    }
+    match TarFileDataSource::open(path) {
+        Ok(source) => Box::new(DeferredDataSourceWrapper::new(source)),
+        Err(e) => {
+            eprintln!("unable to open {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -47,7 +113,13 @@ fn main() {
     )
     .expect("Unable to parse query URL");
 
-    legion_prof_viewer::app::start(vec![Box::new(HTTPClientDataSource::new(url))]);
+    // e.g. ?interval=1.5ms..3ms, or a link copied from the address bar
+    let options = StartOptions::from_query_pairs(browser_url.query_pairs());
+
+    legion_prof_viewer::app::start_with_options(
+        vec![Box::new(HTTPClientDataSource::new(url))],
+        options,
+    );
 }
 
 type SlotCacheTile = (Vec<Vec<Item>>, Vec<Vec<ItemMeta>>);
@@ -90,6 +162,7 @@ impl RandomDataSource {
         let entry_info = Self::entry_info(&mut rng);
         let mut field_schema = FieldSchema::new();
         let item_uid_field = field_schema.insert("Item UID".to_owned(), false);
+        field_schema.set_numeric(item_uid_field);
         let interval_field = field_schema.insert("Interval".to_owned(), false);
 
         let info = DataSourceInfo {
@@ -98,6 +171,7 @@ impl RandomDataSource {
             tile_set: TileSet::default(),
             field_schema,
             warning_message: Some("Demo only. The data in this profile is synthetic.".to_string()),
+            epoch: None,
         };
 
         let state = RandomState {
@@ -250,45 +324,30 @@ impl RandomDataSource {
 
         const NODES: i32 = 8192;
         const PROCS: i32 = 8;
-        let mut node_slots = Vec::new();
+        let mut builder = EntryInfoBuilder::new();
         for node in 0..NODES {
-            let mut kind_slots = Vec::new();
+            let node_id = builder.add_node(format!("n{node}"), format!("Node {node}"));
             let colors = &[Color32::BLUE, Color32::GREEN, Color32::RED, Color32::YELLOW];
             for (i, kind) in kinds.iter().enumerate() {
                 let color = colors[i % colors.len()];
-                let mut proc_slots = Vec::new();
+                let kind_id = builder.add_kind(
+                    &node_id,
+                    kind.to_lowercase(),
+                    format!("Node {node} {kind}"),
+                    Some(color),
+                );
                 for proc in 0..PROCS {
                     let rows: u64 = rng.gen_range(0..64);
-                    proc_slots.push(EntryInfo::Slot {
-                        short_name: format!(
-                            "{}{}",
-                            kind.chars().next().unwrap().to_lowercase(),
-                            proc
-                        ),
-                        long_name: format!("Node {node} {kind} {proc}"),
-                        max_rows: rows,
-                    });
+                    builder.add_slot(
+                        &kind_id,
+                        format!("{}{}", kind.chars().next().unwrap().to_lowercase(), proc),
+                        format!("Node {node} {kind} {proc}"),
+                        rows,
+                    );
                 }
-                kind_slots.push(EntryInfo::Panel {
-                    short_name: kind.to_lowercase(),
-                    long_name: format!("Node {node} {kind}"),
-                    summary: Some(Box::new(EntryInfo::Summary { color })),
-                    slots: proc_slots,
-                });
             }
-            node_slots.push(EntryInfo::Panel {
-                short_name: format!("n{node}"),
-                long_name: format!("Node {node}"),
-                summary: None,
-                slots: kind_slots,
-            });
-        }
-        EntryInfo::Panel {
-            short_name: "root".to_owned(),
-            long_name: "root".to_owned(),
-            summary: None,
-            slots: node_slots,
         }
+        builder.build()
     }
 }
 
@@ -298,11 +357,16 @@ impl DataSource for RandomDataSource {
             source_locator: vec!["Random Data Source".to_string()],
         }
     }
-    fn fetch_info(&self) -> DataSourceInfo {
-        self.info.clone()
+    fn fetch_info(&self) -> Result<DataSourceInfo> {
+        Ok(self.info.clone())
     }
 
-    fn fetch_summary_tile(&self, entry_id: &EntryID, tile_id: TileID, _full: bool) -> SummaryTile {
+    fn fetch_summary_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SummaryTile> {
         let utilization = self.generate_summary(entry_id);
 
         let mut tile_utilization = Vec::new();
@@ -339,16 +403,33 @@ impl DataSource for RandomDataSource {
 
             last_point = Some(point);
         }
-        SummaryTile {
+
+        // Synthesize a spread around the average to demonstrate the band
+        let utilization_range = tile_utilization
+            .iter()
+            .map(|&UtilPoint { time, util }| UtilRange {
+                time,
+                min: util * util,
+                max: util.sqrt(),
+            })
+            .collect();
+
+        Ok(SummaryTile {
             entry_id: entry_id.clone(),
             tile_id,
             data: SummaryTileData {
+                utilization_range,
                 utilization: tile_utilization,
             },
-        }
+        })
     }
 
-    fn fetch_slot_tile(&self, entry_id: &EntryID, tile_id: TileID, _full: bool) -> SlotTile {
+    fn fetch_slot_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotTile> {
         let items = &self.generate_slot(entry_id).0;
 
         let mut slot_items = Vec::new();
@@ -366,11 +447,11 @@ impl DataSource for RandomDataSource {
             slot_items.push(slot_row);
         }
 
-        SlotTile {
+        Ok(SlotTile {
             entry_id: entry_id.clone(),
             tile_id,
             data: SlotTileData { items: slot_items },
-        }
+        })
     }
 
     fn fetch_slot_meta_tile(
@@ -378,7 +459,7 @@ impl DataSource for RandomDataSource {
         entry_id: &EntryID,
         tile_id: TileID,
         _full: bool,
-    ) -> SlotMetaTile {
+    ) -> Result<SlotMetaTile> {
         let (items, item_metas) = self.generate_slot(entry_id);
 
         let mut slot_items = Vec::new();
@@ -394,10 +475,10 @@ impl DataSource for RandomDataSource {
             slot_items.push(slot_row);
         }
 
-        SlotMetaTile {
+        Ok(SlotMetaTile {
             entry_id: entry_id.clone(),
             tile_id,
             data: SlotMetaTileData { items: slot_items },
-        }
+        })
     }
 }
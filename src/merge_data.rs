@@ -1,26 +1,74 @@
 use std::collections::VecDeque;
 
 use crate::data::{
-    DataSourceDescription, DataSourceInfo, EntryID, EntryIndex, EntryInfo, Field, ItemLink,
-    ItemUID, SlotMetaTile, SlotTile, SummaryTile, TileID,
+    DataSourceDescription, DataSourceError, DataSourceInfo, EntryID, EntryIndex, EntryInfo, Field,
+    ItemLink, ItemUID, Result, SlotMetaTile, SlotTile, SummaryTile, TileID,
 };
 use crate::deferred_data::DeferredDataSource;
+use crate::http::schema::TileRequest;
 use crate::timestamp::Interval;
 
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum MergeMode {
+    // The nodes of each source, one source after another
+    #[default]
+    Concatenate,
+    // Nodes (and other panels without summaries) with the same names are
+    // merged, and entries with the same names are placed next to each other,
+    // so that the sources can be compared row by row. Anything that only
+    // exists in some sources is kept as is.
+    Aligned,
+}
+
+// An entry of the merged tree that comes whole from one source
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Subtree {
+    idx: usize,
+    src: EntryID,
+    dst: EntryID,
+}
+
+// Entries from different sources that end up next to each other (or merged,
+// for panels without summaries)
+struct AlignGroup {
+    mergeable: bool,
+    short_name: String,
+    long_name: String,
+    members: Vec<(usize, EntryID, EntryInfo)>,
+}
+
 pub struct MergeDeferredDataSource {
     data_sources: Vec<Box<dyn DeferredDataSource>>,
-    infos: Vec<VecDeque<DataSourceInfo>>,
+    infos: Vec<VecDeque<Result<DataSourceInfo>>>,
+    mode: MergeMode,
+    // Used in Concatenate mode
     mapping: Vec<u64>,
+    // Used in Aligned mode
+    subtrees: Vec<Subtree>,
+    // Requests for entries that aren't part of the merged profile, failed
+    // on the next get
+    unmapped_summary_tiles: Vec<(Result<SummaryTile>, TileRequest)>,
+    unmapped_slot_tiles: Vec<(Result<SlotTile>, TileRequest)>,
+    unmapped_slot_meta_tiles: Vec<(Result<SlotMetaTile>, TileRequest)>,
 }
 
 impl MergeDeferredDataSource {
     pub fn new(data_sources: Vec<Box<dyn DeferredDataSource>>) -> Self {
+        Self::with_mode(data_sources, MergeMode::Concatenate)
+    }
+
+    pub fn with_mode(data_sources: Vec<Box<dyn DeferredDataSource>>, mode: MergeMode) -> Self {
         assert!(!data_sources.is_empty());
         let infos = vec![VecDeque::new(); data_sources.len()];
         Self {
             data_sources,
             infos,
+            mode,
             mapping: Vec::new(),
+            subtrees: Vec::new(),
+            unmapped_summary_tiles: Vec::new(),
+            unmapped_slot_tiles: Vec::new(),
+            unmapped_slot_meta_tiles: Vec::new(),
         }
     }
 
@@ -80,6 +128,141 @@ impl MergeDeferredDataSource {
         mapping
     }
 
+    // Merges the children of panels that were matched across sources, placing
+    // the results under dst. Members are (source, panel, children).
+    fn align_children(
+        members: Vec<(usize, EntryID, Vec<EntryInfo>)>,
+        dst: &EntryID,
+        subtrees: &mut Vec<Subtree>,
+    ) -> Vec<EntryInfo> {
+        let mut groups: Vec<AlignGroup> = Vec::new();
+        for (idx, src, children) in members {
+            for (i, child) in children.into_iter().enumerate() {
+                let (mergeable, short_name, long_name) = match &child {
+                    EntryInfo::Panel {
+                        short_name,
+                        long_name,
+                        summary,
+                        ..
+                    } => (summary.is_none(), short_name, long_name),
+                    EntryInfo::Slot {
+                        short_name,
+                        long_name,
+                        ..
+                    } => (false, short_name, long_name),
+                    EntryInfo::Summary { .. } => unreachable!(),
+                };
+                // Each group takes at most one entry from each source, so
+                // that entries with duplicate names are kept apart
+                let group = groups.iter_mut().find(|g| {
+                    g.mergeable == mergeable
+                        && g.short_name == *short_name
+                        && g.long_name == *long_name
+                        && g.members.iter().all(|(j, _, _)| *j != idx)
+                });
+                let member = (idx, src.child(i as u64), child.clone());
+                match group {
+                    Some(group) => group.members.push(member),
+                    None => groups.push(AlignGroup {
+                        mergeable,
+                        short_name: short_name.clone(),
+                        long_name: long_name.clone(),
+                        members: vec![member],
+                    }),
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        for group in groups {
+            if group.mergeable {
+                let dst = dst.child(result.len() as u64);
+                let members = group
+                    .members
+                    .into_iter()
+                    .map(|(idx, src, child)| {
+                        let EntryInfo::Panel { slots, .. } = child else {
+                            unreachable!();
+                        };
+                        (idx, src, slots)
+                    })
+                    .collect();
+                result.push(EntryInfo::Panel {
+                    short_name: group.short_name,
+                    long_name: group.long_name,
+                    summary: None,
+                    slots: Self::align_children(members, &dst, subtrees),
+                });
+                continue;
+            }
+
+            // Label each copy with its source so they can be told apart
+            let matched = group.members.len() > 1;
+            for (idx, src, mut child) in group.members {
+                if matched {
+                    match &mut child {
+                        EntryInfo::Panel {
+                            short_name,
+                            long_name,
+                            ..
+                        }
+                        | EntryInfo::Slot {
+                            short_name,
+                            long_name,
+                            ..
+                        } => {
+                            *short_name = format!("{} ({})", short_name, idx);
+                            *long_name = format!("{} (source {})", long_name, idx);
+                        }
+                        EntryInfo::Summary { .. } => unreachable!(),
+                    }
+                }
+                let dst = dst.child(result.len() as u64);
+                subtrees.push(Subtree { idx, src, dst });
+                result.push(child);
+            }
+        }
+        result
+    }
+
+    fn align_entries(source_infos: &[DataSourceInfo]) -> (EntryInfo, Vec<Subtree>) {
+        let members = source_infos
+            .iter()
+            .enumerate()
+            .map(|(idx, info)| {
+                let EntryInfo::Panel {
+                    ref summary,
+                    ref slots,
+                    ..
+                } = info.entry_info
+                else {
+                    unreachable!();
+                };
+                assert!(summary.is_none());
+                (idx, EntryID::root(), slots.clone())
+            })
+            .collect();
+
+        let EntryInfo::Panel {
+            ref short_name,
+            ref long_name,
+            ..
+        } = source_infos[0].entry_info
+        else {
+            unreachable!();
+        };
+
+        let mut subtrees = Vec::new();
+        let slots = Self::align_children(members, &EntryID::root(), &mut subtrees);
+        let entry_info = EntryInfo::Panel {
+            short_name: short_name.clone(),
+            long_name: long_name.clone(),
+            summary: None,
+            slots,
+        };
+        (entry_info, subtrees)
+    }
+
     fn merge_infos(source_infos: Vec<DataSourceInfo>) -> DataSourceInfo {
         assert!(!source_infos.is_empty());
 
@@ -108,6 +291,7 @@ impl MergeDeferredDataSource {
             .map(|info| info.entry_info.clone())
             .reduce(Self::merge_entry)
             .unwrap();
+        let epoch = source_infos.iter().find_map(|info| info.epoch);
 
         DataSourceInfo {
             entry_info,
@@ -115,49 +299,110 @@ impl MergeDeferredDataSource {
             tile_set,
             field_schema,
             warning_message,
+            epoch,
         }
     }
 
-    fn map_src_to_dst_entry(&self, idx: usize, src_entry: &EntryID) -> EntryID {
-        src_entry.shift_level0(self.mapping[idx] as i64)
+    // In Aligned mode, sources may refer to entries (e.g., in item links)
+    // that didn't make it into the merged profile
+    fn map_src_to_dst_entry(&self, idx: usize, src_entry: &EntryID) -> Result<EntryID> {
+        if self.mode == MergeMode::Aligned {
+            let subtree = self
+                .subtrees
+                .iter()
+                .find(|s| s.idx == idx && src_entry.has_prefix(&s.src))
+                .ok_or_else(|| {
+                    DataSourceError::Invalid(format!(
+                        "entry {:?} of source {} is not part of the merged profile",
+                        src_entry, idx
+                    ))
+                })?;
+            return Ok(src_entry.replace_prefix(&subtree.src, &subtree.dst));
+        }
+
+        Ok(src_entry.shift_level0(self.mapping[idx] as i64))
     }
 
-    fn map_dst_to_src_entry(&self, dst_entry: &EntryID) -> (usize, EntryID) {
+    fn map_dst_to_src_entry(&self, dst_entry: &EntryID) -> Result<(usize, EntryID)> {
+        if self.mode == MergeMode::Aligned {
+            let subtree = self
+                .subtrees
+                .iter()
+                .find(|s| dst_entry.has_prefix(&s.dst))
+                .ok_or_else(|| {
+                    DataSourceError::Invalid(format!(
+                        "entry {:?} does not come from any source",
+                        dst_entry
+                    ))
+                })?;
+            return Ok((
+                subtree.idx,
+                dst_entry.replace_prefix(&subtree.dst, &subtree.src),
+            ));
+        }
+
         let Some(EntryIndex::Slot(level0)) = dst_entry.index(0) else {
             unreachable!();
         };
 
         let idx = self.mapping.partition_point(|&offset| offset < level0);
-        (idx, dst_entry.shift_level0(-(self.mapping[idx] as i64)))
+        Ok((idx, dst_entry.shift_level0(-(self.mapping[idx] as i64))))
+    }
+
+    // Splits requests by the data source they belong to. Requests that
+    // belong to none are returned separately, along with the reason.
+    fn map_dst_to_src_requests(
+        &self,
+        requests: &[TileRequest],
+    ) -> (Vec<Vec<TileRequest>>, Vec<(DataSourceError, TileRequest)>) {
+        let mut result = vec![Vec::new(); self.data_sources.len()];
+        let mut unmapped = Vec::new();
+        for req in requests {
+            match self.map_dst_to_src_entry(&req.entry_id) {
+                Ok((idx, entry_id)) => result[idx].push(TileRequest {
+                    entry_id,
+                    tile_id: req.tile_id,
+                }),
+                Err(e) => unmapped.push((e, req.clone())),
+            }
+        }
+        (result, unmapped)
     }
 
     fn map_src_to_dst_item_uid(&self, idx: usize, item_uid: ItemUID) -> ItemUID {
-        ItemUID(item_uid.0 * (self.mapping.len() as u64) + (idx as u64))
+        ItemUID(item_uid.0 * (self.data_sources.len() as u64) + (idx as u64))
+    }
+
+    fn map_src_to_dst_request(&self, idx: usize, req: TileRequest) -> Result<TileRequest> {
+        Ok(TileRequest {
+            entry_id: self.map_src_to_dst_entry(idx, &req.entry_id)?,
+            tile_id: req.tile_id,
+        })
     }
 
-    fn map_src_to_dst_summary(&self, idx: usize, tile: SummaryTile) -> SummaryTile {
-        SummaryTile {
-            entry_id: self.map_src_to_dst_entry(idx, &tile.entry_id),
+    fn map_src_to_dst_summary(&self, idx: usize, tile: SummaryTile) -> Result<SummaryTile> {
+        Ok(SummaryTile {
+            entry_id: self.map_src_to_dst_entry(idx, &tile.entry_id)?,
             tile_id: tile.tile_id,
             data: tile.data,
-        }
+        })
     }
 
-    fn map_src_to_dst_slot(&self, idx: usize, mut tile: SlotTile) -> SlotTile {
+    fn map_src_to_dst_slot(&self, idx: usize, mut tile: SlotTile) -> Result<SlotTile> {
         for items in &mut tile.data.items {
             for item in items {
                 item.item_uid = self.map_src_to_dst_item_uid(idx, item.item_uid);
             }
         }
 
-        SlotTile {
-            entry_id: self.map_src_to_dst_entry(idx, &tile.entry_id),
+        Ok(SlotTile {
+            entry_id: self.map_src_to_dst_entry(idx, &tile.entry_id)?,
             tile_id: tile.tile_id,
             data: tile.data,
-        }
+        })
     }
 
-    fn map_src_to_dst_field(&self, idx: usize, field: &mut Field) {
+    fn map_src_to_dst_field(&self, idx: usize, field: &mut Field) -> Result<()> {
         match field {
             Field::ItemLink(ItemLink {
                 ref mut item_uid,
@@ -165,32 +410,33 @@ impl MergeDeferredDataSource {
                 ..
             }) => {
                 *item_uid = self.map_src_to_dst_item_uid(idx, *item_uid);
-                *entry_id = self.map_src_to_dst_entry(idx, entry_id);
+                *entry_id = self.map_src_to_dst_entry(idx, entry_id)?;
             }
             Field::Vec(elts) => {
                 for elt in elts {
-                    self.map_src_to_dst_field(idx, elt);
+                    self.map_src_to_dst_field(idx, elt)?;
                 }
             }
             _ => (),
         }
+        Ok(())
     }
 
-    fn map_src_to_dst_slot_meta(&self, idx: usize, mut tile: SlotMetaTile) -> SlotMetaTile {
+    fn map_src_to_dst_slot_meta(&self, idx: usize, mut tile: SlotMetaTile) -> Result<SlotMetaTile> {
         for items in &mut tile.data.items {
             for item in items {
                 item.item_uid = self.map_src_to_dst_item_uid(idx, item.item_uid);
                 for (_, field, _) in &mut item.fields {
-                    self.map_src_to_dst_field(idx, field);
+                    self.map_src_to_dst_field(idx, field)?;
                 }
             }
         }
 
-        SlotMetaTile {
-            entry_id: self.map_src_to_dst_entry(idx, &tile.entry_id),
+        Ok(SlotMetaTile {
+            entry_id: self.map_src_to_dst_entry(idx, &tile.entry_id)?,
             tile_id: tile.tile_id,
             data: tile.data,
-        }
+        })
     }
 }
 
@@ -211,7 +457,7 @@ impl DeferredDataSource for MergeDeferredDataSource {
         }
     }
 
-    fn get_infos(&mut self) -> Vec<DataSourceInfo> {
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
         for (data_source, infos) in self.data_sources.iter_mut().zip(self.infos.iter_mut()) {
             infos.extend(data_source.get_infos());
         }
@@ -220,24 +466,45 @@ impl DeferredDataSource for MergeDeferredDataSource {
 
         let mut result = Vec::new();
         for _ in 0..max_available {
-            let source_infos: Vec<_> = self
+            let source_infos: Result<Vec<_>> = self
                 .infos
                 .iter_mut()
                 .map(|infos| infos.pop_front().unwrap())
                 .collect();
-            self.mapping = Self::compute_mapping(&source_infos);
-            result.push(Self::merge_infos(source_infos));
+            result.push(source_infos.map(|source_infos| match self.mode {
+                MergeMode::Concatenate => {
+                    self.mapping = Self::compute_mapping(&source_infos);
+                    Self::merge_infos(source_infos)
+                }
+                MergeMode::Aligned => {
+                    let (entry_info, subtrees) = Self::align_entries(&source_infos);
+                    self.subtrees = subtrees;
+                    DataSourceInfo {
+                        entry_info,
+                        ..Self::merge_infos(source_infos)
+                    }
+                }
+            }));
         }
         result
     }
 
     fn fetch_summary_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
-        let (idx, src_entry) = self.map_dst_to_src_entry(entry_id);
-
-        self.data_sources[idx].fetch_summary_tile(&src_entry, tile_id, full);
+        match self.map_dst_to_src_entry(entry_id) {
+            Ok((idx, src_entry)) => {
+                self.data_sources[idx].fetch_summary_tile(&src_entry, tile_id, full);
+            }
+            Err(e) => {
+                let req = TileRequest {
+                    entry_id: entry_id.clone(),
+                    tile_id,
+                };
+                self.unmapped_summary_tiles.push((Err(e), req));
+            }
+        }
     }
 
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile> {
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
         let mut tiles = Vec::new();
         for (idx, data_source) in self.data_sources.iter_mut().enumerate() {
             tiles.extend(
@@ -249,19 +516,38 @@ impl DeferredDataSource for MergeDeferredDataSource {
         }
 
         // Hack: doing this in two stages to avoid mutability conflict
-        tiles
+        let tiles: Vec<_> = tiles
             .into_iter()
-            .map(|(idx, tile)| self.map_src_to_dst_summary(idx, tile))
-            .collect()
+            .filter_map(|(idx, (tile, req))| {
+                // Drop replies to anything we didn't ask for
+                let req = self.map_src_to_dst_request(idx, req).ok()?;
+                Some((
+                    tile.and_then(|tile| self.map_src_to_dst_summary(idx, tile)),
+                    req,
+                ))
+            })
+            .collect();
+        let mut result = std::mem::take(&mut self.unmapped_summary_tiles);
+        result.extend(tiles);
+        result
     }
 
     fn fetch_slot_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
-        let (idx, src_entry) = self.map_dst_to_src_entry(entry_id);
-
-        self.data_sources[idx].fetch_slot_tile(&src_entry, tile_id, full);
+        match self.map_dst_to_src_entry(entry_id) {
+            Ok((idx, src_entry)) => {
+                self.data_sources[idx].fetch_slot_tile(&src_entry, tile_id, full);
+            }
+            Err(e) => {
+                let req = TileRequest {
+                    entry_id: entry_id.clone(),
+                    tile_id,
+                };
+                self.unmapped_slot_tiles.push((Err(e), req));
+            }
+        }
     }
 
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile> {
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
         let mut tiles = Vec::new();
         for (idx, data_source) in self.data_sources.iter_mut().enumerate() {
             tiles.extend(
@@ -273,19 +559,38 @@ impl DeferredDataSource for MergeDeferredDataSource {
         }
 
         // Hack: doing this in two stages to avoid mutability conflict
-        tiles
+        let tiles: Vec<_> = tiles
             .into_iter()
-            .map(|(idx, tile)| self.map_src_to_dst_slot(idx, tile))
-            .collect()
+            .filter_map(|(idx, (tile, req))| {
+                // Drop replies to anything we didn't ask for
+                let req = self.map_src_to_dst_request(idx, req).ok()?;
+                Some((
+                    tile.and_then(|tile| self.map_src_to_dst_slot(idx, tile)),
+                    req,
+                ))
+            })
+            .collect();
+        let mut result = std::mem::take(&mut self.unmapped_slot_tiles);
+        result.extend(tiles);
+        result
     }
 
     fn fetch_slot_meta_tile(&mut self, entry_id: &EntryID, tile_id: TileID, full: bool) {
-        let (idx, src_entry) = self.map_dst_to_src_entry(entry_id);
-
-        self.data_sources[idx].fetch_slot_meta_tile(&src_entry, tile_id, full);
+        match self.map_dst_to_src_entry(entry_id) {
+            Ok((idx, src_entry)) => {
+                self.data_sources[idx].fetch_slot_meta_tile(&src_entry, tile_id, full);
+            }
+            Err(e) => {
+                let req = TileRequest {
+                    entry_id: entry_id.clone(),
+                    tile_id,
+                };
+                self.unmapped_slot_meta_tiles.push((Err(e), req));
+            }
+        }
     }
 
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile> {
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
         let mut tiles = Vec::new();
         for (idx, data_source) in self.data_sources.iter_mut().enumerate() {
             tiles.extend(
@@ -297,10 +602,47 @@ impl DeferredDataSource for MergeDeferredDataSource {
         }
 
         // Hack: doing this in two stages to avoid mutability conflict
-        tiles
+        let tiles: Vec<_> = tiles
             .into_iter()
-            .map(|(idx, tile)| self.map_src_to_dst_slot_meta(idx, tile))
-            .collect()
+            .filter_map(|(idx, (tile, req))| {
+                // Drop replies to anything we didn't ask for
+                let req = self.map_src_to_dst_request(idx, req).ok()?;
+                Some((
+                    tile.and_then(|tile| self.map_src_to_dst_slot_meta(idx, tile)),
+                    req,
+                ))
+            })
+            .collect();
+        let mut result = std::mem::take(&mut self.unmapped_slot_meta_tiles);
+        result.extend(tiles);
+        result
+    }
+
+    fn fetch_summary_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        let (requests, unmapped) = self.map_dst_to_src_requests(requests);
+        for (data_source, requests) in self.data_sources.iter_mut().zip(requests) {
+            data_source.fetch_summary_tiles(&requests, full);
+        }
+        let unmapped = unmapped.into_iter().map(|(e, req)| (Err(e), req));
+        self.unmapped_summary_tiles.extend(unmapped);
+    }
+
+    fn fetch_slot_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        let (requests, unmapped) = self.map_dst_to_src_requests(requests);
+        for (data_source, requests) in self.data_sources.iter_mut().zip(requests) {
+            data_source.fetch_slot_tiles(&requests, full);
+        }
+        let unmapped = unmapped.into_iter().map(|(e, req)| (Err(e), req));
+        self.unmapped_slot_tiles.extend(unmapped);
+    }
+
+    fn fetch_slot_meta_tiles(&mut self, requests: &[TileRequest], full: bool) {
+        let (requests, unmapped) = self.map_dst_to_src_requests(requests);
+        for (data_source, requests) in self.data_sources.iter_mut().zip(requests) {
+            data_source.fetch_slot_meta_tiles(&requests, full);
+        }
+        let unmapped = unmapped.into_iter().map(|(e, req)| (Err(e), req));
+        self.unmapped_slot_meta_tiles.extend(unmapped);
     }
 }
 
@@ -308,7 +650,11 @@ impl DeferredDataSource for MergeDeferredDataSource {
 mod tests {
     use super::*;
 
-    use crate::data::{FieldSchema, TileSet};
+    use egui::Color32;
+
+    use crate::data::EntryInfoBuilder;
+    use crate::deferred_data::DeferredDataSourceWrapper;
+    use crate::null_data::NullDataSource;
     use crate::timestamp::Timestamp;
 
     #[test]
@@ -370,10 +716,64 @@ mod tests {
         assert_eq!(slot1_short_name, "S2");
     }
 
+    #[test]
+    fn test_align_entries() {
+        let info = |nodes: &[&str]| {
+            let mut builder = EntryInfoBuilder::new();
+            for node in nodes {
+                let node = builder.add_node(*node, *node);
+                let gpu = builder.add_kind(&node, "GPU", "GPU", Some(Color32::RED));
+                builder.add_slot(&gpu, "g0", "GPU 0", 1);
+                let cpu = builder.add_kind(&node, "CPU", "CPU", None);
+                builder.add_slot(&cpu, "c0", "CPU 0", 1);
+                builder.add_slot(&cpu, "c1", "CPU 1", 1);
+            }
+            DataSourceInfo::new(
+                builder.build(),
+                Interval::new(Timestamp(0), Timestamp(1000)),
+            )
+        };
+        let infos = vec![info(&["n0"]), info(&["n0", "n1"])];
+        let (entry_info, subtrees) = MergeDeferredDataSource::align_entries(&infos);
+        entry_info.validate().unwrap();
+
+        let name = |ids: &[u64]| {
+            let entry_id = ids.iter().fold(EntryID::root(), |e, i| e.child(*i));
+            let (EntryInfo::Panel { short_name, .. } | EntryInfo::Slot { short_name, .. }) =
+                entry_info.get(&entry_id).unwrap()
+            else {
+                unreachable!();
+            };
+            short_name.clone()
+        };
+        // Nodes are merged, or kept as is if only one source has them
+        assert_eq!(name(&[0]), "n0");
+        assert_eq!(name(&[1]), "n1");
+        // Kinds with summaries stay whole, side by side
+        assert_eq!(name(&[0, 0]), "GPU (0)");
+        assert_eq!(name(&[0, 1]), "GPU (1)");
+        // Kinds without summaries are merged, with their slots interleaved
+        assert_eq!(name(&[0, 2]), "CPU");
+        assert_eq!(name(&[0, 2, 0]), "c0 (0)");
+        assert_eq!(name(&[0, 2, 1]), "c0 (1)");
+        assert_eq!(name(&[0, 2, 3]), "c1 (1)");
+
+        assert!(subtrees.contains(&Subtree {
+            idx: 1,
+            src: EntryID::root().child(0).child(1).child(1),
+            dst: EntryID::root().child(0).child(2).child(3),
+        }));
+        assert!(subtrees.contains(&Subtree {
+            idx: 1,
+            src: EntryID::root().child(1).child(0),
+            dst: EntryID::root().child(1).child(0),
+        }));
+    }
+
     #[test]
     fn test_merge_info() {
-        let first = DataSourceInfo {
-            entry_info: EntryInfo::Panel {
+        let first = DataSourceInfo::new(
+            EntryInfo::Panel {
                 short_name: "F".to_string(),
                 long_name: "First".to_string(),
                 summary: None,
@@ -390,13 +790,10 @@ mod tests {
                     },
                 ],
             },
-            interval: Interval::new(Timestamp(0), Timestamp(1000)),
-            tile_set: TileSet { tiles: Vec::new() },
-            field_schema: FieldSchema::new(),
-            warning_message: None,
-        };
-        let second = DataSourceInfo {
-            entry_info: EntryInfo::Panel {
+            Interval::new(Timestamp(0), Timestamp(1000)),
+        );
+        let second = DataSourceInfo::new(
+            EntryInfo::Panel {
                 short_name: "S".to_string(),
                 long_name: "Second".to_string(),
                 summary: None,
@@ -406,11 +803,8 @@ mod tests {
                     max_rows: 2,
                 }],
             },
-            interval: Interval::new(Timestamp(0), Timestamp(2000)),
-            tile_set: TileSet { tiles: Vec::new() },
-            field_schema: FieldSchema::new(),
-            warning_message: None,
-        };
+            Interval::new(Timestamp(0), Timestamp(2000)),
+        );
 
         let infos = vec![first, second];
 
@@ -464,4 +858,46 @@ mod tests {
         };
         assert_eq!(slot2_short_name, "S3");
     }
+
+    #[test]
+    fn test_aligned_unknown_entry() {
+        let source = |nodes: &[&str]| -> Box<dyn DeferredDataSource> {
+            let mut builder = EntryInfoBuilder::new();
+            for node in nodes {
+                let node = builder.add_node(*node, *node);
+                let cpu = builder.add_kind(&node, "CPU", "CPU", None);
+                builder.add_slot(&cpu, "c0", "CPU 0", 1);
+            }
+            let interval = Interval::new(Timestamp(0), Timestamp(1000));
+            let source = NullDataSource::new(builder.build(), interval);
+            Box::new(DeferredDataSourceWrapper::new(source))
+        };
+        let sources = vec![source(&["n0"]), source(&["n0", "n1"])];
+        let mut merge = MergeDeferredDataSource::with_mode(sources, MergeMode::Aligned);
+        merge.fetch_info();
+        let info = merge.get_infos().pop().unwrap().unwrap();
+
+        let tile_id = TileID(info.interval);
+        let known = EntryID::root().child(1).child(0).child(0);
+        let unknown = EntryID::root().child(7).child(0).child(0);
+        merge.fetch_slot_tile(&known, tile_id, false);
+        merge.fetch_slot_tile(&unknown, tile_id, false);
+        let requests = [TileRequest {
+            entry_id: unknown.clone(),
+            tile_id,
+        }];
+        merge.fetch_slot_meta_tiles(&requests, false);
+
+        // Entries that don't exist fail rather than panic
+        let tiles = merge.get_slot_tiles();
+        assert_eq!(tiles.len(), 2);
+        for (tile, req) in tiles {
+            assert_eq!(tile.is_ok(), req.entry_id == known);
+        }
+        let tiles = merge.get_slot_meta_tiles();
+        assert_eq!(tiles.len(), 1);
+        assert!(matches!(tiles[0].0, Err(DataSourceError::Invalid(..))));
+        assert_eq!(tiles[0].1.entry_id, unknown);
+        assert!(merge.get_slot_meta_tiles().is_empty());
+    }
 }
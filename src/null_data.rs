@@ -0,0 +1,156 @@
+use crate::data::{
+    DataSource, DataSourceDescription, DataSourceError, DataSourceInfo, EntryID, EntryInfo, Result,
+    SlotMetaTile, SlotMetaTileData, SlotTile, SlotTileData, SummaryTile, SummaryTileData, TileID,
+    UtilPoint,
+};
+use crate::timestamp::Interval;
+
+// A data source with the given entries but no items: every slot is empty and
+// every summary shows zero utilization. The results never change, which makes
+// it a stable fixture for tests and for reproducing layout bugs, and a
+// starting point for writing a new data source.
+pub struct NullDataSource {
+    info: DataSourceInfo,
+}
+
+impl NullDataSource {
+    pub fn new(entry_info: EntryInfo, interval: Interval) -> Self {
+        Self {
+            info: DataSourceInfo::new(entry_info, interval),
+        }
+    }
+
+    // Like new, but with the rest of the info (e.g., the tile set) given too
+    pub fn with_info(info: DataSourceInfo) -> Self {
+        Self { info }
+    }
+
+    fn check_entry(&self, entry_id: &EntryID, summary: bool) -> Result<()> {
+        let found = match self.info.entry_info.get(entry_id) {
+            Some(EntryInfo::Summary { .. }) => summary,
+            Some(EntryInfo::Slot { .. }) => !summary,
+            _ => false,
+        };
+        if found {
+            Ok(())
+        } else {
+            Err(DataSourceError::NotFound(format!(
+                "no such entry {:?}",
+                entry_id
+            )))
+        }
+    }
+
+    // Empty rows, so that slots keep their height
+    fn rows(&self, entry_id: &EntryID) -> usize {
+        match self.info.entry_info.get(entry_id) {
+            Some(EntryInfo::Slot { max_rows, .. }) => *max_rows as usize,
+            _ => 0,
+        }
+    }
+}
+
+impl DataSource for NullDataSource {
+    fn fetch_description(&self) -> DataSourceDescription {
+        DataSourceDescription {
+            source_locator: vec!["null".to_owned()],
+        }
+    }
+
+    fn fetch_info(&self) -> Result<DataSourceInfo> {
+        Ok(self.info.clone())
+    }
+
+    fn fetch_summary_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SummaryTile> {
+        self.check_entry(entry_id, true)?;
+        let idle = |time| UtilPoint { time, util: 0.0 };
+        Ok(SummaryTile {
+            entry_id: entry_id.clone(),
+            tile_id,
+            data: SummaryTileData {
+                utilization: vec![idle(tile_id.0.start), idle(tile_id.0.stop)],
+                utilization_range: Vec::new(),
+            },
+        })
+    }
+
+    fn fetch_slot_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotTile> {
+        self.check_entry(entry_id, false)?;
+        Ok(SlotTile {
+            entry_id: entry_id.clone(),
+            tile_id,
+            data: SlotTileData {
+                items: vec![Vec::new(); self.rows(entry_id)],
+            },
+        })
+    }
+
+    fn fetch_slot_meta_tile(
+        &self,
+        entry_id: &EntryID,
+        tile_id: TileID,
+        _full: bool,
+    ) -> Result<SlotMetaTile> {
+        self.check_entry(entry_id, false)?;
+        Ok(SlotMetaTile {
+            entry_id: entry_id.clone(),
+            tile_id,
+            data: SlotMetaTileData {
+                items: vec![Vec::new(); self.rows(entry_id)],
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use egui::Color32;
+
+    use crate::data::EntryInfoBuilder;
+    use crate::timestamp::Timestamp;
+
+    #[test]
+    fn test_null_data_source() {
+        let mut builder = EntryInfoBuilder::new();
+        let node = builder.add_node("n0", "Node 0");
+        let kind = builder.add_kind(&node, "CPU", "CPU", Some(Color32::RED));
+        let slot = builder.add_slot(&kind, "c0", "CPU 0", 3);
+        let interval = Interval::new(Timestamp(0), Timestamp(100));
+        let source = NullDataSource::new(builder.build(), interval);
+
+        let info = source.fetch_info().unwrap();
+        info.entry_info.validate().unwrap();
+        assert_eq!(info.interval, interval);
+
+        let tile_id = TileID(interval);
+        let tile = source.fetch_slot_tile(&slot, tile_id, false).unwrap();
+        assert_eq!(tile.data.items.len(), 3);
+        assert!(tile.data.items.iter().all(|row| row.is_empty()));
+        let summary = source.fetch_summary_tile(&kind.summary(), tile_id, false);
+        assert!(summary
+            .unwrap()
+            .data
+            .utilization
+            .iter()
+            .all(|p| p.util == 0.0));
+
+        // Entries must exist, and be of the right kind
+        assert!(source.fetch_slot_tile(&kind, tile_id, false).is_err());
+        assert!(source
+            .fetch_slot_tile(&kind.child(7), tile_id, false)
+            .is_err());
+        assert!(source.fetch_summary_tile(&slot, tile_id, false).is_err());
+    }
+}
@@ -8,7 +8,9 @@ use std::ptr::{null, null_mut};
 
 use nvtxw::nvtxw;
 
-use crate::data::{DataSourceInfo, EntryID, EntryIndex, EntryInfo, SlotMetaTile, SlotTile, TileID};
+use crate::data::{
+    DataSourceInfo, EntryID, EntryIndex, EntryInfo, Result, SlotMetaTile, SlotTile, TileID,
+};
 use crate::deferred_data::{CountingDeferredDataSource, DeferredDataSource};
 
 const LEGION_DOMAIN_NAME: &str = "Legion";
@@ -118,7 +120,7 @@ impl<T: DeferredDataSource> NVTXW<T> {
         }
     }
 
-    fn check_info(&mut self) -> Option<DataSourceInfo> {
+    fn check_info(&mut self) -> Option<Result<DataSourceInfo>> {
         // We requested this once, so we know we'll get zero or one result
         self.data_source.get_infos().pop()
     }
@@ -185,19 +187,21 @@ impl<T: DeferredDataSource> NVTXW<T> {
         zero_time: i64,
         unmatched_tiles: &mut UnmatchedTileHold,
         num_requests: u64,
-    ) {
+    ) -> io::Result<()> {
         while data_source.outstanding_requests() > num_requests {
             // When implementing counters, uncomment this.
             // let summary_tiles = data_source.get_summary_tiles();
             let slot_tiles = data_source.get_slot_tiles();
             let slot_meta_tiles = data_source.get_slot_meta_tiles();
 
-            for tile in slot_tiles {
+            for (tile, _) in slot_tiles {
+                let tile = tile.map_err(io::Error::other)?;
                 let e = tile.entry_id.clone();
                 unmatched_tiles.entry(e).or_insert((None, None)).0 = Some(tile);
             }
 
-            for meta_tile in slot_meta_tiles {
+            for (meta_tile, _) in slot_meta_tiles {
+                let meta_tile = meta_tile.map_err(io::Error::other)?;
                 let e = meta_tile.entry_id.clone();
                 unmatched_tiles.entry(e).or_insert((None, None)).1 = Some(meta_tile);
             }
@@ -210,6 +214,7 @@ impl<T: DeferredDataSource> NVTXW<T> {
                 true
             });
         }
+        Ok(())
     }
 
     pub fn write(mut self) -> io::Result<()> {
@@ -218,7 +223,7 @@ impl<T: DeferredDataSource> NVTXW<T> {
         while info.is_none() {
             info = self.check_info();
         }
-        let info = info.unwrap();
+        let info = info.unwrap().map_err(io::Error::other)?;
 
         let entry_ids = walk_entry_list(&info.entry_info);
 
@@ -384,7 +389,7 @@ impl<T: DeferredDataSource> NVTXW<T> {
                 zero_time,
                 &mut unmatched_tiles,
                 MAX_IN_FLIGHT_REQUESTS,
-            );
+            )?;
         }
 
         Self::process_events(
@@ -394,7 +399,7 @@ impl<T: DeferredDataSource> NVTXW<T> {
             zero_time,
             &mut unmatched_tiles,
             0,
-        );
+        )?;
 
         assert!(unmatched_tiles.is_empty());
 
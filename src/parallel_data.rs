@@ -1,29 +1,52 @@
 use std::sync::{Arc, Mutex};
 
+use rayon::ThreadPool;
+
 use crate::data::{
-    DataSource, DataSourceDescription, DataSourceInfo, EntryID, SlotMetaTile, SlotTile,
+    DataSource, DataSourceDescription, DataSourceInfo, EntryID, Result, SlotMetaTile, SlotTile,
     SummaryTile, TileID,
 };
 use crate::deferred_data::DeferredDataSource;
+use crate::http::schema::TileRequest;
+
+type TileResults<T> = Arc<Mutex<Vec<(Result<T>, TileRequest)>>>;
 
 pub struct ParallelDeferredDataSource<T: DataSource + Send + Sync + 'static> {
     data_source: Arc<T>,
-    infos: Arc<Mutex<Vec<DataSourceInfo>>>,
-    summary_tiles: Arc<Mutex<Vec<SummaryTile>>>,
-    slot_tiles: Arc<Mutex<Vec<SlotTile>>>,
-    slot_meta_tiles: Arc<Mutex<Vec<SlotMetaTile>>>,
+    // Runs on the global rayon pool unless given a dedicated one
+    thread_pool: Option<Arc<ThreadPool>>,
+    infos: Arc<Mutex<Vec<Result<DataSourceInfo>>>>,
+    summary_tiles: TileResults<SummaryTile>,
+    slot_tiles: TileResults<SlotTile>,
+    slot_meta_tiles: TileResults<SlotMetaTile>,
 }
 
 impl<T: DataSource + Send + Sync + 'static> ParallelDeferredDataSource<T> {
     pub fn new(data_source: T) -> Self {
+        Self::new_with_thread_pool(data_source, None)
+    }
+
+    pub fn with_thread_pool(data_source: T, thread_pool: Arc<ThreadPool>) -> Self {
+        Self::new_with_thread_pool(data_source, Some(thread_pool))
+    }
+
+    fn new_with_thread_pool(data_source: T, thread_pool: Option<Arc<ThreadPool>>) -> Self {
         Self {
             data_source: Arc::new(data_source),
+            thread_pool,
             infos: Arc::new(Mutex::new(Vec::new())),
             summary_tiles: Arc::new(Mutex::new(Vec::new())),
             slot_tiles: Arc::new(Mutex::new(Vec::new())),
             slot_meta_tiles: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    fn spawn(&self, task: impl FnOnce() + Send + 'static) {
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.spawn(task),
+            None => rayon::spawn(task),
+        }
+    }
 }
 
 impl<T: DataSource + Send + Sync + 'static> DeferredDataSource for ParallelDeferredDataSource<T> {
@@ -34,13 +57,13 @@ impl<T: DataSource + Send + Sync + 'static> DeferredDataSource for ParallelDefer
     fn fetch_info(&mut self) {
         let data_source = self.data_source.clone();
         let infos = self.infos.clone();
-        rayon::spawn(move || {
+        self.spawn(move || {
             let result = data_source.fetch_info();
             infos.lock().unwrap().push(result);
         });
     }
 
-    fn get_infos(&mut self) -> Vec<DataSourceInfo> {
+    fn get_infos(&mut self) -> Vec<Result<DataSourceInfo>> {
         std::mem::take(&mut self.infos.lock().unwrap())
     }
 
@@ -48,13 +71,14 @@ impl<T: DataSource + Send + Sync + 'static> DeferredDataSource for ParallelDefer
         let entry_id = entry_id.clone();
         let data_source = self.data_source.clone();
         let summary_tiles = self.summary_tiles.clone();
-        rayon::spawn(move || {
+        self.spawn(move || {
             let result = data_source.fetch_summary_tile(&entry_id, tile_id, full);
-            summary_tiles.lock().unwrap().push(result);
+            let req = TileRequest { entry_id, tile_id };
+            summary_tiles.lock().unwrap().push((result, req));
         });
     }
 
-    fn get_summary_tiles(&mut self) -> Vec<SummaryTile> {
+    fn get_summary_tiles(&mut self) -> Vec<(Result<SummaryTile>, TileRequest)> {
         std::mem::take(&mut self.summary_tiles.lock().unwrap())
     }
 
@@ -62,13 +86,14 @@ impl<T: DataSource + Send + Sync + 'static> DeferredDataSource for ParallelDefer
         let entry_id = entry_id.clone();
         let data_source = self.data_source.clone();
         let slot_tiles = self.slot_tiles.clone();
-        rayon::spawn(move || {
+        self.spawn(move || {
             let result = data_source.fetch_slot_tile(&entry_id, tile_id, full);
-            slot_tiles.lock().unwrap().push(result);
+            let req = TileRequest { entry_id, tile_id };
+            slot_tiles.lock().unwrap().push((result, req));
         });
     }
 
-    fn get_slot_tiles(&mut self) -> Vec<SlotTile> {
+    fn get_slot_tiles(&mut self) -> Vec<(Result<SlotTile>, TileRequest)> {
         std::mem::take(&mut self.slot_tiles.lock().unwrap())
     }
 
@@ -76,13 +101,14 @@ impl<T: DataSource + Send + Sync + 'static> DeferredDataSource for ParallelDefer
         let entry_id = entry_id.clone();
         let data_source = self.data_source.clone();
         let slot_meta_tiles = self.slot_meta_tiles.clone();
-        rayon::spawn(move || {
+        self.spawn(move || {
             let result = data_source.fetch_slot_meta_tile(&entry_id, tile_id, full);
-            slot_meta_tiles.lock().unwrap().push(result);
+            let req = TileRequest { entry_id, tile_id };
+            slot_meta_tiles.lock().unwrap().push((result, req));
         });
     }
 
-    fn get_slot_meta_tiles(&mut self) -> Vec<SlotMetaTile> {
+    fn get_slot_meta_tiles(&mut self) -> Vec<(Result<SlotMetaTile>, TileRequest)> {
         std::mem::take(&mut self.slot_meta_tiles.lock().unwrap())
     }
 }
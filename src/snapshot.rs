@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use crate::data::{Color32, EntryID, EntryInfo, Result, UtilPoint};
+use crate::deferred_data::{CountingDeferredDataSource, DeferredDataSource};
+use crate::timestamp::Interval;
+
+// A non-interactive rendering of a profile's utilization, suitable for
+// thumbnails. Pixels are stored row-major, top row first, as unmultiplied
+// RGBA (ready to be encoded as PNG).
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+// One horizontal band of the snapshot: the average of all summaries of a
+// given kind (e.g., CPU, GPU) across nodes.
+struct Band {
+    color: Color32,
+    entry_ids: Vec<EntryID>,
+}
+
+fn walk_summaries(info: &EntryInfo) -> Vec<Band> {
+    fn walk(
+        info: &EntryInfo,
+        entry_id: EntryID,
+        name: &str,
+        bands: &mut Vec<Band>,
+        index: &mut BTreeMap<String, usize>,
+    ) {
+        match info {
+            EntryInfo::Panel {
+                summary,
+                slots,
+                short_name,
+                ..
+            } => {
+                if let Some(summary) = summary {
+                    walk(summary, entry_id.summary(), short_name, bands, index);
+                }
+                for (i, slot) in slots.iter().enumerate() {
+                    walk(slot, entry_id.child(i as u64), short_name, bands, index);
+                }
+            }
+            EntryInfo::Summary { color } => {
+                let i = *index.entry(name.to_owned()).or_insert_with(|| {
+                    bands.push(Band {
+                        color: *color,
+                        entry_ids: Vec::new(),
+                    });
+                    bands.len() - 1
+                });
+                bands[i].entry_ids.push(entry_id);
+            }
+            EntryInfo::Slot { .. } => {}
+        }
+    }
+    let mut bands = Vec::new();
+    walk(info, EntryID::root(), "", &mut bands, &mut BTreeMap::new());
+    bands
+}
+
+// Renders the utilization of the profile over the given interval into an
+// image of the given size. This blocks until all the required tiles have
+// been loaded.
+pub fn render_snapshot(
+    data_source: impl DeferredDataSource,
+    interval: Interval,
+    width: usize,
+    height: usize,
+) -> Result<Snapshot> {
+    let mut data_source = CountingDeferredDataSource::new(data_source);
+
+    data_source.fetch_info();
+    let info = loop {
+        // We requested this once, so we know we'll get zero or one result
+        if let Some(info) = data_source.get_infos().pop() {
+            break info?;
+        }
+    };
+
+    let interval = interval.intersection(info.interval);
+    let bands = walk_summaries(&info.entry_info);
+
+    let full = false;
+    for tile_id in info.tile_set.covering_tiles(interval) {
+        for band in &bands {
+            for entry_id in &band.entry_ids {
+                data_source.fetch_summary_tile(entry_id, tile_id, full);
+            }
+        }
+    }
+
+    let mut utilization: BTreeMap<EntryID, Vec<UtilPoint>> = BTreeMap::new();
+    while data_source.outstanding_requests() > 0 {
+        for (tile, _) in data_source.get_summary_tiles() {
+            let tile = tile?;
+            utilization
+                .entry(tile.entry_id)
+                .or_default()
+                .extend(tile.data.utilization);
+        }
+    }
+    for points in utilization.values_mut() {
+        points.sort_by_key(|p| p.time);
+    }
+
+    let mut rgba = vec![0; width * height * 4];
+    if bands.is_empty() || width == 0 {
+        return Ok(Snapshot {
+            width,
+            height,
+            rgba,
+        });
+    }
+
+    for (i, band) in bands.iter().enumerate() {
+        let band_top = height * i / bands.len();
+        let band_bottom = height * (i + 1) / bands.len();
+        let band_height = (band_bottom - band_top) as f32;
+        let color = band.color.to_srgba_unmultiplied();
+
+        for x in 0..width {
+            // Sample at the center of each column
+            let time = interval.lerp((x as f32 + 0.5) / width as f32);
+            let samples: Vec<_> = band
+                .entry_ids
+                .iter()
+                .filter_map(|entry_id| UtilPoint::interpolate(utilization.get(entry_id)?, time))
+                .collect();
+            if samples.is_empty() {
+                continue;
+            }
+            let util = samples.iter().sum::<f32>() / samples.len() as f32;
+
+            // Fill from the bottom of the band up
+            let filled = (util.clamp(0.0, 1.0) * band_height).round() as usize;
+            for y in band_bottom - filled..band_bottom {
+                let offset = (y * width + x) * 4;
+                rgba[offset..offset + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    Ok(Snapshot {
+        width,
+        height,
+        rgba,
+    })
+}
@@ -0,0 +1,148 @@
+use std::fmt::Write;
+
+use egui::{Color32, Pos2, Rect};
+
+// Accumulates shapes into an SVG document. Coordinates are in points, the
+// same as on screen, so the timeline can be traversed exactly as it's drawn.
+#[derive(Default)]
+pub struct SvgWriter {
+    body: String,
+    clips: usize,
+}
+
+impl SvgWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rect(&mut self, rect: Rect, fill: Color32) {
+        writeln!(
+            self.body,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" {}/>"#,
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height(),
+            paint("fill", fill)
+        )
+        .unwrap();
+    }
+
+    pub fn polyline(&mut self, points: &[Pos2], stroke: Color32, width: f32) {
+        if points.len() < 2 {
+            return;
+        }
+        let points = points
+            .iter()
+            .map(|p| format!("{:.2},{:.2}", p.x, p.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            self.body,
+            r#"<polyline points="{}" fill="none" stroke-width="{}" {}/>"#,
+            points,
+            width,
+            paint("stroke", stroke)
+        )
+        .unwrap();
+    }
+
+    // The position is the left end of the text's baseline
+    pub fn text(&mut self, pos: Pos2, text: &str, size: f32, fill: Color32) {
+        writeln!(
+            self.body,
+            r#"<text x="{:.2}" y="{:.2}" font-family="sans-serif" font-size="{}" {}>{}</text>"#,
+            pos.x,
+            pos.y,
+            size,
+            paint("fill", fill),
+            escape(text)
+        )
+        .unwrap();
+    }
+
+    // Shapes up to the matching end_clip are cut off at the rect
+    pub fn begin_clip(&mut self, rect: Rect) {
+        let id = self.clips;
+        self.clips += 1;
+        writeln!(
+            self.body,
+            r#"<clipPath id="clip{}"><rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}"/></clipPath>"#,
+            id,
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height()
+        )
+        .unwrap();
+        writeln!(self.body, r#"<g clip-path="url(#clip{})">"#, id).unwrap();
+    }
+
+    pub fn end_clip(&mut self) {
+        self.body.push_str("</g>\n");
+    }
+
+    pub fn finish(self, width: f32, height: f32) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\">\n{}</svg>\n",
+            self.body,
+            w = width.ceil(),
+            h = height.ceil(),
+        )
+    }
+}
+
+// SVG has no premultiplied colors, so the alpha goes in a separate attribute
+fn paint(attr: &str, color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let mut result = format!(r#"{}="rgb({},{},{})""#, attr, r, g, b);
+    if a < 255 {
+        write!(result, r#" {}-opacity="{:.3}""#, attr, a as f32 / 255.0).unwrap();
+    }
+    result
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_writer() {
+        let mut svg = SvgWriter::new();
+        let rect = Rect::from_min_max(Pos2::new(1.0, 2.0), Pos2::new(4.0, 6.0));
+        svg.begin_clip(rect);
+        svg.rect(rect, Color32::RED);
+        svg.polyline(&[Pos2::new(0.0, 0.0)], Color32::BLUE, 1.0);
+        svg.end_clip();
+        svg.text(Pos2::new(0.0, 10.0), "a<b & c", 12.0, Color32::TRANSPARENT);
+        let output = svg.finish(9.5, 10.0);
+
+        assert!(output.starts_with("<svg "));
+        assert!(output.contains(r#"width="10" height="10""#));
+        assert!(output.contains(
+            r#"<rect x="1.00" y="2.00" width="3.00" height="4.00" fill="rgb(255,0,0)"/>"#
+        ));
+        // A single point isn't a line
+        assert!(!output.contains("<polyline"));
+        assert!(output.contains(r#"fill-opacity="0.000">a&lt;b &amp; c</text>"#));
+        assert_eq!(
+            output.matches("<g ").count(),
+            output.matches("</g>").count()
+        );
+    }
+}
@@ -12,29 +12,166 @@ pub enum TimestampParseError {
     InvalidUnit,
 }
 
+impl fmt::Display for TimestampParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampParseError::InvalidValue => {
+                write!(f, "invalid value (expected a number like 1.5 or -20)")
+            }
+            TimestampParseError::NoUnit => write!(f, "no unit (expected ns, us, ms or s)"),
+            TimestampParseError::InvalidUnit => {
+                write!(f, "invalid unit (expected ns, us, ms or s)")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimestampParseError {}
+
 impl Timestamp {
+    // Parses a time of the form `<value> <unit>`, e.g. `1.5 ms` or `-20us`.
+    // The value is a decimal number (digits after the separator may be
+    // grouped with spaces, as in `1.234 567 s`) and the unit is one of `ns`,
+    // `us`, `ms` or `s` (case insensitive). Whitespace around the value and
+    // unit is ignored. Digits below 1 ns are truncated.
     pub fn parse(s: &str) -> Result<Timestamp, TimestampParseError> {
         let s = s.trim();
         let split_idx = s
-            .find(|c| !(char::is_ascii_digit(&c) || c == '.'))
+            .find(|c| !(char::is_ascii_digit(&c) || c == '.' || c == '-' || c == ' '))
             .ok_or(TimestampParseError::NoUnit)?;
 
         let (value_s, unit_s) = s.split_at(split_idx);
-        let value = value_s
-            .parse::<f64>()
-            .map_err(|_| TimestampParseError::InvalidValue)?;
         let unit = unit_s.trim().to_lowercase();
 
         let factor = match unit.as_str() {
             "ns" => 1,
-            "us" => 1_000,
+            "us" | "µs" => 1_000,
             "ms" => 1_000_000,
             "s" => 1_000_000_000,
+            _ if value_s.trim().is_empty() => return Err(TimestampParseError::InvalidValue),
             _ => return Err(TimestampParseError::InvalidUnit),
         };
 
-        Ok(Timestamp((value * factor as f64) as i64))
+        Self::parse_value(value_s.trim(), factor)
+            .map(Timestamp)
+            .ok_or(TimestampParseError::InvalidValue)
     }
+
+    // Exact decimal parsing (going through floating point would turn e.g.
+    // 4.35 ms into 4349999 ns)
+    fn parse_value(s: &str, factor: i64) -> Option<i64> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let fraction = fraction.replace(' ', "");
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(&fraction) {
+            return None;
+        }
+
+        let mut value = if whole.is_empty() {
+            0
+        } else {
+            whole.parse::<i64>().ok()?.checked_mul(factor)?
+        };
+        let mut scale = factor;
+        for digit in fraction.bytes() {
+            scale /= 10;
+            value = value.checked_add((digit - b'0') as i64 * scale)?;
+        }
+        Some(if negative { -value } else { value })
+    }
+
+    // Parses a UTC wall-clock time of the form `YYYY-MM-DD HH:MM:SS[.fff]`
+    // (optionally followed by `UTC` or `Z`) and returns it relative to
+    // `epoch`, the wall-clock time at which the profile starts.
+    pub fn parse_absolute(s: &str, epoch: Timestamp) -> Result<Timestamp, TimestampParseError> {
+        let s = s.trim();
+        let s = s
+            .strip_suffix("UTC")
+            .or_else(|| s.strip_suffix('Z'))
+            .unwrap_or(s)
+            .trim_end();
+        let (date, time) = s
+            .split_once([' ', 'T'])
+            .ok_or(TimestampParseError::InvalidValue)?;
+
+        fn fields<const N: usize>(s: &str, sep: char) -> Option<[i64; N]> {
+            let mut result = [0; N];
+            let mut parts = s.split(sep);
+            for value in result.iter_mut() {
+                let part = parts.next()?;
+                if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                *value = part.parse().ok()?;
+            }
+            parts.next().is_none().then_some(result)
+        }
+
+        let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+        let [year, month, day] = fields(date, '-').ok_or(TimestampParseError::InvalidValue)?;
+        let [hour, minute, second] = fields(time, ':').ok_or(TimestampParseError::InvalidValue)?;
+        if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(TimestampParseError::InvalidValue);
+        }
+        let nanos = format!("{fraction:0<9}").parse::<i64>().unwrap();
+        if year > 9999
+            || !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+            || hour >= 24
+            || minute >= 60
+            || second >= 60
+        {
+            return Err(TimestampParseError::InvalidValue);
+        }
+
+        // Only about 292 years on either side of the epoch fit in a Timestamp
+        let seconds =
+            days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second;
+        seconds
+            .checked_mul(1_000_000_000)
+            .and_then(|ns| ns.checked_add(nanos))
+            .and_then(|ns| ns.checked_sub(epoch.0))
+            .map(Timestamp)
+            .ok_or(TimestampParseError::InvalidValue)
+    }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar. See
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Inverse of days_from_civil: (year, month, day)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
 }
 
 impl fmt::Display for Timestamp {
@@ -46,12 +183,36 @@ impl fmt::Display for Timestamp {
             TimestampDisplay {
                 timestamp: *self,
                 units,
-                include_units: true
+                include_units: true,
+                epoch: None,
             }
         )
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IntervalParseError {
+    NoSeparator,
+    InvalidStart(TimestampParseError),
+    InvalidStop(TimestampParseError),
+    StartAfterStop,
+}
+
+impl fmt::Display for IntervalParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalParseError::NoSeparator => {
+                write!(f, "no separator (expected <start>..<stop>)")
+            }
+            IntervalParseError::InvalidStart(e) => write!(f, "start: {}", e),
+            IntervalParseError::InvalidStop(e) => write!(f, "stop: {}", e),
+            IntervalParseError::StartAfterStop => write!(f, "start is after stop"),
+        }
+    }
+}
+
+impl std::error::Error for IntervalParseError {}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize, Serialize)]
 pub struct Interval {
     pub start: Timestamp,
@@ -60,26 +221,59 @@ pub struct Interval {
 
 impl fmt::Display for Interval {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let units: TimestampUnits = (*self).into();
-        let duration = Timestamp(self.duration_ns());
-        let duration_units: TimestampUnits = duration.into();
+        write!(
+            f,
+            "{}",
+            IntervalDisplay {
+                interval: *self,
+                epoch: None,
+                unit: DisplayUnit::Auto,
+            }
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct IntervalDisplay {
+    pub interval: Interval,
+    pub epoch: Option<Timestamp>,
+    pub unit: DisplayUnit,
+}
+
+impl fmt::Display for IntervalDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            interval,
+            epoch,
+            unit,
+        } = *self;
+        let units = unit.interval_units(interval);
+        let duration = Timestamp(interval.duration_ns());
+        let duration_units = match unit {
+            DisplayUnit::Auto => duration.into(),
+            // Show enough digits to make short durations visible
+            _ => unit.interval_units(Interval::new(Timestamp(0), duration)),
+        };
         write!(
             f,
             "from {} to {} (duration: {})",
             TimestampDisplay {
-                timestamp: self.start,
+                timestamp: interval.start,
                 units,
-                include_units: false
+                include_units: false,
+                epoch,
             },
             TimestampDisplay {
-                timestamp: self.stop,
+                timestamp: interval.stop,
                 units,
-                include_units: true
+                include_units: true,
+                epoch,
             },
             TimestampDisplay {
                 timestamp: duration,
                 units: duration_units,
-                include_units: true
+                include_units: true,
+                epoch: None,
             }
         )
     }
@@ -89,6 +283,17 @@ impl Interval {
     pub fn new(start: Timestamp, stop: Timestamp) -> Self {
         Self { start, stop }
     }
+    // Parses an interval of the form `<start>..<stop>`, where each end is
+    // a time as accepted by `Timestamp::parse`, e.g. `1.5 ms..3 ms`.
+    pub fn parse(s: &str) -> Result<Interval, IntervalParseError> {
+        let (start, stop) = s.split_once("..").ok_or(IntervalParseError::NoSeparator)?;
+        let start = Timestamp::parse(start).map_err(IntervalParseError::InvalidStart)?;
+        let stop = Timestamp::parse(stop).map_err(IntervalParseError::InvalidStop)?;
+        if start > stop {
+            return Err(IntervalParseError::StartAfterStop);
+        }
+        Ok(Interval::new(start, stop))
+    }
     pub fn center(self) -> Timestamp {
         Timestamp(self.start.0 + self.duration_ns() / 2)
     }
@@ -98,6 +303,9 @@ impl Interval {
     pub fn contains(self, point: Timestamp) -> bool {
         point >= self.start && point < self.stop
     }
+    pub fn contains_interval(self, other: Interval) -> bool {
+        other.start >= self.start && other.stop <= self.stop
+    }
     pub fn overlaps(self, other: Interval) -> bool {
         !(other.stop <= self.start || other.start >= self.stop)
     }
@@ -113,9 +321,14 @@ impl Interval {
             stop: Timestamp(self.stop.0.max(other.stop.0)),
         }
     }
-    // Convert a timestamp into [0,1] relative space
+    // Convert a timestamp into [0,1] relative space. Empty intervals have no
+    // inside, so times map to 0 up to the start and 1 after it (never NaN).
     pub fn unlerp(self, time: Timestamp) -> f32 {
-        (time.0 - self.start.0) as f32 / (self.duration_ns() as f32)
+        let duration = self.duration_ns();
+        if duration <= 0 {
+            return if time > self.start { 1.0 } else { 0.0 };
+        }
+        (time.0 - self.start.0) as f32 / (duration as f32)
     }
     // Convert [0,1] relative space into a timestamp
     pub fn lerp(self, value: f32) -> Timestamp {
@@ -227,15 +440,91 @@ impl From<Interval> for TimestampUnits {
     }
 }
 
+impl TimestampUnits {
+    // Units with a fixed unit name, but as many digits after the separator
+    // as needed to distinguish times within the given duration (like the
+    // automatic units chosen for an interval)
+    fn fixed(divisor: i64, unit_name: &'static str, duration: i64) -> TimestampUnits {
+        let max_digits = divisor.ilog10() as i64;
+        let mut digits_after_separator = max_digits.min(3);
+        while digits_after_separator < max_digits
+            && duration < divisor / 10_i64.pow(digits_after_separator as u32)
+        {
+            digits_after_separator += 3;
+        }
+        TimestampUnits {
+            divisor,
+            digits_after_separator,
+            unit_name,
+        }
+    }
+}
+
+// The unit to display times in. `Auto` picks a unit based on the magnitude
+// of the time, so it changes when zooming.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum DisplayUnit {
+    #[default]
+    Auto,
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+}
+
+impl DisplayUnit {
+    fn fixed(self, duration: i64) -> Option<TimestampUnits> {
+        match self {
+            DisplayUnit::Auto => None,
+            DisplayUnit::Nanoseconds => Some(TimestampUnits::fixed(1, "ns", duration)),
+            DisplayUnit::Microseconds => Some(TimestampUnits::fixed(1_000, "us", duration)),
+            DisplayUnit::Milliseconds => Some(TimestampUnits::fixed(1_000_000, "ms", duration)),
+            DisplayUnit::Seconds => Some(TimestampUnits::fixed(1_000_000_000, "s", duration)),
+        }
+    }
+
+    pub fn timestamp_units(self, timestamp: Timestamp) -> TimestampUnits {
+        // Without an interval, there is no duration to resolve, so use the
+        // same precision as Auto
+        self.fixed(i64::MAX).unwrap_or_else(|| timestamp.into())
+    }
+
+    pub fn interval_units(self, interval: Interval) -> TimestampUnits {
+        self.fixed(interval.duration_ns())
+            .unwrap_or_else(|| interval.into())
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct TimestampDisplay {
     pub timestamp: Timestamp,
     pub units: TimestampUnits,
     pub include_units: bool,
+    // If set, display as a UTC wall-clock time, where epoch is the wall-clock
+    // time (in ns since 1970-01-01) at which the profile starts. The units
+    // are ignored in this case.
+    pub epoch: Option<Timestamp>,
 }
 
 impl fmt::Display for TimestampDisplay {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(epoch) = self.epoch {
+            let ns = epoch.0 + self.timestamp.0;
+            let seconds = ns.div_euclid(1_000_000_000);
+            let nanos = ns.rem_euclid(1_000_000_000);
+            let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+            let time = seconds.rem_euclid(86_400);
+            let (hour, minute, second) = (time / 3_600, time / 60 % 60, time % 60);
+            write!(
+                f,
+                "{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}.{nanos:09}"
+            )?;
+            if self.include_units {
+                write!(f, " UTC")?;
+            }
+            return Ok(());
+        }
+
         let TimestampUnits {
             divisor,
             unit_name,
@@ -412,6 +701,76 @@ mod tests {
                 Err(TimestampParseError::InvalidUnit)
             );
         }
+
+        #[test]
+        fn test_exact() {
+            assert_eq!(Timestamp::parse("4.35 ms"), Ok(Timestamp(4_350_000)));
+            assert_eq!(Timestamp::parse("1.5ms"), Timestamp::parse("1500us"));
+            assert_eq!(Timestamp::parse(".5 us"), Ok(Timestamp(500)));
+            assert_eq!(Timestamp::parse("7. s"), Ok(Timestamp(7_000_000_000)));
+        }
+
+        #[test]
+        fn test_truncate() {
+            assert_eq!(Timestamp::parse("1.2345 us"), Ok(Timestamp(1_234)));
+            assert_eq!(Timestamp::parse("0.5 ns"), Ok(Timestamp(0)));
+        }
+
+        #[test]
+        fn test_negative() {
+            assert_eq!(Timestamp::parse("-1.5 us"), Ok(Timestamp(-1_500)));
+            assert_eq!(
+                Timestamp::parse("1-5 us"),
+                Err(TimestampParseError::InvalidValue)
+            );
+        }
+
+        #[test]
+        fn test_digit_groups() {
+            assert_eq!(Timestamp::parse("0.000 005 s"), Ok(Timestamp(5_000)));
+            assert_eq!(
+                Timestamp::parse("1 000 ns"),
+                Err(TimestampParseError::InvalidValue)
+            );
+        }
+
+        #[test]
+        fn test_overflow() {
+            assert_eq!(
+                Timestamp::parse("100000000000 s"),
+                Err(TimestampParseError::InvalidValue)
+            );
+        }
+
+        #[test]
+        fn test_round_trip() {
+            for ns in [
+                0,
+                -5,
+                999,
+                1_000,
+                123_456,
+                1_500_000,
+                999_999_000,
+                123_456_000_000,
+            ] {
+                let t = Timestamp(ns);
+                assert_eq!(Timestamp::parse(&t.to_string()), Ok(t));
+            }
+        }
+
+        #[test]
+        fn test_round_trip_interval_units() {
+            let interval = Interval::new(Timestamp(1_000_000_000), Timestamp(1_000_000_005));
+            let t = Timestamp(1_000_000_005);
+            let display = TimestampDisplay {
+                timestamp: t,
+                units: interval.into(),
+                include_units: true,
+                epoch: None,
+            };
+            assert_eq!(Timestamp::parse(&display.to_string()), Ok(t));
+        }
     }
 
     mod interval {
@@ -496,6 +855,17 @@ mod tests {
             assert_eq!(i1.grow(2), Interval::new(Timestamp(18), Timestamp(22)));
         }
 
+        #[test]
+        fn test_lerp_empty() {
+            let empty = Interval::new(Timestamp(10), Timestamp(10));
+            assert_eq!(empty.unlerp(Timestamp(5)), 0.0);
+            assert_eq!(empty.unlerp(Timestamp(10)), 0.0);
+            assert_eq!(empty.unlerp(Timestamp(15)), 1.0);
+            assert_eq!(empty.lerp(0.5), Timestamp(10));
+            let reversed = Interval::new(Timestamp(10), Timestamp(5));
+            assert!(!reversed.unlerp(Timestamp(7)).is_nan());
+        }
+
         #[test]
         fn test_translate() {
             let origin = Interval::new(Timestamp(1000), Timestamp(2000));
@@ -505,6 +875,32 @@ mod tests {
             let expect = Interval::new(Timestamp(750), Timestamp(1750));
             assert_eq!(origin.translate(-250), expect);
         }
+
+        #[test]
+        fn test_parse() {
+            assert_eq!(
+                Interval::parse("1.5 ms..3ms"),
+                Ok(Interval::new(Timestamp(1_500_000), Timestamp(3_000_000)))
+            );
+            assert_eq!(
+                Interval::parse("-1 us .. 2 s"),
+                Ok(Interval::new(Timestamp(-1_000), Timestamp(2_000_000_000)))
+            );
+            assert_eq!(
+                Interval::parse("1 ms"),
+                Err(IntervalParseError::NoSeparator)
+            );
+            assert_eq!(
+                Interval::parse("1..2 ms"),
+                Err(IntervalParseError::InvalidStart(
+                    TimestampParseError::NoUnit
+                ))
+            );
+            assert_eq!(
+                Interval::parse("2 ms..1 ms"),
+                Err(IntervalParseError::StartAfterStop)
+            );
+        }
     }
 
     mod timestamp_units_from_timestamp {
@@ -802,4 +1198,161 @@ mod tests {
             assert_eq!(&format!("{}", i0), "from 0 to 123 ns (duration: 123 ns)");
         }
     }
+    mod display_unit {
+        use super::*;
+
+        #[test]
+        fn test_pinned_interval() {
+            let i0 = Interval::new(Timestamp(1_000), Timestamp(2_000_000_000));
+            let display = |unit| {
+                format!(
+                    "{}",
+                    IntervalDisplay {
+                        interval: i0,
+                        epoch: None,
+                        unit,
+                    }
+                )
+            };
+            assert_eq!(
+                display(DisplayUnit::Milliseconds),
+                "from 0.001 to 2000.000 ms (duration: 1999.999 ms)"
+            );
+            assert_eq!(
+                display(DisplayUnit::Nanoseconds),
+                "from 1000 to 2000000000 ns (duration: 1999999000 ns)"
+            );
+        }
+
+        #[test]
+        fn test_pinned_short_duration() {
+            let i0 = Interval::new(Timestamp(0), Timestamp(5_000));
+            assert_eq!(
+                format!(
+                    "{}",
+                    IntervalDisplay {
+                        interval: i0,
+                        epoch: None,
+                        unit: DisplayUnit::Seconds,
+                    }
+                ),
+                "from 0.000 000 to 0.000 005 s (duration: 0.000 005 s)"
+            );
+        }
+    }
+
+    mod absolute {
+        use super::*;
+
+        fn display(epoch: i64, t: i64) -> String {
+            let t = Timestamp(t);
+            format!(
+                "{}",
+                TimestampDisplay {
+                    timestamp: t,
+                    units: t.into(),
+                    include_units: true,
+                    epoch: Some(Timestamp(epoch)),
+                }
+            )
+        }
+
+        #[test]
+        fn test_display() {
+            assert_eq!(display(0, 0), "1970-01-01 00:00:00.000000000 UTC");
+            assert_eq!(
+                display(1_700_000_000_000_000_000, 123_456_789),
+                "2023-11-14 22:13:20.123456789 UTC"
+            );
+            assert_eq!(
+                display(951_782_400_000_000_000, -1),
+                "2000-02-28 23:59:59.999999999 UTC"
+            );
+        }
+
+        #[test]
+        fn test_parse() {
+            let epoch = Timestamp(1_700_000_000_000_000_000);
+            assert_eq!(
+                Timestamp::parse_absolute("2023-11-14 22:13:20.5 UTC", epoch),
+                Ok(Timestamp(500_000_000))
+            );
+            assert_eq!(
+                Timestamp::parse_absolute("2023-11-14T22:13:19Z", epoch),
+                Ok(Timestamp(-1_000_000_000))
+            );
+            assert_eq!(
+                Timestamp::parse_absolute("2023-11-14", epoch),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert_eq!(
+                Timestamp::parse_absolute("2023-13-14 22:13:20", epoch),
+                Err(TimestampParseError::InvalidValue)
+            );
+        }
+
+        #[test]
+        fn test_parse_day_of_month() {
+            let epoch = Timestamp(0);
+            let parse = |s| Timestamp::parse_absolute(s, epoch);
+            assert_eq!(
+                parse("2023-02-31 00:00:00"),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert_eq!(
+                parse("2023-04-31 00:00:00"),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert_eq!(
+                parse("2023-02-29 00:00:00"),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert_eq!(
+                parse("1900-02-29 00:00:00"),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert!(parse("2024-02-29 00:00:00").is_ok());
+            assert!(parse("2000-02-29 00:00:00").is_ok());
+            assert!(parse("2023-12-31 00:00:00").is_ok());
+        }
+
+        #[test]
+        fn test_parse_overflow() {
+            let parse = |s, epoch| Timestamp::parse_absolute(s, Timestamp(epoch));
+            // Representable as seconds, but not as nanoseconds
+            assert_eq!(
+                parse("2500-01-01 00:00:00", 0),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert_eq!(
+                parse("1000-01-01 00:00:00", 0),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert_eq!(
+                parse("99999-01-01 00:00:00", 0),
+                Err(TimestampParseError::InvalidValue)
+            );
+            // In range, but too far from the epoch
+            assert_eq!(
+                parse("2200-01-01 00:00:00", -5_000_000_000_000_000_000),
+                Err(TimestampParseError::InvalidValue)
+            );
+            assert!(parse("2200-01-01 00:00:00", 0).is_ok());
+        }
+
+        #[test]
+        fn test_round_trip() {
+            let epoch = Timestamp(1_234_567_890_123_456_789);
+            for t in [
+                0,
+                1,
+                999_999_999,
+                86_400_000_000_000,
+                40_000_000_000_000_007,
+            ] {
+                let s = display(epoch.0, t);
+                assert_eq!(Timestamp::parse_absolute(&s, epoch), Ok(Timestamp(t)));
+            }
+        }
+    }
 }